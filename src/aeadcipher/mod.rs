@@ -51,6 +51,7 @@ pub use crate::blockmode::{
     Aes256OcbTag64, Aes256OcbTag96, Aes256OcbTag128,
 
     AesSivCmac256, AesSivCmac384, AesSivCmac512,
+    AesSivPmac256, AesSivPmac384, AesSivPmac512,
     
     Aria128Ccm, Aria256Ccm, 
     Aria128Gcm, Aria256Gcm, 
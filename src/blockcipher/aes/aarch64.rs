@@ -9,8 +9,8 @@ use super::generic;
 // Emulating x86 AES Intrinsics on ARMv8-A
 // https://blog.michaelbrase.com/2018/05/08/emulating-x86-aes-intrinsics-on-armv8-a/
 
-#[inline]
-fn encrypt_aarch64(expanded_key: &[u8], nr: isize, plaintext: &mut [u8]) {
+#[target_feature(enable = "neon,aes")]
+unsafe fn encrypt_aarch64(expanded_key: &[u8], nr: isize, plaintext: &mut [u8]) {
     debug_assert_eq!(plaintext.len(), 16);
 
     unsafe {
@@ -30,8 +30,8 @@ fn encrypt_aarch64(expanded_key: &[u8], nr: isize, plaintext: &mut [u8]) {
     }
 }
 
-#[inline]
-fn decrypt_aarch64(expanded_key: &[u8], nr: isize, ciphertext: &mut [u8]) {
+#[target_feature(enable = "neon,aes")]
+unsafe fn decrypt_aarch64(expanded_key: &[u8], nr: isize, ciphertext: &mut [u8]) {
     debug_assert_eq!(ciphertext.len(), 16);
     unsafe {
         let mut state: uint8x16_t = vld1q_u8(ciphertext.as_ptr());
@@ -90,16 +90,22 @@ impl Aes128 {
         Self { ek }
     }
     
-    pub fn encrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn encrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
         
-        encrypt_aarch64(&self.ek, Self::NR as isize, block);
+        unsafe {
+            encrypt_aarch64(&self.ek, Self::NR as isize, block);
+        }
     }
 
-    pub fn decrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn decrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
-        decrypt_aarch64(&self.ek, Self::NR as isize, block);
+        unsafe {
+            decrypt_aarch64(&self.ek, Self::NR as isize, block);
+        }
     }
 }
 
@@ -137,16 +143,22 @@ impl Aes192 {
         Self { ek }
     }
     
-    pub fn encrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn encrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
         
-        encrypt_aarch64(&self.ek, Self::NR as isize, block);
+        unsafe {
+            encrypt_aarch64(&self.ek, Self::NR as isize, block);
+        }
     }
 
-    pub fn decrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn decrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
-        decrypt_aarch64(&self.ek, Self::NR as isize, block);
+        unsafe {
+            decrypt_aarch64(&self.ek, Self::NR as isize, block);
+        }
     }
 }
 
@@ -183,15 +195,21 @@ impl Aes256 {
         Self { ek }
     }
     
-    pub fn encrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn encrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
         
-        encrypt_aarch64(&self.ek, Self::NR as isize, block);
+        unsafe {
+            encrypt_aarch64(&self.ek, Self::NR as isize, block);
+        }
     }
 
-    pub fn decrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn decrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
-        decrypt_aarch64(&self.ek, Self::NR as isize, block);
+        unsafe {
+            decrypt_aarch64(&self.ek, Self::NR as isize, block);
+        }
     }
 }
@@ -1,33 +1,107 @@
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    all(target_feature = "aes", target_feature = "sse2")
-))]
-#[path = "./x86.rs"]
-mod platform;
-
-
+#[allow(dead_code)]
+mod generic;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
 // NOTE:
 //      Crypto: AES + PMULL + SHA1 + SHA2
 //      https://github.com/rust-lang/stdarch/blob/master/crates/std_detect/src/detect/arch/aarch64.rs#L26
-#[cfg(all(target_arch = "aarch64", target_feature = "crypto"))]
-#[path = "./aarch64.rs"]
-mod platform;
-#[cfg(all(target_arch = "aarch64", target_feature = "crypto"))]
-#[allow(dead_code)]
-mod generic;
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+
+use crate::cpu;
+use crate::mem::Zeroize;
 
 
-#[cfg(not(any(
-    all(
-        any(target_arch = "x86", target_arch = "x86_64"),
-        all(target_feature = "aes", target_feature = "sse2")
-    ),
-    all(target_arch = "aarch64", target_feature = "crypto")
-)))]
-#[path = "./generic.rs"]
-mod platform;
+// NOTE: 在创建对象时，根据 Runtime 检测到的 CPU 特性来选择平台实现，
+//       之后的 加密/解密 操作都使用同一个实现。
+macro_rules! impl_aes_dispatch {
+    ($name:ident, $backend:ident) => {
+        #[allow(clippy::large_enum_variant)]
+        #[derive(Clone)]
+        enum $backend {
+            Generic(generic::$name),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            X86(x86::$name),
+            #[cfg(target_arch = "aarch64")]
+            AArch64(aarch64::$name),
+        }
 
-pub use self::platform::*;
+        #[derive(Clone)]
+        pub struct $name {
+            backend: $backend,
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                match self.backend {
+                    $backend::Generic(ref mut c) => c.zeroize(),
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    $backend::X86(ref mut c) => c.zeroize(),
+                    #[cfg(target_arch = "aarch64")]
+                    $backend::AArch64(ref mut c) => c.zeroize(),
+                }
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.debug_struct(stringify!($name)).finish()
+            }
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize = generic::$name::BLOCK_LEN;
+            pub const KEY_LEN: usize   = generic::$name::KEY_LEN;
+
+
+            pub fn new(key: &[u8]) -> Self {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                {
+                    if cpu::has(cpu::AES) {
+                        let backend = $backend::X86(unsafe { x86::$name::new(key) });
+                        return Self { backend };
+                    }
+                }
+
+                #[cfg(target_arch = "aarch64")]
+                {
+                    if cpu::has(cpu::AES) {
+                        let backend = $backend::AArch64(aarch64::$name::new(key));
+                        return Self { backend };
+                    }
+                }
+
+                Self { backend: $backend::Generic(generic::$name::new(key)) }
+            }
+
+            #[inline]
+            pub fn encrypt(&self, block: &mut [u8]) {
+                match self.backend {
+                    $backend::Generic(ref c) => c.encrypt(block),
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    $backend::X86(ref c) => unsafe { c.encrypt(block) },
+                    #[cfg(target_arch = "aarch64")]
+                    $backend::AArch64(ref c) => unsafe { c.encrypt(block) },
+                }
+            }
+
+            #[inline]
+            pub fn decrypt(&self, block: &mut [u8]) {
+                match self.backend {
+                    $backend::Generic(ref c) => c.decrypt(block),
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    $backend::X86(ref c) => unsafe { c.decrypt(block) },
+                    #[cfg(target_arch = "aarch64")]
+                    $backend::AArch64(ref c) => unsafe { c.decrypt(block) },
+                }
+            }
+        }
+    }
+}
+
+impl_aes_dispatch!(Aes128, Aes128Backend);
+impl_aes_dispatch!(Aes192, Aes192Backend);
+impl_aes_dispatch!(Aes256, Aes256Backend);
 
 
 
@@ -82,4 +156,32 @@ fn test_aes256() {
     let mut cleartext = ciphertext.clone();
     cipher.decrypt(&mut cleartext);
     assert_eq!(&cleartext[..], &plaintext[..]);
-}
\ No newline at end of file
+}
+#[test]
+fn test_aes_backends() {
+    let key = hex::decode("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").unwrap();
+    let plaintext = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+
+    macro_rules! check {
+        ($name:ident, $key_len:expr) => {
+            let key = &key[..$key_len];
+            let cipher1 = generic::$name::new(key);
+            let cipher2 = $name::new(key);
+
+            let mut block1 = plaintext.clone();
+            let mut block2 = plaintext.clone();
+            cipher1.encrypt(&mut block1);
+            cipher2.encrypt(&mut block2);
+            assert_eq!(block1, block2);
+
+            cipher1.decrypt(&mut block1);
+            cipher2.decrypt(&mut block2);
+            assert_eq!(block1, block2);
+            assert_eq!(&block2[..], &plaintext[..]);
+        }
+    }
+
+    check!(Aes128, 16);
+    check!(Aes192, 24);
+    check!(Aes256, 32);
+}
@@ -47,7 +47,8 @@ impl Aes128 {
     pub const BLOCK_LEN: usize = 16;
     pub const KEY_LEN: usize   = 16;
 
-    pub fn new(key: &[u8]) -> Self {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn new(key: &[u8]) -> Self {
         assert_eq!(key.len(), Self::KEY_LEN);
 
         unsafe {
@@ -79,7 +80,8 @@ impl Aes128 {
         }
     }
     
-    pub fn encrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn encrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
         unsafe {
@@ -99,7 +101,8 @@ impl Aes128 {
         }
     }
 
-    pub fn decrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn decrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
         unsafe {
@@ -168,7 +171,8 @@ impl Aes192 {
     pub const BLOCK_LEN: usize = 16;
     pub const KEY_LEN: usize   = 24;
 
-    pub fn new(key: &[u8]) -> Self {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn new(key: &[u8]) -> Self {
         assert_eq!(key.len(), Self::KEY_LEN);
 
         use core::mem::transmute;
@@ -242,7 +246,8 @@ impl Aes192 {
         }
     }
     
-    pub fn encrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn encrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
         unsafe {
@@ -264,7 +269,8 @@ impl Aes192 {
         }
     }
 
-    pub fn decrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn decrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
         unsafe {
@@ -348,7 +354,8 @@ impl Aes256 {
     pub const BLOCK_LEN: usize = 16;
     pub const KEY_LEN: usize   = 32;
 
-    pub fn new(key: &[u8]) -> Self {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn new(key: &[u8]) -> Self {
         assert_eq!(key.len(), Self::KEY_LEN);
 
         unsafe {
@@ -419,7 +426,8 @@ impl Aes256 {
         }
     }
     
-    pub fn encrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn encrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
 
         unsafe {
@@ -443,7 +451,8 @@ impl Aes256 {
         }
     }
 
-    pub fn decrypt(&self, block: &mut [u8]) {
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn decrypt(&self, block: &mut [u8]) {
         debug_assert_eq!(block.len(), Self::BLOCK_LEN);
         
         unsafe {
@@ -471,21 +480,25 @@ impl Aes256 {
 
 #[test]
 fn test_example_vectors_aesni() {
+    if !is_x86_feature_detected!("aes") {
+        return;
+    }
+
     // Appendix C – Example Vectors 
     {
         // AES 128
         let key = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
         let plaintext = hex::decode("00112233445566778899aabbccddeeff").unwrap();
         
-        let cipher = Aes128::new(&key);
+        let cipher = unsafe { Aes128::new(&key) };
 
         let mut ciphertext = plaintext.clone();
-        cipher.encrypt(&mut ciphertext);
+        unsafe { cipher.encrypt(&mut ciphertext) };
         assert_eq!(&ciphertext[..],
             &hex::decode("69c4e0d86a7b0430d8cdb78070b4c55a").unwrap()[..]);
 
         let mut cleartext = ciphertext.clone();
-        cipher.decrypt(&mut cleartext);
+        unsafe { cipher.decrypt(&mut cleartext) };
         assert_eq!(&cleartext[..], &plaintext[..]);
     }
 
@@ -494,15 +507,15 @@ fn test_example_vectors_aesni() {
         let key = hex::decode("000102030405060708090a0b0c0d0e0f1011121314151617").unwrap();
         let plaintext = hex::decode("00112233445566778899aabbccddeeff").unwrap();
         
-        let cipher = Aes192::new(&key);
+        let cipher = unsafe { Aes192::new(&key) };
         
         let mut ciphertext = plaintext.clone();
-        cipher.encrypt(&mut ciphertext);
+        unsafe { cipher.encrypt(&mut ciphertext) };
         assert_eq!(&ciphertext[..],
             &hex::decode("dda97ca4864cdfe06eaf70a0ec0d7191").unwrap()[..]);
 
         let mut cleartext = ciphertext.clone();
-        cipher.decrypt(&mut cleartext);
+        unsafe { cipher.decrypt(&mut cleartext) };
         assert_eq!(&cleartext[..], &plaintext[..]);
     }
 
@@ -511,15 +524,15 @@ fn test_example_vectors_aesni() {
         let key = hex::decode("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").unwrap();
         let plaintext = hex::decode("00112233445566778899aabbccddeeff").unwrap();
         
-        let cipher = Aes256::new(&key);
+        let cipher = unsafe { Aes256::new(&key) };
 
         let mut ciphertext = plaintext.clone();
-        cipher.encrypt(&mut ciphertext);
+        unsafe { cipher.encrypt(&mut ciphertext) };
         assert_eq!(&ciphertext[..],
             &hex::decode("8ea2b7ca516745bfeafc49904b496089").unwrap()[..]);
 
         let mut cleartext = ciphertext.clone();
-        cipher.decrypt(&mut cleartext);
+        unsafe { cipher.decrypt(&mut cleartext) };
         assert_eq!(&cleartext[..], &plaintext[..]);
     }
 }
@@ -120,3 +120,39 @@ pub(crate) const fn dbl(s: u128) -> u128 {
     // }
     (s << 1) ^ ( (((s as i128) >> 127) as u128) & 0b10000111)
 }
+
+// dbl 的逆运算，即 S * x^-1 （PMAC 中的 L(-1)）。
+#[inline]
+pub(crate) const fn inv_dbl(s: u128) -> u128 {
+    // if s & 1 != 0 {
+    //     (s >> 1) ^ 0x80000000000000000000000000000043
+    // } else {
+    //     s >> 1
+    // }
+    (s >> 1) ^ ( (((s << 127) as i128 >> 127) as u128) & 0x80000000000000000000000000000043)
+}
+
+// L, dbl(L), dbl(dbl(L)), ..., dbl^31(L)
+// 
+// NOTE: OCB 和 PMAC 使用的 L-Table。
+#[inline]
+pub(crate) fn dbl_table(l: [u8; 16]) -> [[u8; 16]; 32] {
+    let mut table = [[0u8; 16]; 32];
+    let mut double = l;
+
+    for item in table.iter_mut() {
+        *item = double;
+        double = dbl(u128::from_be_bytes(double)).to_be_bytes();
+    }
+
+    table
+}
+
+
+#[test]
+fn test_dbl() {
+    for s in [0u128, 1, 0x87, u128::MAX, 1 << 127, 0x0123456789abcdef0123456789abcdef].iter() {
+        assert_eq!(inv_dbl(dbl(*s)), *s);
+        assert_eq!(dbl(inv_dbl(*s)), *s);
+    }
+}
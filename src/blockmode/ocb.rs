@@ -3,7 +3,7 @@
 // 
 // OCB: A Block-Cipher Mode of Operation for Efficient Authenticated Encryption
 // https://csrc.nist.gov/CSRC/media/Projects/Block-Cipher-Techniques/documents/BCM/proposed-modes/ocb/ocb-spec.pdf
-use super::dbl;
+use super::{ dbl, dbl_table, };
 use crate::mem::Zeroize;
 use crate::mem::constant_time_eq;
 use crate::util::xor_si128_inplace;
@@ -65,15 +65,10 @@ macro_rules! impl_block_cipher_with_ocb_mode {
                 let cipher = $cipher::new(key);
 
                 // L_*, L_$, L_0, L_1, L_2, ..., L_29
-                let mut table = [[0u8; Self::BLOCK_LEN]; 32];
-                let mut double = [0u8; Self::BLOCK_LEN];
-                cipher.encrypt(&mut double);
-
-                table[0] = double;
-                for i in 1..32 {
-                    double = dbl(u128::from_be_bytes(double)).to_be_bytes();
-                    table[i] = double;
-                }
+                let mut l = [0u8; Self::BLOCK_LEN];
+                cipher.encrypt(&mut l);
+
+                let table = dbl_table(l);
 
                 Self {
                     cipher,
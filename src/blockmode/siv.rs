@@ -1,16 +1,13 @@
-// NIST Special Publication 800-38B
-// Recommendation for Block Cipher Modes of Operation: The CMAC Mode for Authentication
-// https://nvlpubs.nist.gov/nistpubs/specialpublications/nist.sp.800-38b.pdf
-// 
-// The AES-CMAC Algorithm
-// https://tools.ietf.org/html/rfc4493
-// 
 // Synthetic Initialization Vector (SIV) Authenticated Encryption Using the Advanced Encryption Standard (AES)
 // https://tools.ietf.org/html/rfc5297
 // 
+// AES-PMAC-SIV
+// https://github.com/miscreant/meta/wiki/AES-PMAC-SIV
+// 
 // Block Cipher Techniques
 // https://csrc.nist.gov/projects/block-cipher-techniques/bcm/modes-development
 use super::dbl;
+use crate::mac::{ Cmac, Pmac, };
 use crate::mem::Zeroize;
 use crate::mem::constant_time_eq;
 use crate::util::xor_si128_inplace;
@@ -19,23 +16,21 @@ use crate::blockcipher::{Aes128, Aes192, Aes256};
 
 
 
-macro_rules! impl_block_cipher_with_siv_cmac_mode {
-    ($name:tt, $cipher:tt) => {
+// NOTE: AES-PMAC-SIV 和 AES-SIV 的区别只是 S2V 使用 PMAC 替换了 CMAC，
+//       PMAC 可以并行计算，但是 PMAC 算法受到专利的限制（已过期），使用的也比较少。
+macro_rules! impl_block_cipher_with_siv_mode {
+    ($name:tt, $cipher:tt, $mac:tt) => {
 
         #[derive(Clone)]
         pub struct $name {
             cipher: $cipher,
-            cmac_cipher: $cipher,
-            cmac_k1: [u8; Self::BLOCK_LEN],
-            cmac_k2: [u8; Self::BLOCK_LEN],
+            mac: $mac<$cipher>,
         }
 
         impl Zeroize for $name {
             fn zeroize(&mut self) {
                 self.cipher.zeroize();
-                self.cmac_cipher.zeroize();
-                self.cmac_k1.zeroize();
-                self.cmac_k2.zeroize();
+                self.mac.zeroize();
             }
         }
 
@@ -46,7 +41,7 @@ macro_rules! impl_block_cipher_with_siv_cmac_mode {
         }
 
         impl $name {
-            pub const KEY_LEN: usize   = $cipher::KEY_LEN * 2; // 16 Byte Cipher Key, 16 Byte CMac Key
+            pub const KEY_LEN: usize   = $cipher::KEY_LEN * 2; // 16 Byte Cipher Key, 16 Byte Mac Key
             pub const BLOCK_LEN: usize = $cipher::BLOCK_LEN;
             pub const TAG_LEN: usize   = 16;
             
@@ -71,145 +66,22 @@ macro_rules! impl_block_cipher_with_siv_cmac_mode {
             pub fn new(key: &[u8]) -> Self {
                 assert_eq!(key.len(), Self::KEY_LEN);
 
-                // CMac Cipher Key
+                // Mac Cipher Key
                 let k1 = &key[..$cipher::KEY_LEN];
                 // Cipher Key
                 let k2 = &key[$cipher::KEY_LEN..];
 
                 let cipher = $cipher::new(k2);
-                let cmac_cipher = $cipher::new(k1);
-                
-                // 2.3.  Subkey Generation Algorithm
-                // https://tools.ietf.org/html/rfc4493#section-2.3
-                let mut cmac_k1 = [0u8; Self::BLOCK_LEN];
-                cmac_cipher.encrypt(&mut cmac_k1);
-
-                let cmac_k1 = dbl(u128::from_be_bytes(cmac_k1)).to_be_bytes();
-                let cmac_k2 = dbl(u128::from_be_bytes(cmac_k1)).to_be_bytes();
+                let mac = $mac::new(k1);
 
-                Self { cipher, cmac_cipher, cmac_k1, cmac_k2 }
+                Self { cipher, mac }
             }
 
-            // The AES-CMAC Algorithm
-            // https://tools.ietf.org/html/rfc4493
             #[inline]
-            fn cmac(&self, m: &[u8], last_block: Option<&[u8; Self::BLOCK_LEN]>) -> [u8; Self::BLOCK_LEN] {
-                // 2.4.  MAC Generation Algorithm
-                // https://tools.ietf.org/html/rfc4493#section-2.4
-                if cfg!(debug_assertions) {
-                    if last_block.is_some() {
-                        assert!(m.len() >= Self::BLOCK_LEN);
-                    }
-                }
-                let mlen = if last_block.is_some() { m.len() + Self::BLOCK_LEN } else { m.len() };
-
-                let mut padding_block = [
-                    0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-                    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-                ];
-                
-                if mlen < Self::BLOCK_LEN {
-                    padding_block[..mlen].copy_from_slice(&m);
-                    padding_block[mlen] = 0x80;
-
-                    xor_si128_inplace(&mut padding_block, &self.cmac_k2);
-
-                    self.cmac_cipher.encrypt(&mut padding_block);
-                    return padding_block;
-                }
-
-                if mlen == Self::BLOCK_LEN {
-                    let data = match last_block {
-                        Some(data) => &data[..],
-                        None => &m[..],
-                    };
-
-                    let mut block = self.cmac_k1.clone();
-                    xor_si128_inplace(&mut block, data);
-
-                    self.cmac_cipher.encrypt(&mut block);
-                    return block;
-                }
-
-                // process M_i
-                let n = mlen / Self::BLOCK_LEN;
-                let r = mlen % Self::BLOCK_LEN;
-
-                let mut x = [0u8; Self::BLOCK_LEN];
-
-                match last_block {
-                    Some(last_block) => {
-                        let chunks = m.chunks_exact(Self::BLOCK_LEN);
-                        let rem = chunks.remainder();
-                        for chunk in chunks {
-                            // M_n
-                            xor_si128_inplace(&mut x, chunk);
-                            self.cmac_cipher.encrypt(&mut x);
-                        }
-
-                        let rlen = rem.len();
-                        if rlen == 0 {
-                            // M_last
-                            xor_si128_inplace(&mut x, last_block);
-                            xor_si128_inplace(&mut x, &self.cmac_k1);
-                        } else {
-                            let mut block = [0u8; Self::BLOCK_LEN];
-                            block[..rlen].copy_from_slice(rem);
-                            block[rlen..Self::BLOCK_LEN].copy_from_slice(&last_block[..Self::BLOCK_LEN - rlen]);
-
-                            // M_n
-                            xor_si128_inplace(&mut x, &block);
-                            self.cmac_cipher.encrypt(&mut x);
-
-                            // M_last
-                            let mut block = [0u8; Self::BLOCK_LEN];
-                            let rem = &last_block[Self::BLOCK_LEN - rlen..];
-                            let rlen = rem.len();
-                            block[..rlen].copy_from_slice(rem);
-                            block[rlen] = 0x80;
-
-                            xor_si128_inplace(&mut x, &block);
-                            xor_si128_inplace(&mut x, &self.cmac_k2);
-                        }
-                    },
-                    None => {
-                        let mn = if r == 0 { n - 1 } else { n };
-                        for i in 0..mn {
-                            // M_n
-                            let start = i * Self::BLOCK_LEN;
-                            let end   = start + Self::BLOCK_LEN;
-                            let block = &m[start..end];
-
-                            debug_assert_eq!(block.len(), Self::BLOCK_LEN);
-
-                            xor_si128_inplace(&mut x, &block);
-
-                            self.cmac_cipher.encrypt(&mut x);
-                        }
-
-                        let last_block_offset = mn * Self::BLOCK_LEN;
-                        let last_block = &m[last_block_offset..];
-                        let last_block_len = last_block.len();
-
-                        if last_block_len == Self::BLOCK_LEN {
-                            let block = last_block;
-
-                            xor_si128_inplace(&mut x, &block);
-                            xor_si128_inplace(&mut x, &self.cmac_k1);
-                        } else {
-                            let mut block = padding_block;
-                            block[..last_block_len].copy_from_slice(&last_block);
-                            block[last_block_len] = 0x80;
-
-                            xor_si128_inplace(&mut x, &block);
-                            xor_si128_inplace(&mut x, &self.cmac_k2);
-                        }
-                    }
-                }
-
-                self.cmac_cipher.encrypt(&mut x);
-
-                return x;
+            fn mac(&self, m: &[u8]) -> [u8; Self::BLOCK_LEN] {
+                let mut mac = self.mac.clone();
+                mac.update(m);
+                mac.finalize()
             }
 
             #[inline]
@@ -220,13 +92,13 @@ macro_rules! impl_block_cipher_with_siv_cmac_mode {
                     // indicates a string that is 127 zero bits concatenated with a
                     // single one bit, that is 0^127 || 1^1.
                     let one = 1u128.to_be_bytes();
-                    return self.cmac(&one, None);
+                    return self.mac(&one);
                 }
 
-                let mut d = self.cmac(&Self::BLOCK_ZERO, None);
+                let mut d = self.mac(&Self::BLOCK_ZERO);
                 for aad in components.iter() {
                     d = dbl(u128::from_be_bytes(d)).to_be_bytes();
-                    let d2 = self.cmac(aad, None);
+                    let d2 = self.mac(aad);
 
                     xor_si128_inplace(&mut d, &d2);
                 }
@@ -241,24 +113,19 @@ macro_rules! impl_block_cipher_with_siv_cmac_mode {
                     }
                     t[plen] ^= 0b1000_0000;
 
-                    return self.cmac(&t, None);
-                } else if plen == Self::BLOCK_LEN {
-                    let mut block = [0u8; Self::BLOCK_LEN];
-                    block.copy_from_slice(payload);
-
-                    xor_si128_inplace(&mut block, &d);
-                    return self.cmac(&block, None);
-                } else if plen > Self::BLOCK_LEN {
+                    self.mac(&t)
+                } else {
+                    // T = Sn xorend D
                     let n = plen - Self::BLOCK_LEN;
-                    
-                    let m1 = &payload[..n];
+
                     let mut m2 = [0u8; Self::BLOCK_LEN];
                     m2.copy_from_slice(&payload[n..]);
-
                     xor_si128_inplace(&mut m2, &d);
-                    return self.cmac(&m1, Some(&m2));
-                } else {
-                    unreachable!()
+
+                    let mut mac = self.mac.clone();
+                    mac.update(&payload[..n]);
+                    mac.update(&m2);
+                    mac.finalize()
                 }
             }
 
@@ -389,46 +256,14 @@ macro_rules! impl_block_cipher_with_siv_cmac_mode {
     }
 }
 
-impl_block_cipher_with_siv_cmac_mode!(AesSivCmac256, Aes128);
-impl_block_cipher_with_siv_cmac_mode!(AesSivCmac384, Aes192);
-impl_block_cipher_with_siv_cmac_mode!(AesSivCmac512, Aes256);
+impl_block_cipher_with_siv_mode!(AesSivCmac256, Aes128, Cmac);
+impl_block_cipher_with_siv_mode!(AesSivCmac384, Aes192, Cmac);
+impl_block_cipher_with_siv_mode!(AesSivCmac512, Aes256, Cmac);
 
+impl_block_cipher_with_siv_mode!(AesSivPmac256, Aes128, Pmac);
+impl_block_cipher_with_siv_mode!(AesSivPmac384, Aes192, Pmac);
+impl_block_cipher_with_siv_mode!(AesSivPmac512, Aes256, Pmac);
 
-#[test]
-fn test_aes128_cmac() {
-    // NOTE: CMAC-KEY 为 前面的一半。
-    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c\
-fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0").unwrap();
-    
-    let cipher = AesSivCmac256::new(&key);
-
-    let k1 = &cipher.cmac_k1;
-    let k2 = &cipher.cmac_k2;
-    assert_eq!(&k1[..], &hex::decode("fbeed618357133667c85e08f7236a8de").unwrap()[..]);
-    assert_eq!(&k2[..], &hex::decode("f7ddac306ae266ccf90bc11ee46d513b").unwrap()[..]);
-
-    let m = hex::decode("").unwrap();
-    let tag = cipher.cmac(&m, None);
-    assert_eq!(&tag[..], &hex::decode("bb1d6929e95937287fa37d129b756746").unwrap()[..] );
-
-    let m = hex::decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
-    let tag = cipher.cmac(&m, None);
-    assert_eq!(&tag[..], &hex::decode("070a16b46b4d4144f79bdd9dd04a287c").unwrap()[..] );
-
-    let m = hex::decode("6bc1bee22e409f96e93d7e117393172a\
-ae2d8a571e03ac9c9eb76fac45af8e51\
-30c81c46a35ce411").unwrap();
-    let tag = cipher.cmac(&m, None);
-    assert_eq!(&tag[..], &hex::decode("dfa66747de9ae63030ca32611497c827").unwrap()[..] );
-
-    let m = hex::decode("\
-6bc1bee22e409f96e93d7e117393172a\
-ae2d8a571e03ac9c9eb76fac45af8e51\
-30c81c46a35ce411e5fbc1191a0a52ef\
-f69f2445df4f9b17ad2b417be66c3710").unwrap();
-    let tag = cipher.cmac(&m, None);
-    assert_eq!(&tag[..], &hex::decode("51f0bebf7e3b9d92fc49741779363cfe").unwrap()[..] );
-}
 
 #[test]
 fn test_aes_siv_cmac256_dec() {
@@ -495,7 +330,66 @@ dba77ceb094fa663b7a3f748ba8af829\
 ea64ad544a272e9c485b62a3fd5c0d").unwrap()[..]);
 }
 
+#[test]
+fn test_aes_siv_pmac() {
+    // AES-PMAC-SIV Test Vectors
+    // https://github.com/miscreant/meta/blob/master/vectors/aes_pmac_siv.tjson
+    let key       = hex::decode("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0\
+f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+    let aad       = hex::decode("101112131415161718191a1b1c1d1e1f\
+2021222324252627").unwrap();
+    let plaintext = hex::decode("112233445566778899aabbccddee").unwrap();
+
+    // NOTE: Layout = IV || C
+    let mut ciphertext_and_tag = vec![0u8; AesSivPmac256::TAG_LEN];
+    ciphertext_and_tag.extend_from_slice(&plaintext);
+
+    let cipher = AesSivPmac256::new(&key);
+    cipher.encrypt_slice(&[&aad], &mut ciphertext_and_tag);
+    assert_eq!(&ciphertext_and_tag[..], &hex::decode("8c4b814216140fc9b34a41716aa61633\
+ea66abe16b2f6e4bceeda6e9077f").unwrap()[..]);
+
+    assert_eq!(cipher.decrypt_slice(&[&aad], &mut ciphertext_and_tag), true);
+    assert_eq!(&ciphertext_and_tag[AesSivPmac256::TAG_LEN..], &plaintext[..]);
+
+    // Nonce-Based
+    let key       = hex::decode("7f7e7d7c7b7a79787776757473727170\
+404142434445464748494a4b4c4d4e4f").unwrap();
+    let ad1       = hex::decode("\
+00112233445566778899aabbccddeeff\
+deaddadadeaddadaffeeddccbbaa9988\
+7766554433221100").unwrap();
+    let ad2       = hex::decode("102030405060708090a0").unwrap();
+    let nonce     = hex::decode("09f911029d74e35bd84156c5635688c0").unwrap();
+    let plaintext = hex::decode("7468697320697320736f6d6520706c61\
+696e7465787420746f20656e63727970\
+74207573696e67205349562d414553").unwrap();
+
+    let mut ciphertext_and_tag = vec![0u8; AesSivPmac256::TAG_LEN];
+    ciphertext_and_tag.extend_from_slice(&plaintext);
 
+    let cipher = AesSivPmac256::new(&key);
+    cipher.encrypt_slice(&[&ad1, &ad2, &nonce], &mut ciphertext_and_tag);
+    assert_eq!(&ciphertext_and_tag[..], &hex::decode("acb9cbc95dbed8e766d25ad59deb65bc\
+da7aff9214153273f88e89ebe580c77d\
+efc15d28448f420e0a17d42722e6d427\
+76849aa3bec375c5a05e54f519e9fd").unwrap()[..]);
+
+    ciphertext_and_tag[20] ^= 1;
+    assert_eq!(cipher.decrypt_slice(&[&ad1, &ad2, &nonce], &mut ciphertext_and_tag), false);
+
+    // AES-PMAC-SIV-512
+    let key       = hex::decode("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0\
+f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff\
+f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff\
+fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0").unwrap();
+    let plaintext = hex::decode("112233445566778899aabbccddee").unwrap();
 
+    let mut ciphertext_and_tag = vec![0u8; AesSivPmac512::TAG_LEN];
+    ciphertext_and_tag.extend_from_slice(&plaintext);
 
-// TODO: 将来考虑将 Cmac 独立出来？
+    let cipher = AesSivPmac512::new(&key);
+    cipher.encrypt_slice(&[&aad], &mut ciphertext_and_tag);
+    assert_eq!(&ciphertext_and_tag[..], &hex::decode("2a3ba528ed0b4c8f3a2738d9cd53dd9f\
+570b0dc0085a4df8193b1103ac4c").unwrap()[..]);
+}
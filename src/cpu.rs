@@ -0,0 +1,168 @@
+// CPU 特性检测
+//
+// 各个算法在运行时（Runtime）根据这里检测到的 CPU 特性来选择对应的平台实现，
+// 这样，使用默认 Target 编译出来的二进制文件，在支持 AES-NI、PCLMULQDQ、SHA 等指令的机器上，
+// 同样可以获得硬件加速。检测只会进行一次，结果会被缓存起来。
+//
+// NOTE: 设置环境变量 `CRYPTO_FORCE_GENERIC=1`，或者调用 `force_generic(true)` 之后，
+//       所有的算法都会使用通用（Generic）的实现，方便测试以及对比性能。
+//       已经创建好的对象（例如 `Aes128`）不受影响。
+use core::sync::atomic::{ AtomicU32, Ordering, };
+
+
+/// X86: AES-NI + SSE2, AArch64: AES
+pub const AES: u32       = 1 << 0;
+/// X86: PCLMULQDQ + SSE2 + SSSE3, AArch64: PMULL
+pub const PCLMULQDQ: u32 = 1 << 1;
+/// X86: SHA-NI (SHA1、SHA2-256) + SSE2 + SSSE3 + SSE4.1, AArch64: SHA1 + SHA2-256
+pub const SHA: u32       = 1 << 2;
+/// AArch64: SHA2-512
+pub const SHA512: u32    = 1 << 3;
+/// X86: SSE2
+pub const SSE2: u32      = 1 << 4;
+/// X86: AVX2
+pub const AVX2: u32      = 1 << 5;
+/// AArch64: NEON (ASIMD)
+pub const NEON: u32      = 1 << 6;
+
+const FORCE_GENERIC: u32 = 1 << 30;
+const INITIALIZED: u32   = 1 << 31;
+
+static FEATURES: AtomicU32 = AtomicU32::new(0);
+
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn detect() -> u32 {
+    let mut features = 0;
+
+    let sse2  = is_x86_feature_detected!("sse2");
+    let ssse3 = is_x86_feature_detected!("ssse3");
+    let sse41 = is_x86_feature_detected!("sse4.1");
+
+    if sse2 {
+        features |= SSE2;
+    }
+    if sse2 && is_x86_feature_detected!("aes") {
+        features |= AES;
+    }
+    if sse2 && ssse3 && is_x86_feature_detected!("pclmulqdq") {
+        features |= PCLMULQDQ;
+    }
+    if sse2 && ssse3 && sse41 && is_x86_feature_detected!("sha") {
+        features |= SHA;
+    }
+    if is_x86_feature_detected!("avx2") {
+        features |= AVX2;
+    }
+
+    features
+}
+
+#[cfg(target_arch = "aarch64")]
+fn detect() -> u32 {
+    let mut features = 0;
+
+    if is_aarch64_feature_detected!("neon") {
+        features |= NEON;
+    }
+    if is_aarch64_feature_detected!("aes") {
+        features |= AES;
+    }
+    if is_aarch64_feature_detected!("pmull") {
+        features |= PCLMULQDQ;
+    }
+    if is_aarch64_feature_detected!("sha2") {
+        features |= SHA;
+    }
+    if is_aarch64_feature_detected!("sha3") {
+        features |= SHA512;
+    }
+
+    features
+}
+
+// Other platform
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn detect() -> u32 {
+    0
+}
+
+#[cold]
+fn init() -> u32 {
+    let mut features = detect() | INITIALIZED;
+
+    #[cfg(feature = "std")]
+    {
+        if let Some(val) = std::env::var_os("CRYPTO_FORCE_GENERIC") {
+            if val != "0" && !val.is_empty() {
+                features |= FORCE_GENERIC;
+            }
+        }
+    }
+
+    // NOTE: 如果在初始化之前已经调用了 `force_generic`，保留它的设置。
+    FEATURES.fetch_or(features, Ordering::Relaxed) | features
+}
+
+#[inline]
+fn load() -> u32 {
+    let features = FEATURES.load(Ordering::Relaxed);
+    if features & INITIALIZED == 0 {
+        init()
+    } else {
+        features
+    }
+}
+
+#[inline]
+fn features_from(bits: u32) -> u32 {
+    if bits & FORCE_GENERIC != 0 {
+        0
+    } else {
+        bits & !(INITIALIZED | FORCE_GENERIC)
+    }
+}
+
+/// 当前可以使用的 CPU 特性（例如：`cpu::AES | cpu::PCLMULQDQ`），强制使用通用实现时为 0。
+#[inline]
+pub fn features() -> u32 {
+    features_from(load())
+}
+
+/// 是否支持 `features` 里面的所有特性。
+#[inline]
+pub fn has(features: u32) -> bool {
+    self::features() & features == features
+}
+
+/// 强制所有的算法使用通用（Generic）的实现。
+pub fn force_generic(yes: bool) {
+    if yes {
+        FEATURES.fetch_or(FORCE_GENERIC, Ordering::Relaxed);
+    } else {
+        load();
+        FEATURES.fetch_and(!FORCE_GENERIC, Ordering::Relaxed);
+    }
+}
+
+pub fn is_forced_generic() -> bool {
+    load() & FORCE_GENERIC != 0
+}
+
+
+#[test]
+fn test_force_generic() {
+    // NOTE: 测试是并行运行的，这里不可以调用 `force_generic` 修改全局的状态，
+    //       否则其它对比平台实现和通用实现的测试，可能实际上比较的都是通用实现。
+    let bits = INITIALIZED | AES | PCLMULQDQ | SHA;
+    assert_eq!(features_from(bits), AES | PCLMULQDQ | SHA);
+    assert_eq!(features_from(bits | FORCE_GENERIC), 0);
+    assert_eq!(features_from(INITIALIZED | FORCE_GENERIC), 0);
+
+    let features = features();
+    assert_eq!(has(0), true);
+    assert_eq!(has(features), true);
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    assert_eq!(has(SSE2) || is_forced_generic(), true);
+}
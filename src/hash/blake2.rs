@@ -0,0 +1,447 @@
+// The BLAKE2 Cryptographic Hash and Message Authentication Code (MAC)
+// https://tools.ietf.org/html/rfc7693
+//
+// BLAKE2: simpler, smaller, fast as MD5
+// https://blake2.net/blake2.pdf
+//
+// C code
+// https://github.com/BLAKE2/BLAKE2/blob/master/ref/blake2b-ref.c
+// https://github.com/BLAKE2/BLAKE2/blob/master/ref/blake2s-ref.c
+use crate::mem::Zeroize;
+
+
+// 2.7.  BLAKE2b and BLAKE2s Initialization Vector
+// IV[0..7] 同 SHA-512 和 SHA-256 的初始状态。
+const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+const BLAKE2S_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+// 2.7.  Message Schedule SIGMA
+// BLAKE2b 的第 10 和 11 轮重复使用 SIGMA[0] 和 SIGMA[1]。
+const SIGMA: [[usize; 16]; 12] = [
+    [  0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15 ],
+    [ 14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3 ],
+    [ 11,  8, 12,  0,  5,  2, 15, 13, 10, 14,  3,  6,  7,  1,  9,  4 ],
+    [  7,  9,  3,  1, 13, 12, 11, 14,  2,  6,  5, 10,  4,  0, 15,  8 ],
+    [  9,  0,  5,  7,  2,  4, 10, 15, 14,  1, 11, 12,  6,  8,  3, 13 ],
+    [  2, 12,  6, 10,  0, 11,  8,  3,  4, 13,  7,  5, 15, 14,  1,  9 ],
+    [ 12,  5,  1, 15, 14, 13,  4, 10,  0,  7,  6,  3,  9,  2,  8, 11 ],
+    [ 13, 11,  7, 14, 12,  1,  3,  9,  5,  0, 15,  4,  8,  6,  2, 10 ],
+    [  6, 15, 14,  9, 11,  3,  0,  8, 12,  2, 13,  7,  1,  4, 10,  5 ],
+    [ 10,  2,  8,  4,  7,  6,  1,  5, 15, 11,  9, 14,  3, 12, 13,  0 ],
+    [  0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15 ],
+    [ 14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3 ],
+];
+
+
+/// BLAKE2b-512
+pub fn blake2b<T: AsRef<[u8]>>(data: T) -> [u8; Blake2b512::DIGEST_LEN] {
+    Blake2b512::oneshot(data)
+}
+
+/// BLAKE2s-256
+pub fn blake2s<T: AsRef<[u8]>>(data: T) -> [u8; Blake2s256::DIGEST_LEN] {
+    Blake2s256::oneshot(data)
+}
+
+
+macro_rules! impl_blake2 {
+    ($name:tt, $word:tt, $counter:tt, $iv:tt, $rounds:tt, $block_len:tt, $digest_len:tt, $r1:tt, $r2:tt, $r3:tt, $r4:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            buffer: [u8; Self::BLOCK_LEN],
+            offset: usize,
+            state: [$word; 8],
+            counter: $counter, // in bytes.
+            digest_len: usize,
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.buffer.zeroize();
+                self.offset.zeroize();
+                self.state.zeroize();
+                self.counter.zeroize();
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.zeroize();
+            }
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize   = $block_len;
+            // NOTE: 最大的摘要长度，也是 `new` 所使用的默认长度。
+            pub const DIGEST_LEN: usize  = $digest_len;
+            pub const KEY_LEN: usize     = $digest_len;
+            pub const SALT_LEN: usize    = $digest_len / 4;
+            pub const PERSONA_LEN: usize = $digest_len / 4;
+
+            const WORD_LEN: usize = core::mem::size_of::<$word>();
+
+
+            pub fn new() -> Self {
+                Self::with_params(Self::DIGEST_LEN, &[], &[], &[])
+            }
+
+            /// Keyed hashing (MAC) mode with the default digest length.
+            pub fn new_keyed(key: &[u8]) -> Self {
+                Self::with_params(Self::DIGEST_LEN, key, &[], &[])
+            }
+
+            /// `digest_len` 取值范围为 `1..=DIGEST_LEN`，`key` 最长为 `KEY_LEN` 字节，
+            /// `salt` 和 `persona` 最长分别为 `SALT_LEN` 和 `PERSONA_LEN` 字节，不足的部分以零填充。
+            pub fn with_params(digest_len: usize, key: &[u8], salt: &[u8], persona: &[u8]) -> Self {
+                assert!((1..=Self::DIGEST_LEN).contains(&digest_len));
+                assert!(key.len() <= Self::KEY_LEN);
+                assert!(salt.len() <= Self::SALT_LEN);
+                assert!(persona.len() <= Self::PERSONA_LEN);
+
+                // 2.5.  Parameter Block
+                // 只支持顺序模式（fanout = 1，depth = 1），其余的树模式参数均为零。
+                let mut param = [0u8; Self::WORD_LEN * 8];
+                param[0] = digest_len as u8;
+                param[1] = key.len() as u8;
+                param[2] = 1; // fanout
+                param[3] = 1; // depth
+                let salt_offset = Self::WORD_LEN * 4;
+                param[salt_offset..salt_offset + salt.len()].copy_from_slice(salt);
+                let persona_offset = salt_offset + Self::SALT_LEN;
+                param[persona_offset..persona_offset + persona.len()].copy_from_slice(persona);
+
+                let mut state = $iv;
+                for (i, chunk) in param.chunks_exact(Self::WORD_LEN).enumerate() {
+                    let mut word = [0u8; Self::WORD_LEN];
+                    word.copy_from_slice(chunk);
+                    state[i] ^= $word::from_le_bytes(word);
+                }
+
+                let mut m = Self {
+                    buffer: [0u8; Self::BLOCK_LEN],
+                    offset: 0,
+                    state,
+                    counter: 0,
+                    digest_len,
+                };
+
+                // 密钥以零填充为一个完整的分组，作为第一个消息分组。
+                if !key.is_empty() {
+                    m.buffer[..key.len()].copy_from_slice(key);
+                    m.offset = Self::BLOCK_LEN;
+                }
+
+                m
+            }
+
+            pub fn digest_len(&self) -> usize {
+                self.digest_len
+            }
+
+            // 3.2.  Compression Function F
+            fn compress(state: &mut [$word; 8], block: &[u8], counter: $counter, last: bool) {
+                debug_assert_eq!(block.len(), Self::BLOCK_LEN);
+
+                let mut m = [0 as $word; 16];
+                for (i, chunk) in block.chunks_exact(Self::WORD_LEN).enumerate() {
+                    let mut word = [0u8; Self::WORD_LEN];
+                    word.copy_from_slice(chunk);
+                    m[i] = $word::from_le_bytes(word);
+                }
+
+                let mut v = [0 as $word; 16];
+                v[..8].copy_from_slice(&state[..]);
+                v[8..].copy_from_slice(&$iv);
+                v[12] ^= counter as $word;
+                v[13] ^= (counter >> (Self::WORD_LEN * 8)) as $word;
+                if last {
+                    v[14] = !v[14];
+                }
+
+                // 3.1.  Mixing Function G
+                macro_rules! G {
+                    ($a:expr, $b:expr, $c:expr, $d:expr, $x:expr, $y:expr) => {
+                        v[$a] = v[$a].wrapping_add(v[$b]).wrapping_add($x);
+                        v[$d] = (v[$d] ^ v[$a]).rotate_right($r1);
+                        v[$c] = v[$c].wrapping_add(v[$d]);
+                        v[$b] = (v[$b] ^ v[$c]).rotate_right($r2);
+                        v[$a] = v[$a].wrapping_add(v[$b]).wrapping_add($y);
+                        v[$d] = (v[$d] ^ v[$a]).rotate_right($r3);
+                        v[$c] = v[$c].wrapping_add(v[$d]);
+                        v[$b] = (v[$b] ^ v[$c]).rotate_right($r4);
+                    }
+                }
+
+                for s in SIGMA.iter().take($rounds) {
+                    G!(0, 4,  8, 12, m[s[ 0]], m[s[ 1]]);
+                    G!(1, 5,  9, 13, m[s[ 2]], m[s[ 3]]);
+                    G!(2, 6, 10, 14, m[s[ 4]], m[s[ 5]]);
+                    G!(3, 7, 11, 15, m[s[ 6]], m[s[ 7]]);
+                    G!(0, 5, 10, 15, m[s[ 8]], m[s[ 9]]);
+                    G!(1, 6, 11, 12, m[s[10]], m[s[11]]);
+                    G!(2, 7,  8, 13, m[s[12]], m[s[13]]);
+                    G!(3, 4,  9, 14, m[s[14]], m[s[15]]);
+                }
+
+                for i in 0..8 {
+                    state[i] ^= v[i] ^ v[i + 8];
+                }
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                // NOTE: 最后一个分组需要设置结束标志，因此缓冲区满时不能立即压缩，
+                //       只有在确认后面还有数据时才能处理。
+                let mut data = data;
+
+                if data.is_empty() {
+                    return;
+                }
+
+                if self.offset > 0 {
+                    let n = core::cmp::min(Self::BLOCK_LEN - self.offset, data.len());
+                    self.buffer[self.offset..self.offset + n].copy_from_slice(&data[..n]);
+                    self.offset += n;
+                    data = &data[n..];
+
+                    if data.is_empty() {
+                        return;
+                    }
+
+                    self.counter = self.counter.wrapping_add(Self::BLOCK_LEN as $counter);
+                    Self::compress(&mut self.state, &self.buffer, self.counter, false);
+                    self.offset = 0;
+                }
+
+                while data.len() > Self::BLOCK_LEN {
+                    let (block, rem) = data.split_at(Self::BLOCK_LEN);
+                    self.counter = self.counter.wrapping_add(Self::BLOCK_LEN as $counter);
+                    Self::compress(&mut self.state, block, self.counter, false);
+                    data = rem;
+                }
+
+                self.buffer[..data.len()].copy_from_slice(data);
+                self.offset = data.len();
+            }
+
+            /// `out` 的长度必须等于 `digest_len`。
+            pub fn finalize_into(mut self, out: &mut [u8]) {
+                assert_eq!(out.len(), self.digest_len);
+
+                self.counter = self.counter.wrapping_add(self.offset as $counter);
+                for byte in self.buffer[self.offset..].iter_mut() {
+                    *byte = 0;
+                }
+                Self::compress(&mut self.state, &self.buffer, self.counter, true);
+
+                let mut output = [0u8; Self::DIGEST_LEN];
+                for (chunk, word) in output.chunks_exact_mut(Self::WORD_LEN).zip(self.state.iter()) {
+                    chunk.copy_from_slice(&word.to_le_bytes());
+                }
+                out.copy_from_slice(&output[..self.digest_len]);
+            }
+        }
+    }
+}
+
+// NOTE: `Blake2b` 和 `Blake2s` 的摘要长度在运行时才确定，没有办法作为 `CryptoHasher` 的输出类型，
+//       固定摘要长度的版本（`Blake2b512`、`Blake2s256` 等）才实现 `CryptoHasher`。
+macro_rules! impl_blake2_fixed {
+    ($name:tt, $inner:tt, $digest_len:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            inner: $inner,
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.inner.zeroize();
+            }
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize  = $inner::BLOCK_LEN;
+            pub const DIGEST_LEN: usize = $digest_len;
+            pub const KEY_LEN: usize    = $inner::KEY_LEN;
+
+
+            pub fn new() -> Self {
+                Self { inner: $inner::with_params(Self::DIGEST_LEN, &[], &[], &[]) }
+            }
+
+            /// Keyed hashing (MAC) mode.
+            pub fn new_keyed(key: &[u8]) -> Self {
+                Self { inner: $inner::with_params(Self::DIGEST_LEN, key, &[], &[]) }
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.update(data);
+            }
+
+            pub fn finalize(self) -> [u8; Self::DIGEST_LEN] {
+                let mut output = [0u8; Self::DIGEST_LEN];
+                self.inner.finalize_into(&mut output);
+                output
+            }
+
+            pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+                let mut m = Self::new();
+                m.update(data.as_ref());
+                m.finalize()
+            }
+        }
+    }
+}
+
+// 2.1.  Parameters
+//                             | BLAKE2b          | BLAKE2s          |
+//               --------------+------------------+------------------+
+//                Bits in word | w = 64           | w = 32           |
+//                Rounds in F  | r = 12           | r = 10           |
+//                Block bytes  | bb = 128         | bb = 64          |
+//                Hash bytes   | 1 <= nn <= 64    | 1 <= nn <= 32    |
+//                Key bytes    | 0 <= kk <= 64    | 0 <= kk <= 32    |
+//                Input bytes  | 0 <= ll < 2**128 | 0 <= ll < 2**64  |
+//               --------------+------------------+------------------+
+//                G Rotation   | (R1, R2, R3, R4) | (R1, R2, R3, R4) |
+//                 constants = | (32, 24, 16, 63) | (16, 12,  8,  7) |
+//               --------------+------------------+------------------+
+impl_blake2!(Blake2b, u64, u128, BLAKE2B_IV, 12, 128, 64, 32, 24, 16, 63);
+impl_blake2!(Blake2s, u32, u64,  BLAKE2S_IV, 10,  64, 32, 16, 12,  8,  7);
+
+impl_blake2_fixed!(Blake2b224, Blake2b, 28);
+impl_blake2_fixed!(Blake2b256, Blake2b, 32);
+impl_blake2_fixed!(Blake2b384, Blake2b, 48);
+impl_blake2_fixed!(Blake2b512, Blake2b, 64);
+impl_blake2_fixed!(Blake2s128, Blake2s, 16);
+impl_blake2_fixed!(Blake2s160, Blake2s, 20);
+impl_blake2_fixed!(Blake2s224, Blake2s, 28);
+impl_blake2_fixed!(Blake2s256, Blake2s, 32);
+
+
+#[test]
+fn test_blake2b() {
+    // Appendix A.  Example of BLAKE2b Computation
+    assert_eq!(&hex::encode(&blake2b(b"abc")[..]), "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923");
+
+    // https://github.com/BLAKE2/BLAKE2/blob/master/testvectors/blake2b-kat.txt
+    let key = (0u8..64).collect::<Vec<u8>>();
+    let msg = (0u8..255).collect::<Vec<u8>>();
+    let mut m = Blake2b512::new_keyed(&key);
+    m.update(&msg);
+    assert_eq!(&hex::encode(&m.finalize()[..]), "142709d62e28fcccd0af97fad0f8465b971e82201dc51070faa0372aa43e9248\
+4be1c1e73ba10906d5d1853db6a4106e0a7bf9800d373d6dee2d46d62ef2a461");
+
+    let mut m = Blake2b::with_params(32, &[], &[], &[]);
+    m.update(b"");
+    let mut digest = [0u8; 32];
+    m.finalize_into(&mut digest);
+    assert_eq!(&hex::encode(&digest), "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8");
+    assert_eq!(&hex::encode(&Blake2b256::oneshot(b"")), "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8");
+}
+
+#[test]
+fn test_blake2s() {
+    // Appendix B.  Example of BLAKE2s Computation
+    assert_eq!(&hex::encode(&blake2s(b"abc")), "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982");
+
+    // https://github.com/BLAKE2/BLAKE2/blob/master/testvectors/blake2s-kat.txt
+    let key = (0u8..32).collect::<Vec<u8>>();
+    let msg = (0u8..255).collect::<Vec<u8>>();
+    let mut m = Blake2s256::new_keyed(&key);
+    m.update(&msg);
+    assert_eq!(&hex::encode(&m.finalize()), "3fb735061abc519dfe979e54c1ee5bfad0a9d858b3315bad34bde999efd724dd");
+
+    assert_eq!(&hex::encode(&Blake2s128::oneshot(b"")), "64550d6ffe2c0a01a14aba1eade0200c");
+}
+
+#[test]
+fn test_blake2_salt_persona() {
+    let mut m = Blake2b::with_params(32, b"secret", b"saltsaltsaltsalt", b"personalization!");
+    m.update(b"hello");
+    let mut digest = [0u8; 32];
+    m.finalize_into(&mut digest);
+    assert_eq!(&hex::encode(&digest), "040d1bd64794e160da2caa786ad7a4323d4647c6a11d81007f663624d62f8c8a");
+
+    let mut m = Blake2s::with_params(20, b"secret", b"salt", b"me");
+    m.update(b"hello");
+    let mut digest = [0u8; 20];
+    m.finalize_into(&mut digest);
+    assert_eq!(&hex::encode(&digest), "55aa559f5afc7c6ea7aba475e49fdd1f9208279e");
+}
+
+#[test]
+fn test_blake2_selftest() {
+    // Appendix E.  BLAKE2b and BLAKE2s Self-Test Module C Source
+    fn selftest_seq(out: &mut [u8], seed: u32) {
+        let mut a = 0xDEAD4BADu32.wrapping_mul(seed);
+        let mut b = 1u32;
+        for byte in out.iter_mut() {
+            let t = a.wrapping_add(b);
+            a = b;
+            b = t;
+            *byte = (t >> 24) as u8;
+        }
+    }
+
+    let mut input = [0u8; 1024];
+    let mut key = [0u8; 64];
+    let mut out = [0u8; 64];
+
+    let mut ctx = Blake2b::with_params(32, &[], &[], &[]);
+    for &outlen in [20usize, 32, 48, 64].iter() {
+        for &inlen in [0usize, 3, 128, 129, 255, 1024].iter() {
+            selftest_seq(&mut input[..inlen], inlen as u32);
+            let mut m = Blake2b::with_params(outlen, &[], &[], &[]);
+            m.update(&input[..inlen]);
+            m.finalize_into(&mut out[..outlen]);
+            ctx.update(&out[..outlen]);
+
+            selftest_seq(&mut key[..outlen], outlen as u32);
+            let mut m = Blake2b::with_params(outlen, &key[..outlen], &[], &[]);
+            m.update(&input[..inlen]);
+            m.finalize_into(&mut out[..outlen]);
+            ctx.update(&out[..outlen]);
+        }
+    }
+    ctx.finalize_into(&mut out[..32]);
+    assert_eq!(&hex::encode(&out[..32]), "c23a7800d98123bd10f506c61e29da5603d763b8bbad2e737f5e765a7bccd475");
+
+    let mut ctx = Blake2s::with_params(32, &[], &[], &[]);
+    for &outlen in [16usize, 20, 28, 32].iter() {
+        for &inlen in [0usize, 3, 64, 65, 255, 1024].iter() {
+            selftest_seq(&mut input[..inlen], inlen as u32);
+            let mut m = Blake2s::with_params(outlen, &[], &[], &[]);
+            m.update(&input[..inlen]);
+            m.finalize_into(&mut out[..outlen]);
+            ctx.update(&out[..outlen]);
+
+            selftest_seq(&mut key[..outlen], outlen as u32);
+            let mut m = Blake2s::with_params(outlen, &key[..outlen], &[], &[]);
+            m.update(&input[..inlen]);
+            m.finalize_into(&mut out[..outlen]);
+            ctx.update(&out[..outlen]);
+        }
+    }
+    ctx.finalize_into(&mut out[..32]);
+    assert_eq!(&hex::encode(&out[..32]), "6a411f08ce25adcdfb02aba641451cec53c598b24f4fc787fbdc88797f4c1dfe");
+}
+
+#[test]
+fn test_blake2b_incremental() {
+    // 按不同的分块大小输入，结果应当和一次性输入相同（包括恰好为分组长度整数倍的情况）。
+    let msg = (0u8..=255).collect::<Vec<u8>>();
+    for chunk_size in 1..msg.len() {
+        let mut m = Blake2b512::new();
+        for chunk in msg.chunks(chunk_size) {
+            m.update(chunk);
+        }
+        assert_eq!(&m.finalize()[..], &blake2b(&msg)[..]);
+    }
+}
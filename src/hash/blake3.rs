@@ -0,0 +1,656 @@
+// BLAKE3: one function, fast everywhere
+// https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf
+//
+// Rust code
+// https://github.com/BLAKE3-team/BLAKE3/blob/master/reference_impl/reference_impl.rs
+use crate::mem::Zeroize;
+
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+const KEY_LEN: usize   = 32;
+const OUT_LEN: usize   = 32;
+
+// 2.1 Compression Function, Table 3: Admissible values for input d.
+const CHUNK_START: u32         = 1 << 0;
+const CHUNK_END: u32           = 1 << 1;
+const PARENT: u32              = 1 << 2;
+const ROOT: u32                = 1 << 3;
+const KEYED_HASH: u32          = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32  = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+// IV 同 SHA-256 的初始状态。
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A,
+    0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+// NOTE: 子树小于该长度时，拆分到多个线程的收益不足以抵消线程的创建开销。
+#[cfg(feature = "std")]
+const PARALLEL_MIN_LEN: usize = 128 * CHUNK_LEN;
+
+
+// The mixing function, G, which mixes either a column or a diagonal.
+#[inline]
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+#[inline]
+fn round(state: &mut [u32; 16], m: &[u32; 16]) {
+    // Mix the columns.
+    g(state, 0, 4,  8, 12, m[ 0], m[ 1]);
+    g(state, 1, 5,  9, 13, m[ 2], m[ 3]);
+    g(state, 2, 6, 10, 14, m[ 4], m[ 5]);
+    g(state, 3, 7, 11, 15, m[ 6], m[ 7]);
+    // Mix the diagonals.
+    g(state, 0, 5, 10, 15, m[ 8], m[ 9]);
+    g(state, 1, 6, 11, 12, m[10], m[11]);
+    g(state, 2, 7,  8, 13, m[12], m[13]);
+    g(state, 3, 4,  9, 14, m[14], m[15]);
+}
+
+#[inline]
+fn permute(m: &mut [u32; 16]) {
+    let mut permuted = [0u32; 16];
+    for i in 0..16 {
+        permuted[i] = m[MSG_PERMUTATION[i]];
+    }
+    *m = permuted;
+}
+
+fn compress(chaining_value: &[u32; 8], block_words: &[u32; 16], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut state = [
+        chaining_value[0], chaining_value[1], chaining_value[2], chaining_value[3],
+        chaining_value[4], chaining_value[5], chaining_value[6], chaining_value[7],
+        IV[0], IV[1], IV[2], IV[3],
+        counter as u32, (counter >> 32) as u32, block_len, flags,
+    ];
+    let mut block = *block_words;
+
+    for r in 0..7 {
+        round(&mut state, &block);
+        if r < 6 {
+            permute(&mut block);
+        }
+    }
+
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= chaining_value[i];
+    }
+
+    state
+}
+
+#[inline]
+fn first_8_words(compression_output: [u32; 16]) -> [u32; 8] {
+    let mut words = [0u32; 8];
+    words.copy_from_slice(&compression_output[..8]);
+    words
+}
+
+#[inline]
+fn words_from_le_bytes(bytes: &[u8], words: &mut [u32]) {
+    debug_assert_eq!(bytes.len(), 4 * words.len());
+
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+}
+
+
+// Each chunk or parent node can produce either an 8-word chaining value or, by
+// setting the ROOT flag, any number of final output bytes. The Output struct
+// captures the state just prior to choosing between those two possibilities.
+#[derive(Clone)]
+struct Output {
+    input_chaining_value: [u32; 8],
+    block_words: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8_words(compress(
+            &self.input_chaining_value,
+            &self.block_words,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    fn root_output_block(&self, output_block_counter: u64) -> [u8; BLOCK_LEN] {
+        let words = compress(
+            &self.input_chaining_value,
+            &self.block_words,
+            output_block_counter,
+            self.block_len,
+            self.flags | ROOT,
+        );
+
+        let mut block = [0u8; BLOCK_LEN];
+        for (chunk, word) in block.chunks_exact_mut(4).zip(words.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        block
+    }
+}
+
+
+#[derive(Clone)]
+struct ChunkState {
+    chaining_value: [u32; 8],
+    chunk_counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: u8,
+    blocks_compressed: u8,
+    flags: u32,
+}
+
+impl Zeroize for ChunkState {
+    fn zeroize(&mut self) {
+        self.chaining_value.zeroize();
+        self.chunk_counter.zeroize();
+        self.block.zeroize();
+        self.block_len.zeroize();
+        self.blocks_compressed.zeroize();
+    }
+}
+
+impl ChunkState {
+    fn new(key_words: [u32; 8], chunk_counter: u64, flags: u32) -> Self {
+        Self {
+            chaining_value: key_words,
+            chunk_counter,
+            block: [0u8; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed as usize + self.block_len as usize
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 { CHUNK_START } else { 0 }
+    }
+
+    fn update(&mut self, input: &[u8]) {
+        let mut input = input;
+
+        while !input.is_empty() {
+            // If the block buffer is full, compress it and clear it. More
+            // input is coming, so this compression is not CHUNK_END.
+            if self.block_len as usize == BLOCK_LEN {
+                let mut block_words = [0u32; 16];
+                words_from_le_bytes(&self.block, &mut block_words);
+                self.chaining_value = first_8_words(compress(
+                    &self.chaining_value,
+                    &block_words,
+                    self.chunk_counter,
+                    BLOCK_LEN as u32,
+                    self.flags | self.start_flag(),
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0u8; BLOCK_LEN];
+                self.block_len = 0;
+            }
+
+            let want = BLOCK_LEN - self.block_len as usize;
+            let take = core::cmp::min(want, input.len());
+            let offset = self.block_len as usize;
+            self.block[offset..offset + take].copy_from_slice(&input[..take]);
+            self.block_len += take as u8;
+            input = &input[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        let mut block_words = [0u32; 16];
+        words_from_le_bytes(&self.block, &mut block_words);
+
+        Output {
+            input_chaining_value: self.chaining_value,
+            block_words,
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+fn parent_output(left_child_cv: [u32; 8], right_child_cv: [u32; 8], key_words: [u32; 8], flags: u32) -> Output {
+    let mut block_words = [0u32; 16];
+    block_words[..8].copy_from_slice(&left_child_cv);
+    block_words[8..].copy_from_slice(&right_child_cv);
+
+    Output {
+        input_chaining_value: key_words,
+        block_words,
+        counter: 0,                  // Always 0 for parent nodes.
+        block_len: BLOCK_LEN as u32, // Always BLOCK_LEN (64) for parent nodes.
+        flags: PARENT | flags,
+    }
+}
+
+fn parent_cv(left_child_cv: [u32; 8], right_child_cv: [u32; 8], key_words: [u32; 8], flags: u32) -> [u32; 8] {
+    parent_output(left_child_cv, right_child_cv, key_words, flags).chaining_value()
+}
+
+// 计算一棵完整子树的 chaining value，子树包含的 chunk 数必须为 2 的幂。
+// `threads` 为可以使用的线程数，左右子树分别分得一半。
+#[cfg(feature = "std")]
+fn subtree_cv(input: &[u8], chunk_counter: u64, key_words: [u32; 8], flags: u32, threads: usize) -> [u32; 8] {
+    debug_assert_eq!(input.len() % CHUNK_LEN, 0);
+    debug_assert!((input.len() / CHUNK_LEN).is_power_of_two());
+
+    if input.len() == CHUNK_LEN {
+        let mut chunk_state = ChunkState::new(key_words, chunk_counter, flags);
+        chunk_state.update(input);
+        return chunk_state.output().chaining_value();
+    }
+
+    let (left, right) = input.split_at(input.len() / 2);
+    let right_counter = chunk_counter + (left.len() / CHUNK_LEN) as u64;
+
+    let (left_cv, right_cv) = if threads > 1 && input.len() >= PARALLEL_MIN_LEN {
+        std::thread::scope(|s| {
+            let left_threads = threads / 2;
+            let handle = s.spawn(move || subtree_cv(left, chunk_counter, key_words, flags, left_threads));
+            let right_cv = subtree_cv(right, right_counter, key_words, flags, threads - left_threads);
+            (handle.join().unwrap(), right_cv)
+        })
+    } else {
+        (
+            subtree_cv(left, chunk_counter, key_words, flags, 1),
+            subtree_cv(right, right_counter, key_words, flags, 1),
+        )
+    };
+
+    parent_cv(left_cv, right_cv, key_words, flags)
+}
+
+
+/// BLAKE3 hash
+pub fn blake3<T: AsRef<[u8]>>(data: T) -> [u8; Blake3::DIGEST_LEN] {
+    Blake3::oneshot(data)
+}
+
+/// BLAKE3 keyed_hash
+pub fn blake3_keyed_hash<T: AsRef<[u8]>>(key: &[u8; Blake3::KEY_LEN], data: T) -> [u8; Blake3::DIGEST_LEN] {
+    let mut m = Blake3::new_keyed(key);
+    m.update(data.as_ref());
+    m.finalize()
+}
+
+/// BLAKE3 derive_key
+pub fn blake3_derive_key(context: &str, key_material: &[u8], output: &mut [u8]) {
+    let mut m = Blake3::new_derive_key(context);
+    m.update(key_material);
+    m.finalize_xof().read(output);
+}
+
+
+/// The output stream of BLAKE3.
+#[derive(Clone)]
+pub struct Blake3Reader {
+    inner: Output,
+    block: [u8; BLOCK_LEN],
+    counter: u64,
+    offset: usize,
+}
+
+impl Blake3Reader {
+    fn new(inner: Output) -> Self {
+        Self { inner, block: [0u8; BLOCK_LEN], counter: 0, offset: BLOCK_LEN }
+    }
+
+    /// Fills `output` with the next `output.len()` bytes of the output stream.
+    pub fn read(&mut self, output: &mut [u8]) {
+        let mut output = output;
+
+        while !output.is_empty() {
+            if self.offset == BLOCK_LEN {
+                self.block = self.inner.root_output_block(self.counter);
+                self.counter += 1;
+                self.offset = 0;
+            }
+
+            let n = core::cmp::min(BLOCK_LEN - self.offset, output.len());
+            output[..n].copy_from_slice(&self.block[self.offset..self.offset + n]);
+            self.offset += n;
+            output = &mut output[n..];
+        }
+    }
+}
+
+
+#[derive(Clone)]
+pub struct Blake3 {
+    chunk_state: ChunkState,
+    key_words: [u32; 8],
+    cv_stack: [[u32; 8]; 54], // Space for 54 subtree chaining values:
+    cv_stack_len: u8,         // 2^54 * CHUNK_LEN = 2^64
+    flags: u32,
+}
+
+impl Zeroize for Blake3 {
+    fn zeroize(&mut self) {
+        self.chunk_state.zeroize();
+        self.key_words.zeroize();
+        self.cv_stack.zeroize();
+        self.cv_stack_len.zeroize();
+    }
+}
+
+impl Drop for Blake3 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Blake3 {
+    pub const BLOCK_LEN: usize  = BLOCK_LEN;
+    pub const CHUNK_LEN: usize  = CHUNK_LEN;
+    pub const KEY_LEN: usize    = KEY_LEN;
+    pub const DIGEST_LEN: usize = OUT_LEN;
+
+
+    fn with_key_words(key_words: [u32; 8], flags: u32) -> Self {
+        Self {
+            chunk_state: ChunkState::new(key_words, 0, flags),
+            key_words,
+            cv_stack: [[0u32; 8]; 54],
+            cv_stack_len: 0,
+            flags,
+        }
+    }
+
+    pub fn new() -> Self {
+        Self::with_key_words(IV, 0)
+    }
+
+    /// The `keyed_hash` mode.
+    pub fn new_keyed(key: &[u8; Self::KEY_LEN]) -> Self {
+        let mut key_words = [0u32; 8];
+        words_from_le_bytes(key, &mut key_words);
+
+        Self::with_key_words(key_words, KEYED_HASH)
+    }
+
+    /// The `derive_key` mode, `context` 应当是硬编码的、全局唯一的且与应用相关的字符串。
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut context_hasher = Self::with_key_words(IV, DERIVE_KEY_CONTEXT);
+        context_hasher.update(context.as_bytes());
+
+        let mut context_key = [0u8; KEY_LEN];
+        context_hasher.finalize_xof().read(&mut context_key);
+
+        let mut key_words = [0u32; 8];
+        words_from_le_bytes(&context_key, &mut key_words);
+
+        Self::with_key_words(key_words, DERIVE_KEY_MATERIAL)
+    }
+
+    fn push_stack(&mut self, cv: [u32; 8]) {
+        self.cv_stack[self.cv_stack_len as usize] = cv;
+        self.cv_stack_len += 1;
+    }
+
+    fn pop_stack(&mut self) -> [u32; 8] {
+        self.cv_stack_len -= 1;
+        self.cv_stack[self.cv_stack_len as usize]
+    }
+
+    // Section 5.1.2 of the BLAKE3 spec explains this algorithm in more detail.
+    fn add_chunk_chaining_value(&mut self, new_cv: [u32; 8], total_chunks: u64) {
+        // This chunk might complete some subtrees. For each completed subtree,
+        // its left child will be the current top entry in the CV stack, and
+        // its right child will be the current value of `new_cv`. Pop each left
+        // child off the stack, merge it with `new_cv`, and overwrite `new_cv`
+        // with the result. After all these merges, push the final value of
+        // `new_cv` onto the stack. The number of completed subtrees is given
+        // by the number of trailing 0-bits in the new total number of chunks.
+        let mut new_cv = new_cv;
+        let mut total_chunks = total_chunks;
+
+        while total_chunks & 1 == 0 {
+            new_cv = parent_cv(self.pop_stack(), new_cv, self.key_words, self.flags);
+            total_chunks >>= 1;
+        }
+
+        self.push_stack(new_cv);
+    }
+
+    // 当前的 chunk 已满且后面还有数据时，该 chunk 一定不是根节点。
+    fn commit_chunk(&mut self) {
+        debug_assert_eq!(self.chunk_state.len(), CHUNK_LEN);
+
+        let chunk_cv = self.chunk_state.output().chaining_value();
+        let total_chunks = self.chunk_state.chunk_counter + 1;
+        self.add_chunk_chaining_value(chunk_cv, total_chunks);
+        self.chunk_state = ChunkState::new(self.key_words, total_chunks, self.flags);
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        while !data.is_empty() {
+            if self.chunk_state.len() == CHUNK_LEN {
+                self.commit_chunk();
+            }
+
+            let want = CHUNK_LEN - self.chunk_state.len();
+            let take = core::cmp::min(want, data.len());
+            self.chunk_state.update(&data[..take]);
+            data = &data[take..];
+        }
+    }
+
+    /// 结果与 `update` 相同。较大的输入会按照 chunk tree 划分为若干棵完整的子树，
+    /// 子树的左右两半分别交由不同的线程计算。
+    #[cfg(feature = "std")]
+    pub fn update_parallel(&mut self, data: &[u8]) {
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        let mut data = data;
+
+        // 先补齐当前的 chunk，使后面的子树从 chunk 边界开始。
+        let len = self.chunk_state.len();
+        if len > 0 && len < CHUNK_LEN {
+            let take = core::cmp::min(CHUNK_LEN - len, data.len());
+            self.chunk_state.update(&data[..take]);
+            data = &data[take..];
+        }
+
+        // NOTE: 最后至少要留下一个字节交给 `chunk_state`，因为只有它可能是根节点。
+        while data.len() > CHUNK_LEN {
+            if self.chunk_state.len() == CHUNK_LEN {
+                self.commit_chunk();
+            }
+
+            // 子树包含的 chunk 数必须为 2 的幂，并且与当前的 chunk 计数对齐。
+            let chunk_counter = self.chunk_state.chunk_counter;
+            let max_chunks = (data.len() - 1) / CHUNK_LEN;
+            let mut chunks = 1usize << (usize::BITS - 1 - max_chunks.leading_zeros());
+            while chunk_counter % chunks as u64 != 0 {
+                chunks /= 2;
+            }
+
+            let (subtree, rem) = data.split_at(chunks * CHUNK_LEN);
+            let cv = subtree_cv(subtree, chunk_counter, self.key_words, self.flags, threads);
+
+            // 大小为 2^k 个 chunk 的子树相当于第 k 层上的一个节点。
+            let total_chunks = chunk_counter + chunks as u64;
+            self.add_chunk_chaining_value(cv, total_chunks >> chunks.trailing_zeros());
+            self.chunk_state = ChunkState::new(self.key_words, total_chunks, self.flags);
+
+            data = rem;
+        }
+
+        self.update(data);
+    }
+
+    fn final_output(&self) -> Output {
+        // Starting with the Output from the current chunk, compute all the
+        // parent chaining values along the right edge of the tree, until we
+        // have the root Output.
+        let mut output = self.chunk_state.output();
+        let mut parent_nodes_remaining = self.cv_stack_len as usize;
+
+        while parent_nodes_remaining > 0 {
+            parent_nodes_remaining -= 1;
+            output = parent_output(
+                self.cv_stack[parent_nodes_remaining],
+                output.chaining_value(),
+                self.key_words,
+                self.flags,
+            );
+        }
+
+        output
+    }
+
+    pub fn finalize_xof(self) -> Blake3Reader {
+        Blake3Reader::new(self.final_output())
+    }
+
+    pub fn finalize(self) -> [u8; Self::DIGEST_LEN] {
+        let mut output = [0u8; Self::DIGEST_LEN];
+        self.finalize_xof().read(&mut output);
+        output
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize()
+    }
+}
+
+
+#[cfg(test)]
+fn test_input(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn test_blake3() {
+    // https://github.com/BLAKE3-team/BLAKE3/blob/master/test_vectors/test_vectors.json
+    let key = b"whats the Elephant and Crocodile";
+    let context = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+    // (input_len, hash, keyed_hash, derive_key)
+    let suites: &[(usize, &str, &str, &str)] = &[
+        (0, "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+            "caccf49867850164528ead4954b723c92bc4587be32c7bb48067495af4b98b94",
+            "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d"),
+        (1, "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+            "3c7b65f34140398bd6c699a2a708eeff3e822de7a0c88dbf21e1a188dcb133e8",
+            "b3e2e340a117a499c6cf2398a19ee0d29cca2bb7404c73063382693bf66cb06c"),
+        (1023, "10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11",
+            "3c03056a6293424364d1f43e82583e0f017e41237dc84805db3550ffd4373a85",
+            "74a16c1c3d44368a86e1ca6df64be6a2f64cce8f09220787450722d85725dea5"),
+        (1024, "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+            "3645958c1a889639863f5e8174a06d7aa661de56996f5e2a4a6fa4ccd7f45ab1",
+            "7356cd7720d5b66b6d0697eb3177d9f8d73a4a5c5e968896eb6a689684302706"),
+        (1025, "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+            "6232c34f4876399f817b3dfafa2a09921e4debb32c63d2cf043b16fd8138d04d",
+            "effaa245f065fbf82ac186839a249707c3bddf6d3fdda22d1b95a3c970379bcb"),
+        (2048, "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a",
+            "a8575dcd9c15925d10379f128aae970ff6b0f814504bf85ea1244a8cb534969e",
+            "7b2945cb4fef70885cc5d78a87bf6f6207dd901ff239201351ffac04e1088a23"),
+        (2049, "5f4d72f40d7a5f82b15ca2b2e44b1de3c2ef86c426c95c1af0b6879522563030",
+            "01878885a6c1a5fe2ef16c7664ba6e37b3a9ae28b813a486060eb522dbd15622",
+            "2ea477c5515cc3dd606512ee72bb3e0e758cfae7232826f35fb98ca1bcbdf273"),
+        (3072, "b98cb0ff3623be03326b373de6b9095218513e64f1ee2edd2525c7ad1e5cffd2",
+            "0ce5e20888513f93a6e3fea0bac8d05ba178372bf96a1b41bb9605b2723a224d",
+            "050df97f8c2ead654d9bb3ab8c9178edcd902a32f8495949feadcc1e0480c46b"),
+        (3073, "7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3",
+            "4f7c2ece3e27ad586b704cac4787d2c13d9ad450fd64d63298f89c8a72232275",
+            "72613c9ec9ff7e40f8f5c173784c532ad852e827dba2bf85b2ab4b76f7079081"),
+        (4097, "9b4052b38f1c5fc8b1f9ff7ac7b27cd242487b3d890d15c96a1c25b8aa0fb995",
+            "66096cae3e8f5357f8c24c0cfd3cb541a018fac7bf446b87a5c1b2fce5ec8931",
+            "aca51029626b55fda7117b42a7c211f8c6e9ba4fe5b7a8ca922f34299500ead8"),
+        (8193, "bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3b",
+            "9c470187c5b79648264c936073d5e7c6124197461b8845795c6c9354be3ca906",
+            "af1e0346e389b17c23200270a64aa4e1ead98c61695d917de7d5b00491c9b0f1"),
+        (31744, "62b6960e1a44bcc1eb1a611a8d6235b6b4b78f32e7abc4fb4c6cdcce94895c47",
+            "2eb860a3d98797006a421c1c735bc7097043a1ba83da334b54a6ccb9bd800a08",
+            "39772aef80e0ebe60596361e45b061e8f417429d529171b6764468c22928e28e"),
+        (102400, "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085",
+            "9988b58dda41d27b4a0015b292f37847352561dd7db5c6853df3606b9442eceb",
+            "4652cff7a3f385a6103b5c260fc1593e13c778dbe608efb092fe7ee69df6e9c6"),
+    ];
+    for (len, hash, keyed_hash, derive_key) in suites.iter() {
+        let input = test_input(*len);
+        assert_eq!(&hex::encode(&blake3(&input)), hash);
+        assert_eq!(&hex::encode(&blake3_keyed_hash(key, &input)), keyed_hash);
+
+        let mut output = [0u8; 32];
+        blake3_derive_key(context, &input, &mut output);
+        assert_eq!(&hex::encode(&output), derive_key);
+    }
+}
+
+#[test]
+fn test_blake3_xof() {
+    let input = test_input(1025);
+    let mut m = Blake3::new();
+    m.update(&input);
+    let mut expected = [0u8; 131];
+    m.finalize_xof().read(&mut expected);
+    assert_eq!(&hex::encode(&expected[..]), "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444f4c4a22b4b399155358a994e52bf255de60035742ec71bd08ac275a1b51cc6bf\
+e332b0ef84b409108cda080e6269ed4b3e2c3f7d722aa4cdc98d16deb554e5627be8f955c98e1d5f9565a9194cad0c4285f93700062d9595adb992ae68ff12800ab67a");
+
+    // 分多次读取的输出应当和一次性读取的输出相同。
+    for chunk_size in &[1usize, 7, 64, 100] {
+        let mut m = Blake3::new();
+        m.update(&input);
+
+        let mut reader = m.finalize_xof();
+        let mut output = [0u8; 131];
+        for chunk in output.chunks_mut(*chunk_size) {
+            reader.read(chunk);
+        }
+        assert_eq!(&output[..], &expected[..]);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_blake3_parallel() {
+    // 并行模式的结果应当和串行模式相同，包括从 chunk 中间开始的情况。
+    let input = test_input(1024 * 1024 + 3 * CHUNK_LEN + 17);
+    for prefix_len in &[0usize, 1, CHUNK_LEN, 3 * CHUNK_LEN + 5] {
+        let mut m1 = Blake3::new();
+        m1.update(&input[..*prefix_len]);
+        m1.update(&input[*prefix_len..]);
+
+        let mut m2 = Blake3::new();
+        m2.update(&input[..*prefix_len]);
+        m2.update_parallel(&input[*prefix_len..]);
+
+        assert_eq!(m1.finalize(), m2.finalize());
+    }
+
+    let input = test_input(102400);
+    let mut m = Blake3::new();
+    m.update_parallel(&input);
+    assert_eq!(&hex::encode(&m.finalize()), "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085");
+}
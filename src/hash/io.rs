@@ -0,0 +1,107 @@
+use super::{ CryptoHasher, BuildCryptoHasher, };
+
+use std::io::{ self, Read, Write, };
+use std::path::Path;
+use std::fs::File;
+
+
+/// 在读取数据的同时，把读到的数据写入 `H`（Hasher 或者 MAC）。
+pub struct HashingReader<R, H> {
+    inner: R,
+    hasher: H,
+}
+
+impl<R: Read, H: Write> HashingReader<R, H> {
+    pub fn new(inner: R, hasher: H) -> Self {
+        Self { inner, hasher }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    pub fn into_inner(self) -> (R, H) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<R: Read, H: Write + CryptoHasher> HashingReader<R, H> {
+    pub fn digest(self) -> H::Output {
+        self.hasher.digest()
+    }
+}
+
+impl<R: Read, H: Write> Read for HashingReader<R, H> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.write_all(&buf[..n])?;
+        Ok(n)
+    }
+}
+
+
+/// 计算文件的摘要，例如：`hash_file::<Sha256, _>("Cargo.toml")`。
+pub fn hash_file<H, P>(path: P) -> io::Result<H::Output>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Write,
+    P: AsRef<Path>,
+{
+    let mut file = File::open(path)?;
+    let mut hasher = H::build_hasher();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(hasher.digest())
+}
+
+
+#[test]
+fn test_io_write() {
+    use super::{ Sha256, sha256, };
+
+    let data = (0..10000usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let mut hasher = Sha256::new();
+    io::copy(&mut &data[..], &mut hasher).unwrap();
+    assert_eq!(hasher.finalize(), sha256(&data));
+}
+
+#[test]
+fn test_hashing_reader() {
+    use super::{ Sha256, sha256, };
+    use crate::mac::HmacSha256;
+
+    let data = (0..10000usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let mut reader = HashingReader::new(&data[..], Sha256::new());
+    let mut output = Vec::new();
+    reader.read_to_end(&mut output).unwrap();
+    assert_eq!(&output, &data);
+    assert_eq!(reader.digest(), sha256(&data));
+
+    let mut reader = HashingReader::new(&data[..], HmacSha256::new(b"key"));
+    io::copy(&mut reader, &mut io::sink()).unwrap();
+    let (_, mac) = reader.into_inner();
+    assert_eq!(mac.finalize(), HmacSha256::oneshot(b"key", &data));
+}
+
+#[test]
+fn test_hash_file() {
+    use super::{ Sha256, sha256, };
+
+    let data = (0..10000usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let path = std::env::temp_dir().join(format!("crypto-hash-file-{}", std::process::id()));
+    std::fs::write(&path, &data).unwrap();
+    let digest = hash_file::<Sha256, _>(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(digest.unwrap(), sha256(&data));
+}
@@ -1,5 +1,7 @@
 // The MD2 Message-Digest Algorithm
 // https://tools.ietf.org/html/rfc1319
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
 
 
 // The S-table's values are derived from Pi
@@ -75,6 +77,20 @@ pub struct Md2 {
     offset: usize,
 }
 
+impl Zeroize for Md2 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.offset.zeroize();
+    }
+}
+
+impl Drop for Md2 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 impl Md2 {
     pub const BLOCK_LEN: usize  = 16;
     pub const DIGEST_LEN: usize = 16;
@@ -125,6 +141,42 @@ impl Md2 {
         m.update(data.as_ref());
         m.finalize()
     }
+
+    /// Length of the exported state: algorithm id || version || state || checksum || buffer offset || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 16 + 16 + 1 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Md2);
+        // NOTE: `state[16..48]` 只是 `transform` 的临时空间，不需要导出。
+        output[..16].copy_from_slice(&self.state[..16]);
+        output[16..32].copy_from_slice(&self.state[48..]);
+        output[32] = self.offset as u8;
+
+        let n = self.offset;
+        output[33..33 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Md2)?;
+
+        let n = state[32] as usize;
+        if n >= Self::BLOCK_LEN || state[33 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+
+        let mut m = Self::new();
+        m.state[..16].copy_from_slice(&state[..16]);
+        m.state[48..].copy_from_slice(&state[16..32]);
+        m.offset = n;
+        m.buffer[..n].copy_from_slice(&state[33..33 + n]);
+
+        Some(m)
+    }
 }
 
 
@@ -147,3 +199,21 @@ fn test_md2() {
     assert_eq!(&md2("12345678901234567890123456789012345678901234567890123456789012345678901234567890"),
         &hex::decode("d5976f79d83d3a0dc9806c3c66f3efd8").unwrap()[..]);
 }
+
+#[test]
+fn test_md2_export_import_state() {
+    let msg = (0..100usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Md2::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Md2::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), md2(&msg));
+    }
+
+    let mut bad = Md2::new().export_state();
+    bad[2 + 32] = 16;
+    assert!(Md2::import_state(&bad).is_none());
+}
@@ -1,6 +1,8 @@
 // The MD4 Message-Digest Algorithm
 // https://tools.ietf.org/html/rfc1320
 use core::convert::TryFrom;
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
 
 
 const INITIAL_STATE: [u32; 4] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
@@ -17,6 +19,20 @@ pub struct Md4 {
     len: usize,      // in bytes.
 }
 
+impl Zeroize for Md4 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Md4 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 impl Md4 {
     pub const BLOCK_LEN: usize  = 64;
     pub const DIGEST_LEN: usize = 16;
@@ -56,10 +72,7 @@ impl Md4 {
             } else {
                 transform(&mut self.state, &self.buffer);
 
-                let data = &data[i..];
-                if data.len() > 0 {
-                    return self.update(data);
-                }
+                return self.update(&data[i..]);
             }
         }
 
@@ -124,6 +137,46 @@ impl Md4 {
         m.update(data.as_ref());
         m.finalize()
     }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 16 + 8 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in little-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Md4);
+        for (chunk, word) in output[..16].chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output[16..24].copy_from_slice(&(self.len as u64).to_le_bytes());
+
+        let n = self.len % Self::BLOCK_LEN;
+        output[24..24 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Md4)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..16].chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&state[16..24]);
+        m.len = usize::try_from(u64::from_le_bytes(len)).ok()?;
+
+        let n = m.len % Self::BLOCK_LEN;
+        if state[24 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[24..24 + n]);
+
+        Some(m)
+    }
 }
 
 
@@ -231,4 +284,29 @@ fn test_md4() {
         &hex::decode("043f8582f241db351ce627e153e7f0e4").unwrap()[..]);
     assert_eq!(&md4("12345678901234567890123456789012345678901234567890123456789012345678901234567890"),
         &hex::decode("e33b4ddc9c38f2199c3e7b164fcc0536").unwrap()[..]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_md4_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Md4::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Md4::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), md4(&msg));
+    }
+}
+
+#[test]
+fn test_md4_bytewise_update() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let mut m = Md4::new();
+    for byte in msg.chunks(1) {
+        m.update(byte);
+    }
+    assert_eq!(m.finalize(), md4(&msg));
+}
@@ -6,6 +6,8 @@
 // ❗️ MD5算法在1996年后被证实存在弱点，可以被加以破解。
 // ‼️ MD5算法在2004年被证实无法防止碰撞攻击，因此不适用于安全性认证。
 use core::convert::TryFrom;
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
 
 // Use binary integer part of the sines of integers (Radians) as constants:
 //    for i from 0 to 63 do
@@ -76,6 +78,20 @@ pub struct Md5 {
     len: usize,      // in bytes.
 }
 
+impl Zeroize for Md5 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Md5 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 impl Md5 {
     pub const BLOCK_LEN: usize  = 64;
     pub const DIGEST_LEN: usize = 16;
@@ -115,10 +131,7 @@ impl Md5 {
             } else {
                 transform(&mut self.state, &self.buffer);
 
-                let data = &data[i..];
-                if data.len() > 0 {
-                    return self.update(data);
-                }
+                return self.update(&data[i..]);
             }
         }
 
@@ -182,6 +195,46 @@ impl Md5 {
         m.update(data.as_ref());
         m.finalize()
     }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 16 + 8 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in little-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Md5);
+        for (chunk, word) in output[..16].chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output[16..24].copy_from_slice(&(self.len as u64).to_le_bytes());
+
+        let n = self.len % Self::BLOCK_LEN;
+        output[24..24 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Md5)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..16].chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&state[16..24]);
+        m.len = usize::try_from(u64::from_le_bytes(len)).ok()?;
+
+        let n = m.len % Self::BLOCK_LEN;
+        if state[24 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[24..24 + n]);
+
+        Some(m)
+    }
 }
 
 
@@ -447,4 +500,29 @@ fn test_md5_long_message() {
     let msg = vec![b'a'; 1000_000];
     let digest = [119, 7, 214, 174, 78, 2, 124, 112, 238, 162, 169, 53, 194, 41, 111, 33];
     assert_eq!(Md5::oneshot(&msg), digest);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_md5_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Md5::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Md5::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), md5(&msg));
+    }
+}
+
+#[test]
+fn test_md5_bytewise_update() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let mut m = Md5::new();
+    for byte in msg.chunks(1) {
+        m.update(byte);
+    }
+    assert_eq!(m.finalize(), md5(&msg));
+}
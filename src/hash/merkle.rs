@@ -0,0 +1,432 @@
+// Certificate Transparency: 2.1.  Merkle Hash Trees
+// https://tools.ietf.org/html/rfc6962#section-2.1
+//
+// Certificate Transparency Version 2.0: 2.1.3.2 / 2.1.4.2 Verifying Proofs
+// https://tools.ietf.org/html/rfc9162#section-2.1.3.2
+//
+// NOTE: 叶子节点和中间节点分别使用 0x00 和 0x01 作为前缀（Domain Separation），
+//       这样，叶子节点的哈希值不会和中间节点的哈希值发生混淆（Second Preimage Attack）。
+use super::{ Array, CryptoHasher, BuildCryptoHasher, };
+
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+
+/// MTH({}) = HASH()
+pub fn empty_root<H>() -> H::Output
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H>,
+{
+    H::build_hasher().digest()
+}
+
+/// MTH({d(0)}) = HASH(0x00 || d(0))
+pub fn leaf_hash<H>(data: &[u8]) -> H::Output
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H>,
+{
+    let mut hasher = H::build_hasher();
+    hasher.write([LEAF_PREFIX]);
+    hasher.write(data);
+    hasher.digest()
+}
+
+/// HASH(0x01 || left || right)
+pub fn node_hash<H>(left: &H::Output, right: &H::Output) -> H::Output
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H>,
+{
+    let mut hasher = H::build_hasher();
+    hasher.write([NODE_PREFIX]);
+    hasher.write(left.array_as_slice());
+    hasher.write(right.array_as_slice());
+    hasher.digest()
+}
+
+#[inline]
+fn hash_eq<H: CryptoHasher>(a: &H::Output, b: &H::Output) -> bool {
+    a.array_as_slice() == b.array_as_slice()
+}
+
+// 小于 n 的最大的 2 的幂 (n > 1)
+#[inline]
+fn split_point(n: usize) -> usize {
+    debug_assert!(n > 1);
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+
+/// RFC 6962 Merkle Hash Tree，只允许追加（Append-only）。
+pub struct MerkleTree<H: CryptoHasher> {
+    // levels[i][j]: 从第 j * 2^i 个叶子节点开始，包含 2^i 个叶子节点的完整子树的哈希值。
+    levels: Vec<Vec<H::Output>>,
+}
+
+impl<H> MerkleTree<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H>,
+    H::Output: Clone,
+{
+    pub fn new() -> Self {
+        Self { levels: vec![Vec::new()] }
+    }
+
+    /// 叶子节点的数量
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// 第 `index` 个叶子节点的哈希值
+    pub fn leaf(&self, index: usize) -> Option<&H::Output> {
+        self.levels[0].get(index)
+    }
+
+    /// 追加数据，返回叶子节点的位置。
+    pub fn push<T: AsRef<[u8]>>(&mut self, data: T) -> usize {
+        self.push_leaf_hash(leaf_hash::<H>(data.as_ref()))
+    }
+
+    /// 追加叶子节点的哈希值（`leaf_hash`），返回叶子节点的位置。
+    pub fn push_leaf_hash(&mut self, hash: H::Output) -> usize {
+        let index = self.len();
+        self.levels[0].push(hash);
+
+        // 合并新出现的完整子树
+        let mut level = 0;
+        while self.levels[level].len() % 2 == 0 {
+            let len = self.levels[level].len();
+            let node = node_hash::<H>(&self.levels[level][len - 2], &self.levels[level][len - 1]);
+            if self.levels.len() == level + 1 {
+                self.levels.push(Vec::new());
+            }
+            self.levels[level + 1].push(node);
+            level += 1;
+        }
+
+        index
+    }
+
+    /// 当前的 Tree Head
+    pub fn root(&self) -> H::Output {
+        self.subtree(0, self.len())
+    }
+
+    /// 只包含前 `size` 个叶子节点时的 Tree Head
+    pub fn root_at(&self, size: usize) -> Option<H::Output> {
+        if size > self.len() {
+            return None;
+        }
+
+        Some(self.subtree(0, size))
+    }
+
+    /// 第 `index` 个叶子节点在包含前 `size` 个叶子节点的树里面的 Audit Path。
+    pub fn inclusion_proof(&self, index: usize, size: usize) -> Option<Vec<H::Output>> {
+        if size > self.len() || index >= size {
+            return None;
+        }
+
+        let mut proof = Vec::new();
+        self.path(index, 0, size, &mut proof);
+        Some(proof)
+    }
+
+    /// 包含前 `old_size` 个叶子节点的树和包含前 `new_size` 个叶子节点的树的 Consistency Proof。
+    pub fn consistency_proof(&self, old_size: usize, new_size: usize) -> Option<Vec<H::Output>> {
+        if old_size > new_size || new_size > self.len() {
+            return None;
+        }
+
+        let mut proof = Vec::new();
+        if old_size > 0 && old_size < new_size {
+            self.subproof(old_size, 0, new_size, true, &mut proof);
+        }
+        Some(proof)
+    }
+
+    // MTH(D[start:end])
+    fn subtree(&self, start: usize, end: usize) -> H::Output {
+        let n = end - start;
+        if n == 0 {
+            return empty_root::<H>();
+        }
+
+        // NOTE: 递归过程中，左边的子树总是完整并且对齐的。
+        if n.is_power_of_two() && start % n == 0 {
+            let level = n.trailing_zeros() as usize;
+            return self.levels[level][start >> level].clone();
+        }
+
+        let k = split_point(n);
+        node_hash::<H>(&self.subtree(start, start + k), &self.subtree(start + k, end))
+    }
+
+    // PATH(m, D[start:end])
+    fn path(&self, m: usize, start: usize, end: usize, proof: &mut Vec<H::Output>) {
+        let n = end - start;
+        if n <= 1 {
+            return;
+        }
+
+        let k = split_point(n);
+        if m < start + k {
+            self.path(m, start, start + k, proof);
+            proof.push(self.subtree(start + k, end));
+        } else {
+            self.path(m, start + k, end, proof);
+            proof.push(self.subtree(start, start + k));
+        }
+    }
+
+    // SUBPROOF(m, D[start:end], b)
+    fn subproof(&self, m: usize, start: usize, end: usize, b: bool, proof: &mut Vec<H::Output>) {
+        let n = end - start;
+        if m == n {
+            if !b {
+                proof.push(self.subtree(start, end));
+            }
+            return;
+        }
+
+        let k = split_point(n);
+        if m <= k {
+            self.subproof(m, start, start + k, b, proof);
+            proof.push(self.subtree(start + k, end));
+        } else {
+            self.subproof(m - k, start + k, end, false, proof);
+            proof.push(self.subtree(start, start + k));
+        }
+    }
+}
+
+
+/// 验证 Inclusion Proof（RFC 9162 2.1.3.2）
+pub fn verify_inclusion<H>(leaf_hash: &H::Output, index: usize, size: usize, proof: &[H::Output], root: &H::Output) -> bool
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H>,
+    H::Output: Clone,
+{
+    if index >= size {
+        return false;
+    }
+
+    let mut fnode = index;
+    let mut snode = size - 1;
+    let mut r = leaf_hash.clone();
+
+    for p in proof.iter() {
+        if snode == 0 {
+            return false;
+        }
+
+        if fnode & 1 == 1 || fnode == snode {
+            r = node_hash::<H>(p, &r);
+            while fnode & 1 == 0 && fnode != 0 {
+                fnode >>= 1;
+                snode >>= 1;
+            }
+        } else {
+            r = node_hash::<H>(&r, p);
+        }
+
+        fnode >>= 1;
+        snode >>= 1;
+    }
+
+    snode == 0 && hash_eq::<H>(&r, root)
+}
+
+/// 验证 Consistency Proof（RFC 9162 2.1.4.2）
+pub fn verify_consistency<H>(old_size: usize, new_size: usize, old_root: &H::Output, new_root: &H::Output, proof: &[H::Output]) -> bool
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H>,
+    H::Output: Clone,
+{
+    if old_size > new_size {
+        return false;
+    }
+
+    // NOTE: 任何树都和空树保持一致。
+    if old_size == 0 {
+        return proof.is_empty();
+    }
+
+    if old_size == new_size {
+        return proof.is_empty() && hash_eq::<H>(old_root, new_root);
+    }
+
+    if proof.is_empty() {
+        return false;
+    }
+
+    let mut proof = proof;
+    let mut fr: H::Output;
+    let mut sr: H::Output;
+
+    if old_size.is_power_of_two() {
+        fr = old_root.clone();
+        sr = old_root.clone();
+    } else {
+        fr = proof[0].clone();
+        sr = proof[0].clone();
+        proof = &proof[1..];
+    }
+
+    let mut fnode = old_size - 1;
+    let mut snode = new_size - 1;
+    while fnode & 1 == 1 {
+        fnode >>= 1;
+        snode >>= 1;
+    }
+
+    for c in proof.iter() {
+        if snode == 0 {
+            return false;
+        }
+
+        if fnode & 1 == 1 || fnode == snode {
+            fr = node_hash::<H>(c, &fr);
+            sr = node_hash::<H>(c, &sr);
+            while fnode & 1 == 0 && fnode != 0 {
+                fnode >>= 1;
+                snode >>= 1;
+            }
+        } else {
+            sr = node_hash::<H>(&sr, c);
+        }
+
+        fnode >>= 1;
+        snode >>= 1;
+    }
+
+    snode == 0 && hash_eq::<H>(&fr, old_root) && hash_eq::<H>(&sr, new_root)
+}
+
+
+#[cfg(test)]
+fn test_tree() -> MerkleTree<super::Sha256> {
+    // https://github.com/google/certificate-transparency/blob/master/cpp/merkletree/merkle_tree_test.cc
+    let leaves: [&[u8]; 8] = [
+        b"",
+        b"\x00",
+        b"\x10",
+        b"\x20\x21",
+        b"\x30\x31",
+        b"\x40\x41\x42\x43",
+        b"\x50\x51\x52\x53\x54\x55\x56\x57",
+        b"\x60\x61\x62\x63\x64\x65\x66\x67\x68\x69\x6a\x6b\x6c\x6d\x6e\x6f",
+    ];
+
+    let mut tree = MerkleTree::new();
+    for (i, leaf) in leaves.iter().enumerate() {
+        assert_eq!(tree.push(leaf), i);
+    }
+    tree
+}
+
+#[test]
+fn test_merkle_root() {
+    let roots = [
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+        "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+        "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+        "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+        "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+        "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+        "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+    ];
+
+    let tree = test_tree();
+    for (size, root) in roots.iter().enumerate() {
+        assert_eq!(&hex::encode(&tree.root_at(size).unwrap()), root);
+    }
+    assert_eq!(&hex::encode(&tree.root()), roots[8]);
+    assert_eq!(tree.root_at(9), None);
+}
+
+#[test]
+fn test_merkle_inclusion_proof() {
+    let tree = test_tree();
+
+    let proof = tree.inclusion_proof(0, 8).unwrap();
+    assert_eq!(proof.iter().map(hex::encode).collect::<Vec<String>>(), [
+        "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+        "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+        "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+    ]);
+
+    let proof = tree.inclusion_proof(5, 8).unwrap();
+    assert_eq!(proof.iter().map(hex::encode).collect::<Vec<String>>(), [
+        "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+        "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+        "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    ]);
+
+    for size in 1..=tree.len() {
+        let root = tree.root_at(size).unwrap();
+        for index in 0..size {
+            let leaf = tree.leaf(index).unwrap();
+            let proof = tree.inclusion_proof(index, size).unwrap();
+            assert_eq!(verify_inclusion::<super::Sha256>(leaf, index, size, &proof, &root), true);
+
+            // 错误的位置
+            assert_eq!(verify_inclusion::<super::Sha256>(leaf, index + 1, size, &proof, &root), false);
+
+            if !proof.is_empty() {
+                let mut proof = proof.clone();
+                proof[0][0] ^= 1;
+                assert_eq!(verify_inclusion::<super::Sha256>(leaf, index, size, &proof, &root), false);
+            }
+        }
+    }
+
+    assert_eq!(tree.inclusion_proof(8, 8), None);
+}
+
+#[test]
+fn test_merkle_consistency_proof() {
+    let tree = test_tree();
+
+    let proof = tree.consistency_proof(3, 7).unwrap();
+    assert_eq!(proof.iter().map(hex::encode).collect::<Vec<String>>(), [
+        "0298d122906dcfc10892cb53a73992fc5b9f493ea4c9badb27b791b4127a7fe7",
+        "07506a85fd9dd2f120eb694f86011e5bb4662e5c415a62917033d4a9624487e7",
+        "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+        "837dbb152e9b079010717e84e865da4ebc0fa198a806d59d31bf15accef22d0e",
+    ]);
+
+    let proof = tree.consistency_proof(4, 7).unwrap();
+    assert_eq!(proof.iter().map(hex::encode).collect::<Vec<String>>(), [
+        "837dbb152e9b079010717e84e865da4ebc0fa198a806d59d31bf15accef22d0e",
+    ]);
+
+    for new_size in 0..=tree.len() {
+        let new_root = tree.root_at(new_size).unwrap();
+        for old_size in 0..=new_size {
+            let old_root = tree.root_at(old_size).unwrap();
+            let proof = tree.consistency_proof(old_size, new_size).unwrap();
+            assert_eq!(verify_consistency::<super::Sha256>(old_size, new_size, &old_root, &new_root, &proof), true);
+
+            if !proof.is_empty() {
+                let mut proof = proof.clone();
+                proof[0][0] ^= 1;
+                assert_eq!(verify_consistency::<super::Sha256>(old_size, new_size, &old_root, &new_root, &proof), false);
+            }
+
+            if old_size > 0 && old_size < new_size {
+                let wrong_root = tree.root_at(old_size - 1).unwrap();
+                assert_eq!(verify_consistency::<super::Sha256>(old_size, new_size, &wrong_root, &new_root, &proof), false);
+            }
+        }
+    }
+
+    assert_eq!(tree.consistency_proof(7, 3), None);
+    assert_eq!(tree.consistency_proof(3, 9), None);
+}
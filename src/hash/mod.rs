@@ -1,19 +1,83 @@
 mod md2;
 mod md4;
 mod md5;
+mod ripemd160;
+mod whirlpool;
 mod sm3;
+mod streebog;
 mod sha1;
+mod sha1dc;
 mod sha2;
-// TODO: 暂未实现
 mod sha3;
+mod blake2;
+mod blake3;
+#[cfg(feature = "std")]
+mod io;
+
+mod multihash;
+
+// RFC 6962 Merkle Hash Trees
+pub mod merkle;
 
 pub use self::md2::*;
 pub use self::md4::*;
 pub use self::md5::*;
+pub use self::ripemd160::*;
+pub use self::whirlpool::*;
 pub use self::sm3::*;
+pub use self::streebog::*;
 pub use self::sha1::*;
+pub use self::sha1dc::*;
 pub use self::sha2::*;
 pub use self::sha3::*;
+pub use self::blake2::*;
+pub use self::blake3::*;
+#[cfg(feature = "std")]
+pub use self::io::*;
+pub use self::multihash::*;
+
+
+// NOTE: `export_state` 导出的状态以 算法标识（1 字节）|| 格式版本（1 字节）开头，
+//       `import_state` 会拒绝其它算法（例如用 SHA-224 导入 SHA-256 的状态）或者其它版本导出的状态。
+const STATE_VERSION: u8 = 1;
+const STATE_HEADER_LEN: usize = 2;
+
+#[derive(Clone, Copy)]
+enum StateId {
+    Md2                 = 0x01,
+    Md4                 = 0x02,
+    Md5                 = 0x03,
+    Ripemd160           = 0x04,
+    Whirlpool           = 0x05,
+    Sm3                 = 0x06,
+    Streebog256         = 0x07,
+    Streebog512         = 0x08,
+    Sha1                = 0x09,
+    Sha1CollisionDetect = 0x0a,
+    Sha224              = 0x0b,
+    Sha256              = 0x0c,
+    Sha384              = 0x0d,
+    Sha512              = 0x0e,
+    Sha512_224          = 0x0f,
+    Sha512_256          = 0x10,
+}
+
+// 写入状态头部，返回剩余的部分。
+#[inline]
+fn export_state_header(state: &mut [u8], id: StateId) -> &mut [u8] {
+    state[0] = id as u8;
+    state[1] = STATE_VERSION;
+    &mut state[STATE_HEADER_LEN..]
+}
+
+// 检查状态的长度和头部，返回剩余的部分。
+#[inline]
+fn import_state_header(state: &[u8], len: usize, id: StateId) -> Option<&[u8]> {
+    if state.len() != len || state[0] != id as u8 || state[1] != STATE_VERSION {
+        return None;
+    }
+    Some(&state[STATE_HEADER_LEN..])
+}
 
 
 // NOTE: 等待 core::array::FixedSizeArray 稳定后，即可替换。
@@ -49,19 +113,32 @@ array_impls! {
 }
 
 
-// TODO: multihash
-// https://github.com/multiformats/multicodec/blob/master/table.csv
-
+// NOTE: Multicodec 编码见 `multihash.rs`。
+#[allow(non_camel_case_types)]
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum CryptoHashKind {
     MD2,
     MD4,
     MD5,
+    RIPEMD160,
+    WHIRLPOOL,
     SM3,
+    STREEBOG_256,
+    STREEBOG_512,
     SHA1,
+    SHA2_224,
     SHA2_256,
     SHA2_384,
     SHA2_512,
+    SHA2_512_224,
+    SHA2_512_256,
+    SHA3_224,
+    SHA3_256,
+    SHA3_384,
+    SHA3_512,
+    BLAKE2B_512,
+    BLAKE2S_256,
+    BLAKE3,
 }
 
 pub trait CryptoHasher {
@@ -91,6 +168,24 @@ pub trait CryptoHasher {
 }
 
 
+// Extendable-Output Function (XOF)
+pub trait CryptoXof {
+    const BLOCK_LEN: usize;
+
+    type Reader: XofReader;
+
+    fn write<T: AsRef<[u8]>>(&mut self, bytes: T);
+
+    fn finalize_xof(self) -> Self::Reader;
+
+    fn oneshot_xof<T: AsRef<[u8]>>(data: T, output: &mut [u8]);
+}
+
+pub trait XofReader {
+    fn read(&mut self, output: &mut [u8]);
+}
+
+
 pub trait BuildCryptoHasher {
     type Hasher: CryptoHasher;
 
@@ -123,8 +218,39 @@ pub trait BuildCryptoHasher {
 //     }
 // }
 
+// NOTE: 通过 `std::io::Write` 可以直接使用 `std::io::copy` 来计算文件或者 Socket 数据的摘要。
+macro_rules! impl_io_write {
+    ($name:tt) => {
+        #[cfg(feature = "std")]
+        impl std::io::Write for $name {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.update(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    }
+}
+
+macro_rules! impl_io_read {
+    ($name:tt) => {
+        #[cfg(feature = "std")]
+        impl std::io::Read for $name {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                XofReader::read(self, buf);
+                Ok(buf.len())
+            }
+        }
+    }
+}
+
 macro_rules! impl_crypto_hasher {
     ($name:tt) => {
+        impl_io_write!($name);
+
         impl CryptoHasher for $name {
             const BLOCK_LEN : usize = $name::BLOCK_LEN;
             const OUTPUT_LEN: usize = $name::DIGEST_LEN;
@@ -160,26 +286,139 @@ macro_rules! impl_build_crypto_hasher {
     }
 }
 
+macro_rules! impl_crypto_xof {
+    ($name:tt, $reader:tt) => {
+        impl_io_write!($name);
+
+        impl CryptoXof for $name {
+            const BLOCK_LEN: usize = $name::BLOCK_LEN;
+
+            type Reader = $reader;
+
+            fn write<T: AsRef<[u8]>>(&mut self, bytes: T) {
+                self.update(bytes.as_ref());
+            }
+
+            fn finalize_xof(self) -> Self::Reader {
+                self.finalize_xof()
+            }
+
+            fn oneshot_xof<T: AsRef<[u8]>>(data: T, output: &mut [u8]) {
+                Self::oneshot(data, output)
+            }
+        }
+    }
+}
+
 impl_crypto_hasher!(Md2);
 impl_crypto_hasher!(Md4);
 impl_crypto_hasher!(Md5);
+impl_crypto_hasher!(Ripemd160);
+impl_crypto_hasher!(Whirlpool);
 impl_crypto_hasher!(Sm3);
+impl_crypto_hasher!(Streebog256);
+impl_crypto_hasher!(Streebog512);
 impl_crypto_hasher!(Sha1);
+impl_crypto_hasher!(Sha1CollisionDetect);
 impl_build_crypto_hasher!(Md2);
 impl_build_crypto_hasher!(Md4);
 impl_build_crypto_hasher!(Md5);
+impl_build_crypto_hasher!(Ripemd160);
+impl_build_crypto_hasher!(Whirlpool);
 impl_build_crypto_hasher!(Sm3);
+impl_build_crypto_hasher!(Streebog256);
+impl_build_crypto_hasher!(Streebog512);
 impl_build_crypto_hasher!(Sha1);
+impl_build_crypto_hasher!(Sha1CollisionDetect);
 
 // SHA-2
+impl_crypto_hasher!(Sha224);
 impl_crypto_hasher!(Sha256);
 impl_crypto_hasher!(Sha384);
 impl_crypto_hasher!(Sha512);
+impl_crypto_hasher!(Sha512_224);
+impl_crypto_hasher!(Sha512_256);
+impl_build_crypto_hasher!(Sha224);
 impl_build_crypto_hasher!(Sha256);
 impl_build_crypto_hasher!(Sha384);
 impl_build_crypto_hasher!(Sha512);
+impl_build_crypto_hasher!(Sha512_224);
+impl_build_crypto_hasher!(Sha512_256);
 
 // SHA-3
+impl_crypto_hasher!(Sha3_224);
+impl_crypto_hasher!(Sha3_256);
+impl_crypto_hasher!(Sha3_384);
+impl_crypto_hasher!(Sha3_512);
+impl_build_crypto_hasher!(Sha3_224);
+impl_build_crypto_hasher!(Sha3_256);
+impl_build_crypto_hasher!(Sha3_384);
+impl_build_crypto_hasher!(Sha3_512);
+impl_crypto_xof!(Shake128, ShakeReader);
+impl_crypto_xof!(Shake256, ShakeReader);
+
+impl XofReader for ShakeReader {
+    fn read(&mut self, output: &mut [u8]) {
+        self.read(output)
+    }
+}
+impl_io_read!(ShakeReader);
+
+impl_io_write!(CShake128);
+impl_io_write!(CShake256);
+impl_io_write!(ParallelHash128);
+impl_io_write!(ParallelHash256);
+
+// BLAKE2
+impl_io_write!(Blake2b);
+impl_io_write!(Blake2s);
+impl_crypto_hasher!(Blake2b224);
+impl_crypto_hasher!(Blake2b256);
+impl_crypto_hasher!(Blake2b384);
+impl_crypto_hasher!(Blake2b512);
+impl_crypto_hasher!(Blake2s128);
+impl_crypto_hasher!(Blake2s160);
+impl_crypto_hasher!(Blake2s224);
+impl_crypto_hasher!(Blake2s256);
+impl_build_crypto_hasher!(Blake2b224);
+impl_build_crypto_hasher!(Blake2b256);
+impl_build_crypto_hasher!(Blake2b384);
+impl_build_crypto_hasher!(Blake2b512);
+impl_build_crypto_hasher!(Blake2s128);
+impl_build_crypto_hasher!(Blake2s160);
+impl_build_crypto_hasher!(Blake2s224);
+impl_build_crypto_hasher!(Blake2s256);
+
+// BLAKE3
+impl_crypto_hasher!(Blake3);
+impl_build_crypto_hasher!(Blake3);
+
+impl CryptoXof for Blake3 {
+    const BLOCK_LEN: usize = Blake3::BLOCK_LEN;
+
+    type Reader = Blake3Reader;
+
+    fn write<T: AsRef<[u8]>>(&mut self, bytes: T) {
+        self.update(bytes.as_ref());
+    }
+
+    fn finalize_xof(self) -> Self::Reader {
+        self.finalize_xof()
+    }
+
+    fn oneshot_xof<T: AsRef<[u8]>>(data: T, output: &mut [u8]) {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize_xof().read(output)
+    }
+}
+
+impl XofReader for Blake3Reader {
+    fn read(&mut self, output: &mut [u8]) {
+        self.read(output)
+    }
+}
+impl_io_read!(Blake3Reader);
 
 
 
@@ -213,6 +452,26 @@ fn bench_md5(b: &mut test::Bencher) {
     });
 }
 
+#[cfg(test)]
+#[bench]
+fn bench_ripemd160(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        ripemd160(&data)
+    });
+}
+
+#[cfg(test)]
+#[bench]
+fn bench_whirlpool(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        whirlpool(&data)
+    });
+}
+
 #[cfg(test)]
 #[bench]
 fn bench_sm3(b: &mut test::Bencher) {
@@ -223,6 +482,16 @@ fn bench_sm3(b: &mut test::Bencher) {
     });
 }
 
+#[cfg(test)]
+#[bench]
+fn bench_streebog256(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        streebog256(&data)
+    });
+}
+
 #[cfg(test)]
 #[bench]
 fn bench_sha1(b: &mut test::Bencher) {
@@ -233,6 +502,16 @@ fn bench_sha1(b: &mut test::Bencher) {
     });
 }
 
+#[cfg(test)]
+#[bench]
+fn bench_sha1dc(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        sha1dc(&data)
+    });
+}
+
 #[cfg(test)]
 #[bench]
 fn bench_sha256(b: &mut test::Bencher) {
@@ -246,6 +525,51 @@ fn bench_sha256(b: &mut test::Bencher) {
 }
 #[cfg(test)]
 #[bench]
+fn bench_sha256x4(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64 * 4;
+    b.iter(|| {
+        sha256x4([&data; 4])
+    });
+}
+#[cfg(test)]
+#[bench]
+fn bench_sha256x8(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64 * 8;
+    b.iter(|| {
+        sha256x8([&data; 8])
+    });
+}
+// NOTE: 和 `bench_sha256_each` 计算同一组长度不相同的消息，`sha256_batch` 不应该比逐个计算慢。
+#[cfg(test)]
+#[bench]
+fn bench_sha256_batch(b: &mut test::Bencher) {
+    let data = [1u8; 256];
+    let messages = (0..64usize).map(|i| &data[..i * 4]).collect::<Vec<&[u8]>>();
+    let mut digests = vec![[0u8; 32]; messages.len()];
+    b.bytes = messages.iter().map(|m| m.len() as u64).sum();
+    b.iter(|| {
+        sha256_batch(&messages, &mut digests)
+    });
+}
+#[cfg(test)]
+#[bench]
+fn bench_sha256_each(b: &mut test::Bencher) {
+    use self::sha2::sha256;
+
+    let data = [1u8; 256];
+    let messages = (0..64usize).map(|i| &data[..i * 4]).collect::<Vec<&[u8]>>();
+    let mut digests = vec![[0u8; 32]; messages.len()];
+    b.bytes = messages.iter().map(|m| m.len() as u64).sum();
+    b.iter(|| {
+        for (m, d) in messages.iter().zip(digests.iter_mut()) {
+            *d = sha256(m);
+        }
+    });
+}
+#[cfg(test)]
+#[bench]
 fn bench_sha384(b: &mut test::Bencher) {
     use self::sha2::sha384;
 
@@ -264,3 +588,43 @@ fn bench_sha512(b: &mut test::Bencher) {
         sha512(&data)
     });
 }
+
+#[cfg(test)]
+#[bench]
+fn bench_sha3_256(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        sha3_256(&data)
+    });
+}
+
+#[cfg(test)]
+#[bench]
+fn bench_blake2b(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        blake2b(&data)
+    });
+}
+
+#[cfg(test)]
+#[bench]
+fn bench_blake2s(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        blake2s(&data)
+    });
+}
+
+#[cfg(test)]
+#[bench]
+fn bench_blake3(b: &mut test::Bencher) {
+    let data = [1u8; 64];
+    b.bytes = data.len() as u64;
+    b.iter(|| {
+        blake3(&data)
+    });
+}
@@ -0,0 +1,289 @@
+// Multihash: Self-describing hashes
+// https://github.com/multiformats/multihash
+//
+// Multicodec table
+// https://github.com/multiformats/multicodec/blob/master/table.csv
+//
+// Unsigned varint
+// https://github.com/multiformats/unsigned-varint
+//
+// NOTE: Multihash 的格式为 `varint(code) || varint(length) || digest`，
+//       其中 length 可以小于摘要算法的输出长度（截断）。
+use super::*;
+
+
+// NOTE: 按照规范，varint 最多只能有 9 个字节（63 bits）。
+const MAX_VARINT_LEN: usize = 9;
+
+fn varint_encode(mut n: u64, out: &mut Vec<u8>) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn varint_decode(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut n = 0u64;
+    for (i, b) in bytes.iter().enumerate() {
+        if i >= MAX_VARINT_LEN {
+            return None;
+        }
+
+        n |= ((b & 0x7f) as u64) << (i * 7);
+
+        if b & 0x80 == 0 {
+            // 不允许非最短的编码（例如：0x80 0x00）
+            if *b == 0 && i > 0 {
+                return None;
+            }
+            return Some((n, i + 1));
+        }
+    }
+
+    None
+}
+
+
+macro_rules! impl_multicodec {
+    ($($kind:ident => $hasher:ident, $code:expr;)+) => {
+        impl CryptoHashKind {
+            /// Multicodec 编码，没有分配编码的算法返回 `None`。
+            pub fn code(&self) -> Option<u64> {
+                match *self {
+                    $( CryptoHashKind::$kind => $code, )+
+                }
+            }
+
+            pub fn from_code(code: u64) -> Option<Self> {
+                $(
+                    if $code == Some(code) {
+                        return Some(CryptoHashKind::$kind);
+                    }
+                )+
+                None
+            }
+
+            pub fn digest_len(&self) -> usize {
+                match *self {
+                    $( CryptoHashKind::$kind => $hasher::DIGEST_LEN, )+
+                }
+            }
+
+            /// 使用该摘要算法计算 `data` 的摘要。
+            pub fn oneshot<T: AsRef<[u8]>>(&self, data: T) -> Vec<u8> {
+                match *self {
+                    $( CryptoHashKind::$kind => $hasher::oneshot(data).to_vec(), )+
+                }
+            }
+        }
+    }
+}
+
+impl_multicodec! {
+    MD2          => Md2,        None;
+    MD4          => Md4,        Some(0xd4);
+    MD5          => Md5,        Some(0xd5);
+    RIPEMD160    => Ripemd160,  Some(0x1053);
+    WHIRLPOOL    => Whirlpool,  None;
+    SM3          => Sm3,        Some(0x534d);
+    STREEBOG_256 => Streebog256, None;
+    STREEBOG_512 => Streebog512, None;
+    SHA1         => Sha1,       Some(0x11);
+    SHA2_224     => Sha224,     Some(0x1013);
+    SHA2_256     => Sha256,     Some(0x12);
+    SHA2_384     => Sha384,     Some(0x20);
+    SHA2_512     => Sha512,     Some(0x13);
+    SHA2_512_224 => Sha512_224, Some(0x1014);
+    SHA2_512_256 => Sha512_256, Some(0x1015);
+    SHA3_224     => Sha3_224,   Some(0x17);
+    SHA3_256     => Sha3_256,   Some(0x16);
+    SHA3_384     => Sha3_384,   Some(0x15);
+    SHA3_512     => Sha3_512,   Some(0x14);
+    BLAKE2B_512  => Blake2b512, Some(0xb240);
+    BLAKE2S_256  => Blake2s256, Some(0xb260);
+    BLAKE3       => Blake3,     Some(0x1e);
+}
+
+
+/// Multihash: `varint(code) || varint(length) || digest`
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Multihash {
+    kind: CryptoHashKind,
+    digest: Vec<u8>,
+}
+
+impl Multihash {
+    /// `digest` 的长度不能超过摘要算法的输出长度，并且该算法必须有 Multicodec 编码。
+    pub fn new(kind: CryptoHashKind, digest: &[u8]) -> Option<Self> {
+        if kind.code().is_none() || digest.len() > kind.digest_len() {
+            return None;
+        }
+
+        Some(Self { kind, digest: digest.to_vec() })
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(kind: CryptoHashKind, data: T) -> Option<Self> {
+        kind.code()?;
+
+        Some(Self { kind, digest: kind.oneshot(data) })
+    }
+
+    /// 使用 Multicodec 编码对应的摘要算法计算 `data` 的摘要。
+    pub fn hash<T: AsRef<[u8]>>(code: u64, data: T) -> Option<Self> {
+        Self::oneshot(CryptoHashKind::from_code(code)?, data)
+    }
+
+    pub fn kind(&self) -> CryptoHashKind {
+        self.kind
+    }
+
+    pub fn code(&self) -> u64 {
+        // NOTE: 在创建时已经检查过。
+        self.kind.code().unwrap()
+    }
+
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// 截断摘要，`len` 大于当前长度时不做任何操作。
+    pub fn truncate(&mut self, len: usize) {
+        self.digest.truncate(len);
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        let mut buf = Vec::with_capacity(MAX_VARINT_LEN * 2);
+        varint_encode(self.code(), &mut buf);
+        varint_encode(self.digest.len() as u64, &mut buf);
+        buf.len() + self.digest.len()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MAX_VARINT_LEN * 2 + self.digest.len());
+        varint_encode(self.code(), &mut out);
+        varint_encode(self.digest.len() as u64, &mut out);
+        out.extend_from_slice(&self.digest);
+        out
+    }
+
+    /// 解码 `bytes` 开头的 Multihash，返回 Multihash 以及读取的字节数。
+    pub fn read(bytes: &[u8]) -> Option<(Self, usize)> {
+        let (code, n1) = varint_decode(bytes)?;
+        let (len, n2) = varint_decode(&bytes[n1..])?;
+
+        let kind = CryptoHashKind::from_code(code)?;
+        if len > kind.digest_len() as u64 {
+            return None;
+        }
+
+        let start = n1 + n2;
+        let end = start + len as usize;
+        if bytes.len() < end {
+            return None;
+        }
+
+        Some((Self { kind, digest: bytes[start..end].to_vec() }, end))
+    }
+
+    /// 解码 Multihash，`bytes` 不能包含多余的数据。
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match Self::read(bytes) {
+            Some((mh, n)) if n == bytes.len() => Some(mh),
+            _ => None,
+        }
+    }
+}
+
+
+#[test]
+fn test_varint() {
+    let suites: &[(u64, &[u8])] = &[
+        (0, &[0x00]),
+        (1, &[0x01]),
+        (127, &[0x7f]),
+        (128, &[0x80, 0x01]),
+        (255, &[0xff, 0x01]),
+        (300, &[0xac, 0x02]),
+        (16384, &[0x80, 0x80, 0x01]),
+        (0xb240, &[0xc0, 0xe4, 0x02]),
+        ((1 << 63) - 1, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]),
+    ];
+    for (n, bytes) in suites.iter() {
+        let mut out = Vec::new();
+        varint_encode(*n, &mut out);
+        assert_eq!(&out[..], *bytes);
+        assert_eq!(varint_decode(bytes), Some((*n, bytes.len())));
+    }
+
+    // 非最短编码、超过 9 个字节以及不完整的编码
+    assert_eq!(varint_decode(&[0x80, 0x00]), None);
+    assert_eq!(varint_decode(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]), None);
+    assert_eq!(varint_decode(&[0x80]), None);
+    assert_eq!(varint_decode(&[]), None);
+}
+
+#[test]
+fn test_multihash() {
+    // https://github.com/multiformats/multihash#example
+    let data = "Merkle–Damgård".as_bytes();
+
+    let mh = Multihash::hash(0x12, data).unwrap();
+    assert_eq!(mh.kind(), CryptoHashKind::SHA2_256);
+    assert_eq!(&hex::encode(mh.to_bytes()),
+        "122041dd7b6443542e75701aa98a0c235951a28a0d851b11564d20022ab11d2589a8");
+    assert_eq!(mh.encoded_len(), 34);
+
+    let mh = Multihash::oneshot(CryptoHashKind::SHA1, data).unwrap();
+    assert_eq!(&hex::encode(mh.to_bytes()), "11148a173fd3e32c0fa78b90fe42d305f202244e2739");
+
+    let mh = Multihash::oneshot(CryptoHashKind::BLAKE2B_512, b"multihash").unwrap();
+    let bytes = mh.to_bytes();
+    assert_eq!(&bytes[..4], &[0xc0, 0xe4, 0x02, 0x40]);
+    assert_eq!(&bytes[4..], &blake2b(b"multihash")[..]);
+    assert_eq!(Multihash::from_bytes(&bytes), Some(mh.clone()));
+
+    // 截断
+    let mut mh = Multihash::hash(0x12, data).unwrap();
+    mh.truncate(4);
+    assert_eq!(&hex::encode(mh.to_bytes()), "120441dd7b64");
+    assert_eq!(Multihash::from_bytes(&mh.to_bytes()), Some(mh));
+
+    // 没有 Multicodec 编码的算法
+    assert_eq!(Multihash::oneshot(CryptoHashKind::MD2, b"multihash"), None);
+    assert_eq!(Multihash::new(CryptoHashKind::WHIRLPOOL, &[0u8; 64]), None);
+    assert_eq!(Multihash::hash(0x01, b"multihash"), None);
+}
+
+#[test]
+fn test_multihash_decode() {
+    let mh = Multihash::hash(0x16, b"hello world").unwrap();
+    assert_eq!(mh.digest(), &sha3_256(b"hello world")[..]);
+
+    let mut bytes = mh.to_bytes();
+    assert_eq!(Multihash::from_bytes(&bytes), Some(mh.clone()));
+
+    // 多余的数据
+    bytes.push(0x00);
+    assert_eq!(Multihash::from_bytes(&bytes), None);
+    assert_eq!(Multihash::read(&bytes), Some((mh.clone(), bytes.len() - 1)));
+
+    // 数据不完整
+    bytes.truncate(bytes.len() - 2);
+    assert_eq!(Multihash::from_bytes(&bytes), None);
+
+    // 长度超过摘要算法的输出长度
+    let mut bytes = vec![0x16, 33];
+    bytes.extend_from_slice(&[0u8; 33]);
+    assert_eq!(Multihash::from_bytes(&bytes), None);
+
+    // 未知的编码
+    assert_eq!(Multihash::from_bytes(&[0x00, 0x00]), None);
+
+    for kind in [CryptoHashKind::MD5, CryptoHashKind::SM3, CryptoHashKind::SHA2_512_256, CryptoHashKind::BLAKE3].iter() {
+        let mh = Multihash::oneshot(*kind, b"abc").unwrap();
+        assert_eq!(mh.digest().len(), kind.digest_len());
+        assert_eq!(CryptoHashKind::from_code(mh.code()), Some(*kind));
+        assert_eq!(Multihash::from_bytes(&mh.to_bytes()), Some(mh));
+    }
+}
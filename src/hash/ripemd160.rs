@@ -0,0 +1,292 @@
+// RIPEMD-160: A Strengthened Version of RIPEMD
+// https://homes.esat.kuleuven.be/~bosselae/ripemd160/pdf/AB-9601/AB-9601.pdf
+//
+// https://homes.esat.kuleuven.be/~bosselae/ripemd160.html
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
+
+
+// Initial value
+const INITIAL_STATE: [u32; 5] = [ 0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0 ];
+
+// Added constants
+const K_LEFT: [u32; 5]  = [ 0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e ];
+const K_RIGHT: [u32; 5] = [ 0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000 ];
+
+// Selection of message word
+const R_LEFT: [usize; 80] = [
+     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15,
+     7,  4, 13,  1, 10,  6, 15,  3, 12,  0,  9,  5,  2, 14, 11,  8,
+     3, 10, 14,  4,  9, 15,  8,  1,  2,  7,  0,  6, 13, 11,  5, 12,
+     1,  9, 11, 10,  0,  8, 12,  4, 13,  3,  7, 15, 14,  5,  6,  2,
+     4,  0,  5,  9,  7, 12,  2, 10, 14,  1,  3,  8, 11,  6, 15, 13,
+];
+const R_RIGHT: [usize; 80] = [
+     5, 14,  7,  0,  9,  2, 11,  4, 13,  6, 15,  8,  1, 10,  3, 12,
+     6, 11,  3,  7,  0, 13,  5, 10, 14, 15,  8, 12,  4,  9,  1,  2,
+    15,  5,  1,  3,  7, 14,  6,  9, 11,  8, 12,  2, 10,  0,  4, 13,
+     8,  6,  4,  1,  3, 11, 15,  0,  5, 12,  2, 13,  9,  7, 10, 14,
+    12, 15, 10,  4,  1,  5,  8,  7,  6,  2, 13, 14,  0,  3,  9, 11,
+];
+
+// Amount for rotate left (rol)
+const S_LEFT: [u32; 80] = [
+    11, 14, 15, 12,  5,  8,  7,  9, 11, 13, 14, 15,  6,  7,  9,  8,
+     7,  6,  8, 13, 11,  9,  7, 15,  7, 12, 15,  9, 11,  7, 13, 12,
+    11, 13,  6,  7, 14,  9, 13, 15, 14,  8, 13,  6,  5, 12,  7,  5,
+    11, 12, 14, 15, 14, 15,  9,  8,  9, 14,  5,  6,  8,  6,  5, 12,
+     9, 15,  5, 11,  6,  8, 13, 12,  5, 12, 13, 14, 11,  8,  5,  6,
+];
+const S_RIGHT: [u32; 80] = [
+     8,  9,  9, 11, 13, 15, 15,  5,  7,  7,  8, 11, 14, 14, 12,  6,
+     9, 13, 15,  7, 12,  8,  9, 11,  7,  7, 12,  7,  6, 15, 13, 11,
+     9,  7, 15, 11,  8,  6,  6, 14, 12, 13,  5, 14, 13, 13,  7,  5,
+    15,  5,  8, 11, 14, 14,  6, 14,  6,  9, 12,  9, 12,  5, 15,  8,
+     8,  5, 12,  9, 12,  5, 14,  6,  8, 13,  6,  5, 15, 13, 11, 11,
+];
+
+
+pub fn ripemd160<T: AsRef<[u8]>>(data: T) -> [u8; Ripemd160::DIGEST_LEN] {
+    Ripemd160::oneshot(data)
+}
+
+#[derive(Clone)]
+pub struct Ripemd160 {
+    buffer: [u8; Self::BLOCK_LEN],
+    offset: usize,
+    state: [u32; 5],
+    len: u64,        // in bytes.
+}
+
+impl Zeroize for Ripemd160 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.offset.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Ripemd160 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Ripemd160 {
+    pub const BLOCK_LEN: usize  = 64;
+    pub const DIGEST_LEN: usize = 20;
+
+
+    pub fn new() -> Self {
+        Self {
+            buffer: [0u8; Self::BLOCK_LEN],
+            offset: 0,
+            state: INITIAL_STATE,
+            len: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        self.len = self.len.wrapping_add(data.len() as u64);
+
+        if self.offset > 0 {
+            let n = core::cmp::min(Self::BLOCK_LEN - self.offset, data.len());
+            self.buffer[self.offset..self.offset + n].copy_from_slice(&data[..n]);
+            self.offset += n;
+            data = &data[n..];
+
+            if self.offset < Self::BLOCK_LEN {
+                return;
+            }
+
+            transform(&mut self.state, &self.buffer);
+            self.offset = 0;
+        }
+
+        while data.len() >= Self::BLOCK_LEN {
+            let (block, rem) = data.split_at(Self::BLOCK_LEN);
+            transform(&mut self.state, block);
+            data = rem;
+        }
+
+        if !data.is_empty() {
+            self.buffer[..data.len()].copy_from_slice(data);
+            self.offset = data.len();
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; Self::DIGEST_LEN] {
+        // 同 MD4 的填充方式，长度以小端序表示。
+        let len_bits = self.len.wrapping_mul(8);
+
+        self.buffer[self.offset] = 0x80;
+        for byte in self.buffer[self.offset + 1..].iter_mut() {
+            *byte = 0;
+        }
+
+        if self.offset + 1 > 56 {
+            transform(&mut self.state, &self.buffer);
+            self.buffer = [0u8; Self::BLOCK_LEN];
+        }
+
+        self.buffer[56..].copy_from_slice(&len_bits.to_le_bytes());
+        transform(&mut self.state, &self.buffer);
+
+        let mut output = [0u8; Self::DIGEST_LEN];
+        output[ 0.. 4].copy_from_slice(&self.state[0].to_le_bytes());
+        output[ 4.. 8].copy_from_slice(&self.state[1].to_le_bytes());
+        output[ 8..12].copy_from_slice(&self.state[2].to_le_bytes());
+        output[12..16].copy_from_slice(&self.state[3].to_le_bytes());
+        output[16..20].copy_from_slice(&self.state[4].to_le_bytes());
+        output
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize()
+    }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 20 + 8 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in little-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Ripemd160);
+        for (chunk, word) in output[..20].chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output[20..28].copy_from_slice(&self.len.to_le_bytes());
+
+        let n = (self.len % Self::BLOCK_LEN as u64) as usize;
+        output[28..28 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Ripemd160)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..20].chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&state[20..28]);
+        m.len = u64::from_le_bytes(len);
+
+        let n = (m.len % Self::BLOCK_LEN as u64) as usize;
+        if state[28 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[28..28 + n]);
+        m.offset = n;
+
+        Some(m)
+    }
+}
+
+
+// Nonlinear functions at bit level: exor, mux, -, mux, -
+#[inline]
+fn f(j: usize, x: u32, y: u32, z: u32) -> u32 {
+    match j / 16 {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        4 => x ^ (y | !z),
+        _ => unreachable!(),
+    }
+}
+
+#[inline]
+fn transform(state: &mut [u32; 5], block: &[u8]) {
+    debug_assert_eq!(block.len(), Ripemd160::BLOCK_LEN);
+
+    let mut x = [0u32; 16];
+    for (i, chunk) in block.chunks_exact(4).enumerate() {
+        x[i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    let [mut al, mut bl, mut cl, mut dl, mut el] = *state;
+    let [mut ar, mut br, mut cr, mut dr, mut er] = *state;
+
+    for j in 0..80 {
+        let t = al.wrapping_add(f(j, bl, cl, dl))
+            .wrapping_add(x[R_LEFT[j]])
+            .wrapping_add(K_LEFT[j / 16])
+            .rotate_left(S_LEFT[j])
+            .wrapping_add(el);
+        al = el;
+        el = dl;
+        dl = cl.rotate_left(10);
+        cl = bl;
+        bl = t;
+
+        // 右侧的并行分支按相反的顺序使用非线性函数。
+        let t = ar.wrapping_add(f(79 - j, br, cr, dr))
+            .wrapping_add(x[R_RIGHT[j]])
+            .wrapping_add(K_RIGHT[j / 16])
+            .rotate_left(S_RIGHT[j])
+            .wrapping_add(er);
+        ar = er;
+        er = dr;
+        dr = cr.rotate_left(10);
+        cr = br;
+        br = t;
+    }
+
+    let t = state[1].wrapping_add(cl).wrapping_add(dr);
+    state[1] = state[2].wrapping_add(dl).wrapping_add(er);
+    state[2] = state[3].wrapping_add(el).wrapping_add(ar);
+    state[3] = state[4].wrapping_add(al).wrapping_add(br);
+    state[4] = state[0].wrapping_add(bl).wrapping_add(cr);
+    state[0] = t;
+}
+
+
+#[test]
+fn test_ripemd160() {
+    // https://homes.esat.kuleuven.be/~bosselae/ripemd160.html
+    let suites: &[(&[u8], &str)] = &[
+        (b"", "9c1185a5c5e9fc54612808977ee8f548b2258d31"),
+        (b"a", "0bdc9d2d256b3ee9daae347be6f4dc835a467ffe"),
+        (b"abc", "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"),
+        (b"message digest", "5d0689ef49d2fae572b881b123a85ffa21595f36"),
+        (b"abcdefghijklmnopqrstuvwxyz", "f71c27109c692c1b56bbdceb5b9d2865b3708dbc"),
+        (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "12a053384a9c0c88e405a06c27dcf49ada62eb2b"),
+        (b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "b0e20b6e3116640286ed3a87a5713079b21f5189"),
+        (b"12345678901234567890123456789012345678901234567890123456789012345678901234567890", "9b752e45573d4b39f4dbd3323cab82bf63326bfb"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&ripemd160(msg)), digest);
+    }
+
+    // 1 million times "a"
+    let mut m = Ripemd160::new();
+    for _ in 0..1000 {
+        m.update(&[b'a'; 1000]);
+    }
+    assert_eq!(&hex::encode(&m.finalize()), "52783243c1697bdbe16d37f97f68f08325dc1528");
+}
+
+#[test]
+fn test_ripemd160_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Ripemd160::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Ripemd160::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), ripemd160(&msg));
+    }
+}
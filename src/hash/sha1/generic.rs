@@ -0,0 +1,83 @@
+use super::{ K1, K2, K3, K4, };
+use super::Sha1;
+
+
+// https://github.com/B-Con/crypto-algorithms/blob/master/sha1.c
+#[inline]
+pub fn transform(state: &mut [u32; 5], block: &[u8]) {
+    debug_assert_eq!(state.len(), 5);
+    debug_assert_eq!(block.len(), Sha1::BLOCK_LEN);
+
+    let mut w = [0u32; 80];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes([
+            block[i*4 + 0], block[i*4 + 1],
+            block[i*4 + 2], block[i*4 + 3],
+        ]);
+    }
+
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let mut a = state[0];
+    let mut b = state[1];
+    let mut c = state[2];
+    let mut d = state[3];
+    let mut e = state[4];
+
+    for i in 0..20 {
+        let t = a.rotate_left(5)
+                .wrapping_add( (b & c) ^ (!b & d) )
+                .wrapping_add(e)
+                .wrapping_add(K1)
+                .wrapping_add(w[i]);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = t;
+    }
+    for i in 20..40 {
+        let t = a.rotate_left(5)
+                .wrapping_add(b ^ c ^d)
+                .wrapping_add(e)
+                .wrapping_add(K2)
+                .wrapping_add(w[i]);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = t;
+    }
+    for i in 40..60 {
+        let t = a.rotate_left(5)
+                .wrapping_add((b & c) ^ (b & d) ^ (c & d))
+                .wrapping_add(e)
+                .wrapping_add(K3)
+                .wrapping_add(w[i]);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = t;
+    }
+    for i in 60..80 {
+        let t = a.rotate_left(5)
+                .wrapping_add(b ^ c ^ d)
+                .wrapping_add(e)
+                .wrapping_add(K4)
+                .wrapping_add(w[i]);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = t;
+    }
+
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+    state[4] = state[4].wrapping_add(e);
+}
@@ -6,14 +6,19 @@
 // ❗️ SHA1算法在2005年后被证实存在弱点，可以被加以破解。
 // ‼️ SHA1算法在2017年被证实无法防止碰撞攻击，因此不适用于安全性认证。
 use core::convert::TryFrom;
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
 
-// NOTE: 虽然在 X86 和 AArch64 架构上，有很多款芯片都支持对 SHA1 加速，
-//       但是考虑到 SHA1 已经被证实存在弱点，所以这里不再对 SHA1 的代码
-//       做任何性能方面的改进，以减轻代码维护工作。
-// 
-// 如果你需要更好的性能，建议参考 `noloader/SHA-Intrinsics` 的代码自行实现：
+
+#[allow(dead_code)]
+mod generic;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+
+// NOTE: 考虑到 SHA1 已经被证实存在弱点，这里只对 X86/X86-64 架构的 SHA-NI 做了支持，
+//       因为 Git 等场景仍然需要计算大量的 SHA1 摘要。
+//       AArch64 架构的实现可以参考 `noloader/SHA-Intrinsics` 的代码：
 //      https://github.com/noloader/SHA-Intrinsics/blob/master/sha1-arm.c
-//      https://github.com/noloader/SHA-Intrinsics/blob/master/sha1-x86.c
 // 
 const K1: u32 = 0x5a827999;
 const K2: u32 = 0x6ed9eba1;
@@ -23,6 +28,25 @@ const K4: u32 = 0xca62c1d6;
 const INITIAL_STATE: [u32; 5] = [ 0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0 ];
 
 
+// NOTE: 通过 Runtime 检测到的 CPU 特性（由 `crate::cpu` 缓存）来选择是否使用 SHA-NI 来加速。
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn transform(state: &mut [u32; 5], block: &[u8]) {
+    if crate::cpu::has(crate::cpu::SHA) {
+        unsafe { x86::transform(state, block) }
+    } else {
+        generic::transform(state, block)
+    }
+}
+
+// Other platform
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn transform(state: &mut [u32; 5], block: &[u8]) {
+    generic::transform(state, block)
+}
+
+
 /// SHA1
 pub fn sha1<T: AsRef<[u8]>>(data: T) -> [u8; Sha1::DIGEST_LEN] {
     Sha1::oneshot(data)
@@ -36,6 +60,20 @@ pub struct Sha1 {
     len: usize,      // in bytes.
 }
 
+impl Zeroize for Sha1 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Sha1 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 impl Sha1 {
     pub const BLOCK_LEN: usize  = 64;
     pub const DIGEST_LEN: usize = 20;
@@ -67,10 +105,7 @@ impl Sha1 {
             } else {
                 transform(&mut self.state, &self.buffer);
 
-                let data = &data[i..];
-                if data.len() > 0 {
-                    return self.update(data);
-                }
+                return self.update(&data[i..]);
             }
         }
 
@@ -136,89 +171,47 @@ impl Sha1 {
         m.update(data.as_ref());
         m.finalize()
     }
-}
 
-// https://github.com/B-Con/crypto-algorithms/blob/master/sha1.c
-#[inline]
-fn transform(state: &mut [u32; 5], block: &[u8]) {
-    debug_assert_eq!(state.len(), 5);
-    debug_assert_eq!(block.len(), Sha1::BLOCK_LEN);
-
-    let mut w = [0u32; 80];
-    for i in 0..16 {
-        w[i] = u32::from_be_bytes([
-            block[i*4 + 0], block[i*4 + 1],
-            block[i*4 + 2], block[i*4 + 3],
-        ]);
-    }
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 20 + 8 + Self::BLOCK_LEN;
 
-    for i in 16..80 {
-        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
-    }
+    /// Exports the in-progress state. Integers are encoded in big-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Sha1);
+        for (chunk, word) in output[..20].chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output[20..28].copy_from_slice(&(self.len as u64).to_be_bytes());
 
-    let mut a = state[0];
-    let mut b = state[1];
-    let mut c = state[2];
-    let mut d = state[3];
-    let mut e = state[4];
-
-    for i in 0..20 {
-        let t = a.rotate_left(5)
-                .wrapping_add( (b & c) ^ (!b & d) )
-                .wrapping_add(e)
-                .wrapping_add(K1)
-                .wrapping_add(w[i]);
-        e = d;
-        d = c;
-        c = b.rotate_left(30);
-        b = a;
-        a = t;
-    }
-    for i in 20..40 {
-        let t = a.rotate_left(5)
-                .wrapping_add(b ^ c ^d)
-                .wrapping_add(e)
-                .wrapping_add(K2)
-                .wrapping_add(w[i]);
-        e = d;
-        d = c;
-        c = b.rotate_left(30);
-        b = a;
-        a = t;
-    }
-    for i in 40..60 {
-        let t = a.rotate_left(5)
-                .wrapping_add((b & c) ^ (b & d) ^ (c & d))
-                .wrapping_add(e)
-                .wrapping_add(K3)
-                .wrapping_add(w[i]);
-        e = d;
-        d = c;
-        c = b.rotate_left(30);
-        b = a;
-        a = t;
-    }
-    for i in 60..80 {
-        let t = a.rotate_left(5)
-                .wrapping_add(b ^ c ^ d)
-                .wrapping_add(e)
-                .wrapping_add(K4)
-                .wrapping_add(w[i]);
-        e = d;
-        d = c;
-        c = b.rotate_left(30);
-        b = a;
-        a = t;
+        let n = self.len % Self::BLOCK_LEN;
+        output[28..28 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
     }
 
-    state[0] = state[0].wrapping_add(a);
-    state[1] = state[1].wrapping_add(b);
-    state[2] = state[2].wrapping_add(c);
-    state[3] = state[3].wrapping_add(d);
-    state[4] = state[4].wrapping_add(e);
-}
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Sha1)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..20].chunks_exact(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&state[20..28]);
+        m.len = usize::try_from(u64::from_be_bytes(len)).ok()?;
 
+        let n = m.len % Self::BLOCK_LEN;
+        if state[28 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[28..28 + n]);
 
+        Some(m)
+    }
+}
 
 #[test]
 fn test_sha1_one_block_message() {
@@ -238,4 +231,29 @@ fn test_sha1_long_message() {
     let msg = vec![b'a'; 1000_000];
     let digest = [52, 170, 151, 60, 212, 196, 218, 164, 246, 30, 235, 43, 219, 173, 39, 49, 101, 52, 1, 111];
     assert_eq!(sha1(&msg), digest);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_sha1_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Sha1::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Sha1::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), sha1(&msg));
+    }
+}
+
+#[test]
+fn test_sha1_bytewise_update() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let mut m = Sha1::new();
+    for byte in msg.chunks(1) {
+        m.update(byte);
+    }
+    assert_eq!(m.finalize(), sha1(&msg));
+}
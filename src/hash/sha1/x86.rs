@@ -0,0 +1,142 @@
+use super::Sha1;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+
+// Intel® Architecture Instruction Set Extensions ProgrammingReference
+//
+// CHAPTER 8 INTEL® SHA EXTENSIONS
+// https://software.intel.com/sites/default/files/managed/07/b7/319433-023.pdf
+
+// NOTE: 调用方需要先确认 CPU 支持 SHA-NI 指令（`crate::cpu::has`）。
+#[inline]
+pub unsafe fn transform(state: &mut [u32; 5], block: &[u8]) {
+    debug_assert_eq!(state.len(), 5);
+    debug_assert_eq!(block.len(), Sha1::BLOCK_LEN);
+
+    transform_shani(state, block)
+}
+
+// Process a block with the SHA-1 algorithm.
+// Based on https://github.com/noloader/SHA-Intrinsics/blob/master/sha1-x86.c
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn transform_shani(state: &mut [u32; 5], block: &[u8]) {
+    // 4 轮：E 的值由上一组的 ABCD 通过 `sha1nexte` 计算得到。
+    macro_rules! ROUNDS4 {
+        ($abcd:ident, $e0:ident, $e1:ident, $msg:expr, $func:literal) => {
+            $e1 = $abcd;
+            $e0 = _mm_sha1nexte_epu32($e0, $msg);
+            $abcd = _mm_sha1rnds4_epu32($abcd, $e0, $func);
+        }
+    }
+
+    // W[t..t+4] = (W[t-3] ^ W[t-8] ^ W[t-14] ^ W[t-16]) <<< 1
+    macro_rules! SCHEDULE {
+        ($m0:expr, $m1:expr, $m2:expr, $m3:expr) => {
+            _mm_sha1msg2_epu32(_mm_xor_si128(_mm_sha1msg1_epu32($m0, $m1), $m2), $m3)
+        }
+    }
+
+    #[allow(non_snake_case)]
+    let MASK: __m128i = _mm_set_epi64x(
+        0x0001_0203_0405_0607u64 as i64,
+        0x0809_0a0b_0c0d_0e0fu64 as i64,
+    );
+
+    // Load initial values
+    let abcd_save = _mm_set_epi32(state[0] as i32, state[1] as i32, state[2] as i32, state[3] as i32);
+    let e_save    = _mm_set_epi32(state[4] as i32, 0, 0, 0);
+
+    let mut abcd = abcd_save;
+    let mut e0: __m128i;
+    let mut e1: __m128i;
+
+    let mut msg0 = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr().add( 0) as *const __m128i), MASK);
+    let mut msg1 = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr().add(16) as *const __m128i), MASK);
+    let mut msg2 = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr().add(32) as *const __m128i), MASK);
+    let mut msg3 = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr().add(48) as *const __m128i), MASK);
+
+    // Rounds 0-3
+    e0 = _mm_add_epi32(e_save, msg0);
+    e1 = abcd;
+    abcd = _mm_sha1rnds4_epu32(abcd, e0, 0);
+
+    // Rounds 4-15
+    ROUNDS4!(abcd, e1, e0, msg1, 0);
+    ROUNDS4!(abcd, e0, e1, msg2, 0);
+    ROUNDS4!(abcd, e1, e0, msg3, 0);
+
+    // Rounds 16-19
+    msg0 = SCHEDULE!(msg0, msg1, msg2, msg3);
+    ROUNDS4!(abcd, e0, e1, msg0, 0);
+
+    // Rounds 20-39
+    msg1 = SCHEDULE!(msg1, msg2, msg3, msg0);
+    ROUNDS4!(abcd, e1, e0, msg1, 1);
+    msg2 = SCHEDULE!(msg2, msg3, msg0, msg1);
+    ROUNDS4!(abcd, e0, e1, msg2, 1);
+    msg3 = SCHEDULE!(msg3, msg0, msg1, msg2);
+    ROUNDS4!(abcd, e1, e0, msg3, 1);
+    msg0 = SCHEDULE!(msg0, msg1, msg2, msg3);
+    ROUNDS4!(abcd, e0, e1, msg0, 1);
+    msg1 = SCHEDULE!(msg1, msg2, msg3, msg0);
+    ROUNDS4!(abcd, e1, e0, msg1, 1);
+
+    // Rounds 40-59
+    msg2 = SCHEDULE!(msg2, msg3, msg0, msg1);
+    ROUNDS4!(abcd, e0, e1, msg2, 2);
+    msg3 = SCHEDULE!(msg3, msg0, msg1, msg2);
+    ROUNDS4!(abcd, e1, e0, msg3, 2);
+    msg0 = SCHEDULE!(msg0, msg1, msg2, msg3);
+    ROUNDS4!(abcd, e0, e1, msg0, 2);
+    msg1 = SCHEDULE!(msg1, msg2, msg3, msg0);
+    ROUNDS4!(abcd, e1, e0, msg1, 2);
+    msg2 = SCHEDULE!(msg2, msg3, msg0, msg1);
+    ROUNDS4!(abcd, e0, e1, msg2, 2);
+
+    // Rounds 60-79
+    msg3 = SCHEDULE!(msg3, msg0, msg1, msg2);
+    ROUNDS4!(abcd, e1, e0, msg3, 3);
+    msg0 = SCHEDULE!(msg0, msg1, msg2, msg3);
+    ROUNDS4!(abcd, e0, e1, msg0, 3);
+    msg1 = SCHEDULE!(msg1, msg2, msg3, msg0);
+    ROUNDS4!(abcd, e1, e0, msg1, 3);
+    msg2 = SCHEDULE!(msg2, msg3, msg0, msg1);
+    ROUNDS4!(abcd, e0, e1, msg2, 3);
+    msg3 = SCHEDULE!(msg3, msg0, msg1, msg2);
+    ROUNDS4!(abcd, e1, e0, msg3, 3);
+
+    // Combine state
+    e0 = _mm_sha1nexte_epu32(e0, e_save);
+    abcd = _mm_add_epi32(abcd, abcd_save);
+
+    // Save state
+    state[0] = _mm_extract_epi32(abcd, 3) as u32;
+    state[1] = _mm_extract_epi32(abcd, 2) as u32;
+    state[2] = _mm_extract_epi32(abcd, 1) as u32;
+    state[3] = _mm_extract_epi32(abcd, 0) as u32;
+    state[4] = _mm_extract_epi32(e0, 3) as u32;
+}
+
+
+#[test]
+fn test_transform() {
+    use super::{ generic, INITIAL_STATE, };
+
+    if !(is_x86_feature_detected!("sha") && is_x86_feature_detected!("sse4.1")) {
+        return;
+    }
+
+    let data = (0..1024usize).map(|i| (i * 7 + 3) as u8).collect::<Vec<u8>>();
+
+    let mut state1 = INITIAL_STATE;
+    let mut state2 = INITIAL_STATE;
+    for block in data.chunks(Sha1::BLOCK_LEN) {
+        generic::transform(&mut state1, block);
+        unsafe { transform(&mut state2, block) };
+        assert_eq!(state1, state2);
+    }
+}
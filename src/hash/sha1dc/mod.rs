@@ -0,0 +1,453 @@
+// SHA-1 with collision detection (sha1dc)
+//
+// Counter-cryptanalysis, Marc Stevens, CRYPTO 2013
+// https://marc-stevens.nl/research/papers/C13-S.pdf
+//
+// https://github.com/cr-marcstevens/sha1collisiondetection
+//
+// NOTE: 目前已知的 SHA-1 碰撞攻击（例如 SHAttered）都是基于某个扰动向量（Disturbance Vector）
+//       构造出一对存在固定消息差分的分组。对每一个分组，先正常计算压缩函数并保存第 58、65 步的中间状态，
+//       然后把扩展后的消息异或上每一个 DV 对应的消息差分，从中间状态出发向前、向后重新计算压缩函数，
+//       如果得到了相同的输出，说明当前分组是被精心构造出来的碰撞分组。
+//
+//       检测到碰撞之后（Safe-hash 模式），该分组会被额外压缩两次，使得碰撞的两个消息得到不同的摘要，
+//       这与 Git 所使用的 sha1dc 的行为一致。
+use core::convert::TryFrom;
+
+mod ubc_check;
+
+use self::ubc_check::ubc_check;
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
+
+
+const K: [u32; 4] = [ 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xca62c1d6 ];
+
+const INITIAL_STATE: [u32; 5] = [ 0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0 ];
+
+// I(52,0) 和 II(56,0) 的消息差分的前 16 个字，其余的 DV 都是它们平移、循环移位之后的结果：
+//      DV(K, B)[i] = DV(KMAX, 0)[i + KMAX - K] <<< B
+const DV_I_BASE: [u32; 16] = [
+    0x04000010, 0xe8000000, 0x0800000c, 0x18000000, 0xb800000a, 0xc8000010, 0x2c000010, 0xf4000014,
+    0xb4000008, 0x08000000, 0x9800000c, 0xd8000010, 0x08000010, 0xb8000010, 0x98000000, 0x60000000,
+];
+const DV_II_BASE: [u32; 16] = [
+    0x2600001a, 0x00000010, 0x0400001c, 0xcc000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x3c000004,
+    0xbc00001a, 0x20000010, 0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004,
+];
+
+const DV_I_DM: [u32; 96]  = expand_dm(DV_I_BASE);
+const DV_II_DM: [u32; 96] = expand_dm(DV_II_BASE);
+
+const fn expand_dm(base: [u32; 16]) -> [u32; 96] {
+    let mut w = [0u32; 96];
+    let mut i = 0;
+    while i < 96 {
+        w[i] = if i < 16 { base[i] } else { (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1) };
+        i += 1;
+    }
+    w
+}
+
+#[derive(Clone, Copy)]
+enum DvType {
+    I,
+    II,
+}
+
+// (type, K, B, 重新压缩的起始步骤)
+// 顺序与 `ubc_check` 返回的比特位一一对应。
+const SHA1_DVS: [(DvType, usize, u32, usize); 32] = [
+    (DvType::I,  43, 0, 58), (DvType::I,  44, 0, 58), (DvType::I,  45, 0, 58), (DvType::I,  46, 0, 58),
+    (DvType::I,  46, 2, 58), (DvType::I,  47, 0, 58), (DvType::I,  47, 2, 58), (DvType::I,  48, 0, 58),
+    (DvType::I,  48, 2, 58), (DvType::I,  49, 0, 58), (DvType::I,  49, 2, 58), (DvType::I,  50, 0, 65),
+    (DvType::I,  50, 2, 65), (DvType::I,  51, 0, 65), (DvType::I,  51, 2, 65), (DvType::I,  52, 0, 65),
+    (DvType::II, 45, 0, 58), (DvType::II, 46, 0, 58), (DvType::II, 46, 2, 58), (DvType::II, 47, 0, 58),
+    (DvType::II, 48, 0, 58), (DvType::II, 49, 0, 58), (DvType::II, 49, 2, 58), (DvType::II, 50, 0, 65),
+    (DvType::II, 50, 2, 65), (DvType::II, 51, 0, 65), (DvType::II, 51, 2, 65), (DvType::II, 52, 0, 65),
+    (DvType::II, 53, 0, 65), (DvType::II, 54, 0, 65), (DvType::II, 55, 0, 65), (DvType::II, 56, 0, 65),
+];
+
+
+/// SHA1 with collision detection, returns the hardened digest if a collision attack was detected.
+pub fn sha1dc<T: AsRef<[u8]>>(data: T) -> [u8; Sha1CollisionDetect::DIGEST_LEN] {
+    Sha1CollisionDetect::oneshot(data)
+}
+
+/// SHA1 with collision detection (sha1dc)
+#[derive(Clone)]
+pub struct Sha1CollisionDetect {
+    buffer: [u8; 64],
+    state: [u32; 5],
+    len: usize,      // in bytes.
+    collision: bool,
+}
+
+impl Zeroize for Sha1CollisionDetect {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+        self.collision.zeroize();
+    }
+}
+
+impl Drop for Sha1CollisionDetect {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Sha1CollisionDetect {
+    pub const BLOCK_LEN: usize  = 64;
+    pub const DIGEST_LEN: usize = 20;
+
+    pub fn new() -> Self {
+        Self {
+            buffer: [0u8; 64],
+            state: INITIAL_STATE,
+            len: 0,
+            collision: false,
+        }
+    }
+
+    /// 到目前为止，是否遇到过碰撞分组。
+    pub fn collision_detected(&self) -> bool {
+        self.collision
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        let n = self.len % Self::BLOCK_LEN;
+        if n != 0 {
+            let amt = core::cmp::min(Self::BLOCK_LEN - n, data.len());
+            self.buffer[n..n + amt].copy_from_slice(&data[..amt]);
+            self.len += amt;
+            data = &data[amt..];
+
+            if self.len % Self::BLOCK_LEN != 0 {
+                return;
+            }
+
+            let block = self.buffer;
+            self.transform(&block);
+        }
+
+        let mut blocks = data.chunks_exact(Self::BLOCK_LEN);
+        for block in &mut blocks {
+            self.transform(block);
+            self.len += Self::BLOCK_LEN;
+        }
+
+        let rem = blocks.remainder();
+        self.buffer[..rem.len()].copy_from_slice(rem);
+        self.len += rem.len();
+    }
+
+    #[inline]
+    fn transform(&mut self, block: &[u8]) {
+        if transform(&mut self.state, block) {
+            self.collision = true;
+        }
+    }
+
+    fn finalize_digest(&mut self) -> [u8; Self::DIGEST_LEN] {
+        let len_bits = u64::try_from(self.len).unwrap() * 8;
+        let n = self.len % Self::BLOCK_LEN;
+
+        let mut block = [0u8; 64];
+        block[..n].copy_from_slice(&self.buffer[..n]);
+        block[n] = 0x80;
+        if n + 1 + 8 > Self::BLOCK_LEN {
+            self.transform(&block);
+            block = [0u8; 64];
+        }
+        block[56..].copy_from_slice(&len_bits.to_be_bytes());
+        self.transform(&block);
+
+        let mut output = [0u8; Self::DIGEST_LEN];
+        for (chunk, word) in output.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        output
+    }
+
+    /// 当检测到碰撞时，返回加固（Safe-hash）之后的摘要，它与普通的 SHA1 摘要不同。
+    pub fn finalize(mut self) -> [u8; Self::DIGEST_LEN] {
+        self.finalize_digest()
+    }
+
+    /// 当检测到碰撞时，返回 `None`。
+    pub fn finalize_checked(mut self) -> Option<[u8; Self::DIGEST_LEN]> {
+        let digest = self.finalize_digest();
+        if self.collision {
+            None
+        } else {
+            Some(digest)
+        }
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize()
+    }
+
+    pub fn oneshot_checked<T: AsRef<[u8]>>(data: T) -> Option<[u8; Self::DIGEST_LEN]> {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize_checked()
+    }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || collision flag || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 20 + 8 + 1 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in big-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Sha1CollisionDetect);
+        for (chunk, word) in output[..20].chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output[20..28].copy_from_slice(&(self.len as u64).to_be_bytes());
+        output[28] = self.collision as u8;
+
+        let n = self.len % Self::BLOCK_LEN;
+        output[29..29 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Sha1CollisionDetect)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..20].chunks_exact(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&state[20..28]);
+        m.len = usize::try_from(u64::from_be_bytes(len)).ok()?;
+        m.collision = match state[28] {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+
+        let n = m.len % Self::BLOCK_LEN;
+        if state[29 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[29..29 + n]);
+
+        Some(m)
+    }
+}
+
+
+#[inline(always)]
+fn f(t: usize, b: u32, c: u32, d: u32) -> u32 {
+    match t / 20 {
+        0 => (b & c) ^ (!b & d),
+        2 => (b & c) ^ (b & d) ^ (c & d),
+        _ => b ^ c ^ d,
+    }
+}
+
+#[inline(always)]
+fn step(s: &mut [u32; 5], t: usize, w: u32) {
+    let [a, b, c, d, e] = *s;
+    let tmp = a.rotate_left(5)
+        .wrapping_add(f(t, b, c, d))
+        .wrapping_add(e)
+        .wrapping_add(K[t / 20])
+        .wrapping_add(w);
+    *s = [tmp, a, b.rotate_left(30), c, d];
+}
+
+// `step` 的逆运算：由第 t + 1 步的状态计算出第 t 步的状态。
+#[inline(always)]
+fn step_back(s: &mut [u32; 5], t: usize, w: u32) {
+    let [tmp, a, b, c, d] = *s;
+    let b = b.rotate_right(30);
+    let e = tmp.wrapping_sub(
+        a.rotate_left(5)
+            .wrapping_add(f(t, b, c, d))
+            .wrapping_add(K[t / 20])
+            .wrapping_add(w)
+    );
+    *s = [a, b, c, d, e];
+}
+
+#[inline]
+fn compress(state: &mut [u32; 5], w: &[u32; 80]) {
+    let mut s = *state;
+    for t in 0..80 {
+        step(&mut s, t, w[t]);
+    }
+    for i in 0..5 {
+        state[i] = state[i].wrapping_add(s[i]);
+    }
+}
+
+// 返回该分组是否为碰撞分组。
+fn transform(state: &mut [u32; 5], block: &[u8]) -> bool {
+    debug_assert_eq!(block.len(), Sha1CollisionDetect::BLOCK_LEN);
+
+    let mut w = [0u32; 80];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes([
+            block[i*4 + 0], block[i*4 + 1],
+            block[i*4 + 2], block[i*4 + 3],
+        ]);
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let mut s = *state;
+    let mut s58 = s;
+    let mut s65 = s;
+    for t in 0..80 {
+        if t == 58 {
+            s58 = s;
+        } else if t == 65 {
+            s65 = s;
+        }
+        step(&mut s, t, w[t]);
+    }
+    for i in 0..5 {
+        state[i] = state[i].wrapping_add(s[i]);
+    }
+
+    let mask = ubc_check(&w);
+    if mask == 0 {
+        return false;
+    }
+
+    for (i, &(dv_type, k, b, testt)) in SHA1_DVS.iter().enumerate() {
+        if mask & (1 << i) == 0 {
+            continue;
+        }
+
+        let (dm, offset) = match dv_type {
+            DvType::I  => (&DV_I_DM, 52 - k),
+            DvType::II => (&DV_II_DM, 56 - k),
+        };
+        let mut w2 = [0u32; 80];
+        for t in 0..80 {
+            w2[t] = w[t] ^ dm[t + offset].rotate_left(b);
+        }
+
+        let s_testt = if testt == 58 { s58 } else { s65 };
+
+        // 向前还原出另一个分组的输入，再向后计算出它的输出。
+        let mut ihv = s_testt;
+        for t in (0..testt).rev() {
+            step_back(&mut ihv, t, w2[t]);
+        }
+        let mut s2 = s_testt;
+        for t in testt..80 {
+            step(&mut s2, t, w2[t]);
+        }
+
+        if (0..5).all(|j| ihv[j].wrapping_add(s2[j]) == state[j]) {
+            // Safe-hash
+            compress(state, &w);
+            compress(state, &w);
+            return true;
+        }
+    }
+
+    false
+}
+
+
+#[test]
+fn test_sha1dc() {
+    use super::sha1;
+
+    let data = (0..1000usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for len in [0usize, 1, 3, 55, 56, 63, 64, 65, 119, 120, 128, 1000].iter() {
+        let msg = &data[..*len];
+        assert_eq!(sha1dc(msg), sha1(msg));
+        assert_eq!(Sha1CollisionDetect::oneshot_checked(msg), Some(sha1(msg)));
+
+        let mut m = Sha1CollisionDetect::new();
+        for chunk in msg.chunks(7) {
+            m.update(chunk);
+        }
+        assert_eq!(m.collision_detected(), false);
+        assert_eq!(m.finalize(), sha1(msg));
+    }
+}
+
+#[test]
+fn test_sha1dc_export_import_state() {
+    use super::Sha1;
+
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Sha1CollisionDetect::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Sha1CollisionDetect::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), sha1dc(&msg));
+    }
+
+    assert!(Sha1::import_state(&Sha1CollisionDetect::new().export_state()).is_none());
+    assert!(Sha1CollisionDetect::import_state(&Sha1::new().export_state()).is_none());
+}
+
+#[test]
+fn test_sha1dc_shattered() {
+    use super::sha1;
+
+    // https://shattered.io/ 中两个 PDF 文件的前 320 字节（相同的 192 字节的前缀 + 两个碰撞分组）。
+    let prefix = hex::decode("\
+255044462d312e330a25e2e3cfd30a0a0a312030206f626a0a3c3c2f57696474\
+682032203020522f4865696768742033203020522f547970652034203020522f\
+537562747970652035203020522f46696c7465722036203020522f436f6c6f72\
+53706163652037203020522f4c656e6774682038203020522f42697473506572\
+436f6d706f6e656e7420383e3e0a73747265616d0affd8fffe00245348412d31\
+20697320646561642121212121852fec092339759c39b1a1c63c4c97e1fffe01").unwrap();
+    let block1 = hex::decode("\
+7346dc9166b67e118f029ab621b2560ff9ca67cca8c7f85ba84c79030c2b3de2\
+18f86db3a90901d5df45c14f26fedfb3dc38e96ac22fe7bd728f0e45bce046d2\
+3c570feb141398bb552ef5a0a82be331fea48037b8b5d71f0e332edf93ac3500\
+eb4ddc0decc1a864790c782c76215660dd309791d06bd0af3f98cda4bc4629b1").unwrap();
+    let block2 = hex::decode("\
+7f46dc93a6b67e013b029aaa1db2560b45ca67d688c7f84b8c4c791fe02b3df6\
+14f86db1690901c56b45c1530afedfb76038e972722fe7ad728f0e4904e046c2\
+30570fe9d41398abe12ef5bc942be33542a4802d98b5d70f2a332ec37fac3514\
+e74ddc0f2cc1a874cd0c78305a21566461309789606bd0bf3f98cda8044629a1").unwrap();
+
+    let msg1 = [&prefix[..], &block1[..]].concat();
+    let msg2 = [&prefix[..], &block2[..]].concat();
+    assert_ne!(msg1, msg2);
+    assert_eq!(sha1(&msg1), sha1(&msg2));
+
+    let mut m = Sha1CollisionDetect::new();
+    m.update(&prefix);
+    assert_eq!(m.collision_detected(), false);
+    m.update(&block1);
+    assert_eq!(m.collision_detected(), true);
+
+    // 导出的状态会保留碰撞标记
+    let m = Sha1CollisionDetect::import_state(&m.export_state()).unwrap();
+    assert_eq!(m.collision_detected(), true);
+    assert_eq!(m.finalize(), sha1dc(&msg1));
+
+    assert_eq!(Sha1CollisionDetect::oneshot_checked(&msg1), None);
+    assert_eq!(Sha1CollisionDetect::oneshot_checked(&msg2), None);
+
+    // 加固之后的摘要
+    assert_eq!(&sha1dc(&msg1)[..], &hex::decode("7117b3cb9225aaf0d8ef1a40e493957b0bf8693d").unwrap()[..]);
+    assert_eq!(&sha1dc(&msg2)[..], &hex::decode("29f38ae9fd98e2931120fa0bf213e024250d3f6a").unwrap()[..]);
+}
@@ -1,8 +1,12 @@
-// FIPS-180-2 compliant SHA-256 implementation
+// FIPS-180-2 compliant SHA-224/256 implementation
 // 
 // The SHA-256 Secure Hash Standard was published by NIST in 2002.
 // http://csrc.nist.gov/publications/fips/fips180-2/fips180-2.pdf
+use crate::cpu;
+
 use core::convert::TryFrom;
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
 
 
 #[allow(dead_code)]
@@ -11,6 +15,11 @@ mod generic;
 mod x86;
 #[cfg(target_arch = "aarch64")]
 mod aarch64;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86_multibuffer;
+mod multibuffer;
+
+pub use self::multibuffer::*;
 
 // Round constants
 const K32: [u32; 64] = [
@@ -29,6 +38,18 @@ const INITIAL_STATE: [u32; 8] = [
     0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
 ];
 
+// SHA-224
+const SHA224_INITIAL_STATE: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939,
+    0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
+
+
+/// SHA2-224
+pub fn sha224<T: AsRef<[u8]>>(data: T) -> [u8; Sha224::DIGEST_LEN] {
+    Sha224::oneshot(data)
+}
+
 
 /// SHA2-256
 pub fn sha256<T: AsRef<[u8]>>(data: T) -> [u8; Sha256::DIGEST_LEN] {
@@ -36,46 +57,99 @@ pub fn sha256<T: AsRef<[u8]>>(data: T) -> [u8; Sha256::DIGEST_LEN] {
 }
 
 
-// NOTE: 直接使用 SHA-NI 来加速。
-#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sha"))]
-#[inline]
-fn transform(state: &mut [u32; 8], block: &[u8]) {
-    x86::transform(state, block)
+// NOTE: 平台实现的函数表，根据 Runtime 检测到的 CPU 特性（由 `crate::cpu` 缓存）来选择，
+//       这样，没有开启 `+sha` 编译出来的二进制文件同样可以使用 SHA-NI 来加速。
+struct Backend {
+    transform: unsafe fn(&mut [u32; 8], &[u8]),
 }
 
-// NOTE: 当编译时，没有开启 `+sha` 指令时，通过 Runtime 来判断
-//       这样，会有性能损失。后面需要考虑是否有更好的方式。
-#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(target_feature = "sha")))]
+const GENERIC: Backend = Backend { transform: generic::transform };
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const SHANI: Backend = Backend { transform: x86::transform };
+
+#[cfg(target_arch = "aarch64")]
+const AARCH64: Backend = Backend { transform: aarch64::transform };
+
 #[inline]
-fn transform(state: &mut [u32; 8], block: &[u8]) {
-    if is_x86_feature_detected!("sha") {
-        x86::transform(state, block)
-    } else {
-        generic::transform(state, block)
+fn backend() -> &'static Backend {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if cpu::has(cpu::SHA) {
+            return &SHANI;
+        }
     }
-}
 
+    #[cfg(target_arch = "aarch64")]
+    {
+        if cpu::has(cpu::SHA) {
+            return &AARCH64;
+        }
+    }
 
-#[cfg(all(target_arch = "aarch64", target_feature = "crypto"))]
-#[inline]
-fn transform(state: &mut [u32; 8], block: &[u8]) {
-    aarch64::transform(state, block)
+    &GENERIC
 }
-#[cfg(all(target_arch = "aarch64", not(target_feature = "crypto")))]
+
 #[inline]
 fn transform(state: &mut [u32; 8], block: &[u8]) {
-    generic::transform(state, block)
+    // NOTE: `backend` 只会返回当前 CPU 支持的实现。
+    unsafe { (backend().transform)(state, block) }
 }
 
 
-// Other platform (e.g: MIPS)
-#[cfg(all(
-    not(any(target_arch = "x86", target_arch = "x86_64")),
-    not(target_arch = "aarch64"),
-))]
-#[inline]
-fn transform(state: &mut [u32; 8], block: &[u8]) {
-    generic::transform(state, block)
+/// SHA2-224
+#[derive(Clone)]
+pub struct Sha224 {
+    inner: Sha256,
+}
+
+impl Zeroize for Sha224 {
+    fn zeroize(&mut self) {
+        self.inner.zeroize();
+    }
+}
+
+impl Sha224 {
+    pub const BLOCK_LEN: usize  = 64;
+    pub const DIGEST_LEN: usize = 28;
+
+    pub fn new() -> Self {
+        let inner = Sha256 {
+            buffer: [0u8; 64],
+            state: SHA224_INITIAL_STATE,
+            len: 0,
+        };
+        Self { inner }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data)
+    }
+
+    pub fn finalize(self) -> [u8; Self::DIGEST_LEN] {
+        let h = self.inner.finalize();
+
+        let mut output = [0u8; Self::DIGEST_LEN];
+        output.copy_from_slice(&h[..Self::DIGEST_LEN]);
+
+        output
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize()
+    }
+
+    pub const STATE_LEN: usize = Sha256::STATE_LEN;
+
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        self.inner.export_state_with(StateId::Sha224)
+    }
+
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        Sha256::import_state_with(state, StateId::Sha224).map(|inner| Self { inner })
+    }
 }
 
 
@@ -87,6 +161,20 @@ pub struct Sha256 {
     len: usize,      // in bytes.
 }
 
+impl Zeroize for Sha256 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 impl Sha256 {
     pub const BLOCK_LEN: usize  = 64;
     pub const DIGEST_LEN: usize = 32;
@@ -119,10 +207,7 @@ impl Sha256 {
             } else {
                 transform(&mut self.state, &self.buffer);
 
-                let data = &data[i..];
-                if data.len() > 0 {
-                    return self.update(data);
-                }
+                return self.update(&data[i..]);
             }
         }
 
@@ -191,6 +276,54 @@ impl Sha256 {
         m.update(data.as_ref());
         m.finalize()
     }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 32 + 8 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in big-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        self.export_state_with(StateId::Sha256)
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        Self::import_state_with(state, StateId::Sha256)
+    }
+
+    fn export_state_with(&self, id: StateId) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, id);
+        for (chunk, word) in output[..32].chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output[32..40].copy_from_slice(&(self.len as u64).to_be_bytes());
+
+        let n = self.len % Self::BLOCK_LEN;
+        output[40..40 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    fn import_state_with(state: &[u8], id: StateId) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, id)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..32].chunks_exact(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&state[32..40]);
+        m.len = usize::try_from(u64::from_be_bytes(len)).ok()?;
+
+        let n = m.len % Self::BLOCK_LEN;
+        if state[40 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[40..40 + n]);
+
+        Some(m)
+    }
 }
 
 
@@ -222,6 +355,19 @@ fn test_sha256_long_message() {
     assert_eq!(Sha256::oneshot(&msg), digest);
 }
 
+#[test]
+fn test_sha224() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let suites: &[(&[u8], &str)] = &[
+        (b"abc", "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"),
+        (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525"),
+        (&[b'a'; 1000_000], "20794655980c91d8bbb4c1ea97618a4bf03f42581948b2ee4ee7ad67"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sha224(msg)), digest);
+    }
+}
+
 #[test]
 fn test_transform_block() {
     let mut state = INITIAL_STATE;
@@ -229,4 +375,71 @@ fn test_transform_block() {
 
     transform(&mut state, &data);
     assert_eq!(state, [3663108286, 398046313, 1647531929, 2006957770, 2363872401, 3235013187, 3137272298, 406301144]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_transform_backends() {
+    let data = (0..1024usize).map(|i| (i * 7 + 3) as u8).collect::<Vec<u8>>();
+
+    let mut state1 = INITIAL_STATE;
+    let mut state2 = INITIAL_STATE;
+    for block in data.chunks(Sha256::BLOCK_LEN) {
+        generic::transform(&mut state1, block);
+        transform(&mut state2, block);
+        assert_eq!(state1, state2);
+    }
+}
+
+#[test]
+fn test_sha256_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Sha256::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Sha256::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), sha256(&msg));
+    }
+
+    let mut m = Sha224::new();
+    m.update(&msg[..100]);
+    let mut m = Sha224::import_state(&m.export_state()).unwrap();
+    m.update(&msg[100..]);
+    assert_eq!(m.finalize(), sha224(&msg));
+
+    // Layout: algorithm id || version || chaining value || message length || buffered bytes
+    let mut m = Sha256::new();
+    m.update(b"abc");
+    let state = m.export_state();
+    assert_eq!(state.len(), 2 + 32 + 8 + 64);
+    assert_eq!(&state[..2], &[0x0c, 0x01]);
+    assert_eq!(&state[2..6], &[0x6a, 0x09, 0xe6, 0x67]);
+    assert_eq!(&state[34..42], &3u64.to_be_bytes());
+    assert_eq!(&state[42..45], b"abc");
+    assert!(state[45..].iter().all(|&b| b == 0));
+
+    assert!(Sha256::import_state(&state[1..]).is_none());
+    let mut bad = state;
+    bad[45] = 1;
+    assert!(Sha256::import_state(&bad).is_none());
+
+    // 拒绝其它版本和其它算法导出的状态
+    let mut bad = state;
+    bad[1] = 2;
+    assert!(Sha256::import_state(&bad).is_none());
+    assert!(Sha224::import_state(&state).is_none());
+    assert!(Sha256::import_state(&Sha224::new().export_state()).is_none());
+}
+
+#[test]
+fn test_sha256_bytewise_update() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let mut m = Sha256::new();
+    for byte in msg.chunks(1) {
+        m.update(byte);
+    }
+    assert_eq!(m.finalize(), sha256(&msg));
+}
@@ -0,0 +1,232 @@
+// Multi-buffer SHA-256
+//
+// 同时计算多个互相独立的消息的摘要，适用于大量的短消息（例如：去重用的 Key）。
+// 每一个消息占用一个 Lane，消息长度不相同时，已经结束的 Lane 计算的结果会被丢弃。
+use super::{ Sha256, INITIAL_STATE, };
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::cpu;
+
+
+// NOTE: 支持 SHA-NI 时，逐个消息使用 SHA-NI 计算的吞吐量比 SSE2/AVX2 的多个 Lane 更高，
+//       这时（以及没有 SIMD Lane 实现的平台）直接逐个计算，也不用处理长度不相同的 Lane。
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn use_lanes_x4() -> bool {
+    cpu::has(cpu::SSE2) && !cpu::has(cpu::SHA)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn use_lanes_x8() -> bool {
+    cpu::has(cpu::AVX2) && !cpu::has(cpu::SHA)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn transform_x4(states: &mut [[u32; 8]; 4], blocks: &[&[u8]; 4]) {
+    if cpu::has(cpu::SSE2) {
+        unsafe { super::x86_multibuffer::transform_x4(states, blocks) }
+    } else {
+        for lane in 0..4 {
+            super::transform(&mut states[lane], blocks[lane]);
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn transform_x8(states: &mut [[u32; 8]; 8], blocks: &[&[u8]; 8]) {
+    if cpu::has(cpu::AVX2) {
+        unsafe { super::x86_multibuffer::transform_x8(states, blocks) }
+    } else {
+        for lane in 0..8 {
+            super::transform(&mut states[lane], blocks[lane]);
+        }
+    }
+}
+
+// Other platform
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn use_lanes_x4() -> bool {
+    false
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn use_lanes_x8() -> bool {
+    false
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn transform_x4(states: &mut [[u32; 8]; 4], blocks: &[&[u8]; 4]) {
+    for lane in 0..4 {
+        super::transform(&mut states[lane], blocks[lane]);
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn transform_x8(states: &mut [[u32; 8]; 8], blocks: &[&[u8]; 8]) {
+    for lane in 0..8 {
+        super::transform(&mut states[lane], blocks[lane]);
+    }
+}
+
+
+macro_rules! impl_sha256_multibuffer {
+    ($fname:ident, $lanes_fname:ident, $lanes:tt, $use_lanes:ident, $transform:ident) => {
+        /// SHA2-256 of independent messages, one message per SIMD lane.
+        pub fn $fname(messages: [&[u8]; $lanes]) -> [[u8; Sha256::DIGEST_LEN]; $lanes] {
+            if $use_lanes() {
+                return $lanes_fname(messages);
+            }
+
+            let mut digests = [[0u8; Sha256::DIGEST_LEN]; $lanes];
+            for (digest, m) in digests.iter_mut().zip(messages.iter()) {
+                *digest = Sha256::oneshot(m);
+            }
+            digests
+        }
+
+        fn $lanes_fname(messages: [&[u8]; $lanes]) -> [[u8; Sha256::DIGEST_LEN]; $lanes] {
+            const BLOCK_LEN: usize = Sha256::BLOCK_LEN;
+
+            // 每个消息最后的 1 ~ 2 个分组（包含填充和长度）。
+            let mut tails = [[0u8; BLOCK_LEN * 2]; $lanes];
+            let mut full_blocks = [0usize; $lanes];
+            let mut total_blocks = [0usize; $lanes];
+
+            for lane in 0..$lanes {
+                let m = messages[lane];
+                let n = m.len() / BLOCK_LEN;
+                let rem = &m[n * BLOCK_LEN..];
+
+                let tail_len = if rem.len() + 1 + 8 > BLOCK_LEN { BLOCK_LEN * 2 } else { BLOCK_LEN };
+                let len_bits = (m.len() as u64).wrapping_mul(8);
+
+                let tail = &mut tails[lane];
+                tail[..rem.len()].copy_from_slice(rem);
+                tail[rem.len()] = 0x80;
+                tail[tail_len - 8..tail_len].copy_from_slice(&len_bits.to_be_bytes());
+
+                full_blocks[lane] = n;
+                total_blocks[lane] = n + tail_len / BLOCK_LEN;
+            }
+
+            let min_blocks = total_blocks.iter().min().cloned().unwrap_or(0);
+            let max_blocks = total_blocks.iter().max().cloned().unwrap_or(0);
+
+            let dummy = [0u8; BLOCK_LEN];
+            let mut states = [INITIAL_STATE; $lanes];
+
+            for j in 0..max_blocks {
+                let mut blocks: [&[u8]; $lanes] = [&dummy; $lanes];
+                for lane in 0..$lanes {
+                    let n = full_blocks[lane];
+                    if j < n {
+                        blocks[lane] = &messages[lane][j * BLOCK_LEN..j * BLOCK_LEN + BLOCK_LEN];
+                    } else if j < total_blocks[lane] {
+                        let k = j - n;
+                        blocks[lane] = &tails[lane][k * BLOCK_LEN..k * BLOCK_LEN + BLOCK_LEN];
+                    }
+                }
+
+                if j < min_blocks {
+                    $transform(&mut states, &blocks);
+                } else {
+                    let saved = states;
+                    $transform(&mut states, &blocks);
+                    for lane in 0..$lanes {
+                        if j >= total_blocks[lane] {
+                            states[lane] = saved[lane];
+                        }
+                    }
+                }
+            }
+
+            let mut digests = [[0u8; Sha256::DIGEST_LEN]; $lanes];
+            for lane in 0..$lanes {
+                for (chunk, word) in digests[lane].chunks_exact_mut(4).zip(states[lane].iter()) {
+                    chunk.copy_from_slice(&word.to_be_bytes());
+                }
+            }
+
+            digests
+        }
+    }
+}
+
+impl_sha256_multibuffer!(sha256x4, sha256x4_lanes, 4, use_lanes_x4, transform_x4);
+impl_sha256_multibuffer!(sha256x8, sha256x8_lanes, 8, use_lanes_x8, transform_x8);
+
+
+/// SHA2-256 of each message in `messages`, computed 8 (then 4) at a time.
+pub fn sha256_batch(messages: &[&[u8]], digests: &mut [[u8; Sha256::DIGEST_LEN]]) {
+    assert_eq!(messages.len(), digests.len());
+
+    let mut messages = messages;
+    let mut digests = digests;
+
+    while messages.len() >= 8 {
+        let (m, m_rem) = messages.split_at(8);
+        let (d, d_rem) = digests.split_at_mut(8);
+        let r = sha256x8([m[0], m[1], m[2], m[3], m[4], m[5], m[6], m[7]]);
+        d.copy_from_slice(&r);
+
+        messages = m_rem;
+        digests = d_rem;
+    }
+
+    if messages.len() >= 4 {
+        let (m, m_rem) = messages.split_at(4);
+        let (d, d_rem) = digests.split_at_mut(4);
+        let r = sha256x4([m[0], m[1], m[2], m[3]]);
+        d.copy_from_slice(&r);
+
+        messages = m_rem;
+        digests = d_rem;
+    }
+
+    for (m, d) in messages.iter().zip(digests.iter_mut()) {
+        *d = Sha256::oneshot(m);
+    }
+}
+
+
+#[test]
+fn test_sha256_multibuffer() {
+    let data = (0..1000usize).map(|i| i as u8).collect::<Vec<u8>>();
+    // 覆盖了空消息、需要额外填充分组的长度以及长度不相同的消息。
+    let lens = [0usize, 1, 55, 56, 63, 64, 65, 119, 120, 128, 200, 1000, 3, 64, 0, 999];
+
+    let mut messages = [&data[..0]; 8];
+    for chunk in lens.chunks(8) {
+        for (i, len) in chunk.iter().enumerate() {
+            messages[i] = &data[..*len];
+        }
+
+        let digests = sha256x8(messages);
+        for i in 0..8 {
+            assert_eq!(digests[i], Sha256::oneshot(messages[i]));
+        }
+
+        let digests = sha256x4([messages[0], messages[1], messages[2], messages[3]]);
+        for i in 0..4 {
+            assert_eq!(digests[i], Sha256::oneshot(messages[i]));
+        }
+
+        // 支持 SHA-NI 时 `sha256x8` 和 `sha256x4` 不会使用 SIMD Lane。
+        assert_eq!(sha256x8_lanes(messages), sha256x8(messages));
+        let messages = [messages[4], messages[5], messages[6], messages[7]];
+        assert_eq!(sha256x4_lanes(messages), sha256x4(messages));
+    }
+
+    let messages = (0..23usize).map(|i| &data[..i * 37]).collect::<Vec<&[u8]>>();
+    let mut digests = vec![[0u8; 32]; messages.len()];
+    sha256_batch(&messages, &mut digests);
+    for (m, d) in messages.iter().zip(digests.iter()) {
+        assert_eq!(d, &Sha256::oneshot(m));
+    }
+}
@@ -0,0 +1,166 @@
+// Multi-buffer SHA-256: 在 SIMD 寄存器的每一个 Lane 里面各自计算一个独立消息的压缩函数。
+//
+// Fast SHA-256 Implementations on Intel® Architecture Processors
+// https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/sha-256-implementations-paper.pdf
+use super::K32;
+use super::Sha256;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+
+macro_rules! impl_transform_multi {
+    (
+        $(#[$attr:meta])*
+        $fname:ident, $lanes:tt, $vec:ty,
+        $set1:ident, $loadu:ident, $storeu:ident,
+        $add:ident, $and:ident, $andnot:ident, $or:ident, $xor:ident,
+        $srli:ident, $slli:ident
+    ) => {
+        $(#[$attr])*
+        pub unsafe fn $fname(states: &mut [[u32; 8]; $lanes], blocks: &[&[u8]; $lanes]) {
+            macro_rules! ROTR {
+                ($x:expr, $n:literal) => (
+                    $or($srli($x, $n), $slli($x, 32 - $n))
+                )
+            }
+            macro_rules! CH {
+                ($x:expr, $y:expr, $z:expr) => (
+                    $xor($and($x, $y), $andnot($x, $z))
+                )
+            }
+            macro_rules! MAJ {
+                ($x:expr, $y:expr, $z:expr) => (
+                    $or($and($x, $y), $and($z, $or($x, $y)))
+                )
+            }
+            macro_rules! EP0 {
+                ($v:expr) => (
+                    $xor($xor(ROTR!($v, 2), ROTR!($v, 13)), ROTR!($v, 22))
+                )
+            }
+            macro_rules! EP1 {
+                ($v:expr) => (
+                    $xor($xor(ROTR!($v, 6), ROTR!($v, 11)), ROTR!($v, 25))
+                )
+            }
+            macro_rules! SIG0 {
+                ($v:expr) => (
+                    $xor($xor(ROTR!($v, 7), ROTR!($v, 18)), $srli($v, 3))
+                )
+            }
+            macro_rules! SIG1 {
+                ($v:expr) => (
+                    $xor($xor(ROTR!($v, 17), ROTR!($v, 19)), $srli($v, 10))
+                )
+            }
+
+            for block in blocks.iter() {
+                debug_assert_eq!(block.len(), Sha256::BLOCK_LEN);
+            }
+
+            // 转置：第 i 个向量保存所有 Lane 的第 i 个字。
+            let mut tmp = [0u32; $lanes];
+            let mut w: [$vec; 16] = [$set1(0); 16];
+            for i in 0..16 {
+                for lane in 0..$lanes {
+                    let p = &blocks[lane][i * 4..i * 4 + 4];
+                    tmp[lane] = u32::from_be_bytes([p[0], p[1], p[2], p[3]]);
+                }
+                w[i] = $loadu(tmp.as_ptr() as *const $vec);
+            }
+
+            let mut s: [$vec; 8] = [$set1(0); 8];
+            for i in 0..8 {
+                for lane in 0..$lanes {
+                    tmp[lane] = states[lane][i];
+                }
+                s[i] = $loadu(tmp.as_ptr() as *const $vec);
+            }
+
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = s;
+
+            for t in 0..64 {
+                if t >= 16 {
+                    w[t & 15] = $add(
+                        $add(SIG1!(w[(t - 2) & 15]), w[(t - 7) & 15]),
+                        $add(SIG0!(w[(t - 15) & 15]), w[t & 15]),
+                    );
+                }
+
+                let t1 = $add(
+                    $add(h, EP1!(e)),
+                    $add($add(CH!(e, f, g), $set1(K32[t] as i32)), w[t & 15]),
+                );
+                let t2 = $add(EP0!(a), MAJ!(a, b, c));
+                h = g;
+                g = f;
+                f = e;
+                e = $add(d, t1);
+                d = c;
+                c = b;
+                b = a;
+                a = $add(t1, t2);
+            }
+
+            let v = [a, b, c, d, e, f, g, h];
+            for i in 0..8 {
+                $storeu(tmp.as_mut_ptr() as *mut $vec, $add(s[i], v[i]));
+                for lane in 0..$lanes {
+                    states[lane][i] = tmp[lane];
+                }
+            }
+        }
+    }
+}
+
+impl_transform_multi!(
+    #[target_feature(enable = "sse2")]
+    transform_x4, 4, __m128i,
+    _mm_set1_epi32, _mm_loadu_si128, _mm_storeu_si128,
+    _mm_add_epi32, _mm_and_si128, _mm_andnot_si128, _mm_or_si128, _mm_xor_si128,
+    _mm_srli_epi32, _mm_slli_epi32
+);
+
+impl_transform_multi!(
+    #[target_feature(enable = "avx2")]
+    transform_x8, 8, __m256i,
+    _mm256_set1_epi32, _mm256_loadu_si256, _mm256_storeu_si256,
+    _mm256_add_epi32, _mm256_and_si256, _mm256_andnot_si256, _mm256_or_si256, _mm256_xor_si256,
+    _mm256_srli_epi32, _mm256_slli_epi32
+);
+
+
+#[test]
+fn test_transform_multi() {
+    use super::{ generic, INITIAL_STATE, };
+
+    let data = (0..512usize).map(|i| (i * 7) as u8).collect::<Vec<u8>>();
+    let blocks = data.chunks(Sha256::BLOCK_LEN).collect::<Vec<&[u8]>>();
+
+    let mut expected = [INITIAL_STATE; 8];
+    for lane in 0..8 {
+        expected[lane][lane] ^= lane as u32;
+        generic::transform(&mut expected[lane], blocks[lane]);
+    }
+
+    if is_x86_feature_detected!("sse2") {
+        let mut states = [INITIAL_STATE; 4];
+        for lane in 0..4 {
+            states[lane][lane] ^= lane as u32;
+        }
+        unsafe { transform_x4(&mut states, &[blocks[0], blocks[1], blocks[2], blocks[3]]) };
+        assert_eq!(&states[..], &expected[..4]);
+    }
+
+    if is_x86_feature_detected!("avx2") {
+        let mut states = [INITIAL_STATE; 8];
+        for lane in 0..8 {
+            states[lane][lane] ^= lane as u32;
+        }
+        unsafe { transform_x8(&mut states, &[blocks[0], blocks[1], blocks[2], blocks[3], blocks[4], blocks[5], blocks[6], blocks[7]]) };
+        assert_eq!(states, expected);
+    }
+}
@@ -1,10 +1,130 @@
+// AArch64 ARMv8.2-A SHA512 (FEAT_SHA512)
+// https://developer.arm.com/documentation/ddi0596/2021-12/SIMD-FP-Instructions/SHA512H--SHA512-Hash-update-part-1-
+//
+// NOTE: Rust 把 SHA512 和 SHA3 的扩展指令合并成了 `sha3` 这一个 Target Feature。
+use super::K64;
+use super::Sha512;
 
-// AArch64 ARMv8.4-A
-// https://en.wikipedia.org/wiki/AArch64#ARMv8.4-A
-// 
-// NOTE: 
-//      AArch64 架构在 ARMv8.4-A 版本里面添加了对 SHA2-512 的加速指令。
-//      在 Rust 的 `core::arch::aarch64` 里面增加了该指令后，可以考虑实现。
-//      目前 Rust 的 `std_detect` 库只支持到 AArch64 v8.3a。
-// 
-// https://github.com/rust-lang/stdarch/blob/master/crates/std_detect/src/detect/arch/aarch64.rs#L13
\ No newline at end of file
+use core::arch::aarch64::*;
+
+
+// NOTE: 调用方需要先确认 CPU 支持 SHA512 指令（`crate::cpu::has`）。
+#[inline]
+pub unsafe fn transform(state: &mut [u64; 8], block: &[u8]) {
+    debug_assert_eq!(state.len(), 8);
+    debug_assert_eq!(block.len(), Sha512::BLOCK_LEN);
+
+    transform_sha512(state, block)
+}
+
+// Process a block with the SHA-512 algorithm.
+// Based on https://github.com/ARMmbed/mbedtls/blob/development/library/sha512.c
+#[target_feature(enable = "neon,sha3")]
+unsafe fn transform_sha512(state: &mut [u64; 8], block: &[u8]) {
+    // 2 轮：$s 为 W[t..t+2]，`sha512h` 计算 T1，`sha512h2` 计算 T1 + T2。
+    macro_rules! ROUNDS2 {
+        ($ab:ident, $cd:ident, $ef:ident, $gh:ident, $s:expr, $t:expr) => {
+            let initial_sum = vaddq_u64($s, vld1q_u64(K64.as_ptr().add($t)));
+            let sum = vaddq_u64(vextq_u64(initial_sum, initial_sum, 1), $gh);
+            let intermed = vsha512hq_u64(sum, vextq_u64($ef, $gh, 1), vextq_u64($cd, $ef, 1));
+            $gh = vsha512h2q_u64(intermed, $cd, $ab);
+            $cd = vaddq_u64($cd, intermed);
+        }
+    }
+
+    // W[t..t+2] = S1(W[t-2]) + W[t-7] + S0(W[t-15]) + W[t-16]
+    macro_rules! SCHEDULE {
+        ($s0:expr, $s1:expr, $s4:expr, $s5:expr, $s7:expr) => {
+            vsha512su1q_u64(vsha512su0q_u64($s0, $s1), $s7, vextq_u64($s4, $s5, 1))
+        }
+    }
+
+    macro_rules! LOAD {
+        ($offset:literal) => {
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.as_ptr().add($offset))))
+        }
+    }
+
+    // Load state
+    let mut ab = vld1q_u64(state.as_ptr().add(0));
+    let mut cd = vld1q_u64(state.as_ptr().add(2));
+    let mut ef = vld1q_u64(state.as_ptr().add(4));
+    let mut gh = vld1q_u64(state.as_ptr().add(6));
+
+    // Save state
+    let ab_save = ab;
+    let cd_save = cd;
+    let ef_save = ef;
+    let gh_save = gh;
+
+    let mut s0 = LOAD!(0);
+    let mut s1 = LOAD!(16);
+    let mut s2 = LOAD!(32);
+    let mut s3 = LOAD!(48);
+    let mut s4 = LOAD!(64);
+    let mut s5 = LOAD!(80);
+    let mut s6 = LOAD!(96);
+    let mut s7 = LOAD!(112);
+
+    // Rounds 0-15
+    ROUNDS2!(ab, cd, ef, gh, s0, 0);
+    ROUNDS2!(gh, ab, cd, ef, s1, 2);
+    ROUNDS2!(ef, gh, ab, cd, s2, 4);
+    ROUNDS2!(cd, ef, gh, ab, s3, 6);
+    ROUNDS2!(ab, cd, ef, gh, s4, 8);
+    ROUNDS2!(gh, ab, cd, ef, s5, 10);
+    ROUNDS2!(ef, gh, ab, cd, s6, 12);
+    ROUNDS2!(cd, ef, gh, ab, s7, 14);
+
+    // Rounds 16-79
+    for t in (16..80).step_by(16) {
+        s0 = SCHEDULE!(s0, s1, s4, s5, s7);
+        ROUNDS2!(ab, cd, ef, gh, s0, t);
+        s1 = SCHEDULE!(s1, s2, s5, s6, s0);
+        ROUNDS2!(gh, ab, cd, ef, s1, t + 2);
+        s2 = SCHEDULE!(s2, s3, s6, s7, s1);
+        ROUNDS2!(ef, gh, ab, cd, s2, t + 4);
+        s3 = SCHEDULE!(s3, s4, s7, s0, s2);
+        ROUNDS2!(cd, ef, gh, ab, s3, t + 6);
+        s4 = SCHEDULE!(s4, s5, s0, s1, s3);
+        ROUNDS2!(ab, cd, ef, gh, s4, t + 8);
+        s5 = SCHEDULE!(s5, s6, s1, s2, s4);
+        ROUNDS2!(gh, ab, cd, ef, s5, t + 10);
+        s6 = SCHEDULE!(s6, s7, s2, s3, s5);
+        ROUNDS2!(ef, gh, ab, cd, s6, t + 12);
+        s7 = SCHEDULE!(s7, s0, s3, s4, s6);
+        ROUNDS2!(cd, ef, gh, ab, s7, t + 14);
+    }
+
+    // Combine state
+    ab = vaddq_u64(ab, ab_save);
+    cd = vaddq_u64(cd, cd_save);
+    ef = vaddq_u64(ef, ef_save);
+    gh = vaddq_u64(gh, gh_save);
+
+    // Save state
+    vst1q_u64(state.as_mut_ptr().add(0), ab);
+    vst1q_u64(state.as_mut_ptr().add(2), cd);
+    vst1q_u64(state.as_mut_ptr().add(4), ef);
+    vst1q_u64(state.as_mut_ptr().add(6), gh);
+}
+
+
+#[test]
+fn test_transform() {
+    use super::{ generic, SHA512_INITIAL_STATE, };
+
+    if !is_aarch64_feature_detected!("sha3") {
+        return;
+    }
+
+    let data = (0..1024usize).map(|i| (i * 7 + 3) as u8).collect::<Vec<u8>>();
+
+    let mut state1 = SHA512_INITIAL_STATE;
+    let mut state2 = SHA512_INITIAL_STATE;
+    for block in data.chunks(Sha512::BLOCK_LEN) {
+        generic::transform(&mut state1, block);
+        unsafe { transform(&mut state2, block) };
+        assert_eq!(state1, state2);
+    }
+}
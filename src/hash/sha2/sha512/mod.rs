@@ -2,11 +2,16 @@
 // 
 // The SHA-512 Secure Hash Standard was published by NIST in 2002.
 // http://csrc.nist.gov/publications/fips/fips180-2/fips180-2.pdf
+//
+// SHA-512/224 and SHA-512/256 (FIPS 180-4, 5.3.6)
+// https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
 use core::convert::TryFrom;
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
 
 
 // NOTE:
-//      1. AArch64 架构在 ARMv8.4-A 版本里面增加了对 SHA2-512 的支持。
+//      1. AArch64 架构在 ARMv8.2-A 版本里面增加了对 SHA2-512 的支持。
 //      2. X86/X86_64 架构的 SHA-NI 目前并不包含 SHA2-512。 
 mod generic;
 #[cfg(target_arch = "aarch64")]
@@ -50,20 +55,28 @@ const SHA384_INITIAL_STATE: [u64; 8] = [
     0x67332667FFC00B31, 0x8EB44A8768581511, 0xDB0C2E0D64F98FA7, 0x47B5481DBEFA4FA4,
 ];
 
+// SHA-512/224
+const SHA512_224_INITIAL_STATE: [u64; 8] = [
+    0x8C3D37C819544DA2, 0x73E1996689DCD4D6, 0x1DFAB7AE32FF9C82, 0x679DD514582F9FCF,
+    0x0F6D2B697BD44DA8, 0x77E36F7304C48942, 0x3F9D85A86A1D36C8, 0x1112E6AD91D692A1,
+];
+
+// SHA-512/256
+const SHA512_256_INITIAL_STATE: [u64; 8] = [
+    0x22312194FC2BF72C, 0x9F555FA3C84C64C2, 0x2393B86B6F53B151, 0x963877195940EABD,
+    0x96283EE2A88EFFE3, 0xBE5E1E2553863992, 0x2B0199FC2C85B8AA, 0x0EB72DDC81C52CA2,
+];
 
+
+// NOTE: 通过 Runtime 检测到的 CPU 特性（由 `crate::cpu` 缓存）来判断是否支持 SHA512 扩展指令。
 #[cfg(target_arch = "aarch64")]
 #[inline]
 fn transform(state: &mut [u64; 8], block: &[u8]) {
-    // NOTE: 等待 Rust 的 `std_detect` 库支持 AArch64 v8.4-A。
-    // https://github.com/rust-lang/stdarch/blob/master/crates/std_detect/src/detect/arch/aarch64.rs#L13
-    
-    generic::transform(state, block)
-
-    // if is_aarch64_feature_detected!("sha512") {
-    //     aarch64::transform(state, block)
-    // } else {
-    //     generic::transform(state, block)
-    // }
+    if crate::cpu::has(crate::cpu::SHA512) {
+        unsafe { aarch64::transform(state, block) }
+    } else {
+        generic::transform(state, block)
+    }
 }
 
 #[cfg(not(target_arch = "aarch64"))]
@@ -78,6 +91,16 @@ pub fn sha384<T: AsRef<[u8]>>(data: T) -> [u8; Sha384::DIGEST_LEN] {
     Sha384::oneshot(data)
 }
 
+/// SHA2-512/224
+pub fn sha512_224<T: AsRef<[u8]>>(data: T) -> [u8; Sha512_224::DIGEST_LEN] {
+    Sha512_224::oneshot(data)
+}
+
+/// SHA2-512/256
+pub fn sha512_256<T: AsRef<[u8]>>(data: T) -> [u8; Sha512_256::DIGEST_LEN] {
+    Sha512_256::oneshot(data)
+}
+
 /// SHA2-512
 pub fn sha512<T: AsRef<[u8]>>(data: T) -> [u8; Sha512::DIGEST_LEN] {
     Sha512::oneshot(data)
@@ -90,6 +113,12 @@ pub struct Sha384 {
     inner: Sha512,
 }
 
+impl Zeroize for Sha384 {
+    fn zeroize(&mut self) {
+        self.inner.zeroize();
+    }
+}
+
 impl Sha384 {
     pub const BLOCK_LEN: usize  = 128;
     pub const DIGEST_LEN: usize =  48;
@@ -121,8 +150,82 @@ impl Sha384 {
         m.update(data.as_ref());
         m.finalize()
     }
+
+    pub const STATE_LEN: usize = Sha512::STATE_LEN;
+
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        self.inner.export_state_with(StateId::Sha384)
+    }
+
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        Sha512::import_state_with(state, StateId::Sha384).map(|inner| Self { inner })
+    }
+}
+
+
+macro_rules! impl_sha512_truncated {
+    ($name:tt, $iv:tt, $digest_len:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            inner: Sha512,
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.inner.zeroize();
+            }
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize  = 128;
+            pub const DIGEST_LEN: usize = $digest_len;
+
+            pub fn new() -> Self {
+                let inner = Sha512 {
+                    buffer: [0u8; 128],
+                    state: $iv,
+                    len: 0,
+                };
+                Self { inner }
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.update(data)
+            }
+
+            pub fn finalize(self) -> [u8; Self::DIGEST_LEN] {
+                let h = self.inner.finalize();
+
+                let mut output = [0u8; Self::DIGEST_LEN];
+                output.copy_from_slice(&h[..Self::DIGEST_LEN]);
+
+                output
+            }
+
+            pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+                let mut m = Self::new();
+                m.update(data.as_ref());
+                m.finalize()
+            }
+
+            pub const STATE_LEN: usize = Sha512::STATE_LEN;
+
+            pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+                self.inner.export_state_with(StateId::$name)
+            }
+
+            pub fn import_state(state: &[u8]) -> Option<Self> {
+                Sha512::import_state_with(state, StateId::$name).map(|inner| Self { inner })
+            }
+        }
+    }
 }
 
+// SHA2-512/224
+impl_sha512_truncated!(Sha512_224, SHA512_224_INITIAL_STATE, 28);
+// SHA2-512/256
+impl_sha512_truncated!(Sha512_256, SHA512_256_INITIAL_STATE, 32);
+
 
 /// SHA2-512
 #[derive(Clone)]
@@ -132,6 +235,20 @@ pub struct Sha512 {
     len: u128,      // in bytes.
 }
 
+impl Zeroize for Sha512 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Sha512 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 impl Sha512 {
     pub const BLOCK_LEN: usize  = 128;
     pub const DIGEST_LEN: usize =  64;
@@ -163,10 +280,7 @@ impl Sha512 {
             } else {
                 transform(&mut self.state, &self.buffer);
 
-                let data = &data[i..];
-                if data.len() > 0 {
-                    return self.update(data);
-                }
+                return self.update(&data[i..]);
             }
         }
 
@@ -235,6 +349,56 @@ impl Sha512 {
         m.update(data.as_ref());
         m.finalize()
     }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 64 + 16 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in big-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        self.export_state_with(StateId::Sha512)
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        Self::import_state_with(state, StateId::Sha512)
+    }
+
+    fn export_state_with(&self, id: StateId) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, id);
+        for (chunk, word) in output[..64].chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output[64..80].copy_from_slice(&self.len.to_be_bytes());
+
+        let n = (self.len % Self::BLOCK_LEN as u128) as usize;
+        output[80..80 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    fn import_state_with(state: &[u8], id: StateId) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, id)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..64].chunks_exact(8)) {
+            let mut w = [0u8; 8];
+            w.copy_from_slice(chunk);
+            *word = u64::from_be_bytes(w);
+        }
+        let mut len = [0u8; 16];
+        len.copy_from_slice(&state[64..80]);
+        m.len = u128::from_be_bytes(len);
+
+        let n = (m.len % Self::BLOCK_LEN as u128) as usize;
+        if state[80 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[80..80 + n]);
+
+        Some(m)
+    }
 }
 
 
@@ -304,4 +468,78 @@ fn test_sha384_long_message() {
         179, 220, 56, 236, 196, 235, 174, 151, 221, 216, 127, 61, 137, 133
     ];
     assert_eq!(&(sha384(&msg[..]))[..], &digest[..]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_sha512_224() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let suites: &[(&[u8], &str)] = &[
+        (b"abc", "4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa"),
+        (b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu", "23fec5bb94d60b23308192640b0c453335d664734fe40e7268674af9"),
+        (&[b'a'; 1000_000], "37ab331d76f0d36de422bd0edeb22a28accd487b7a8453ae965dd287"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sha512_224(msg)), digest);
+    }
+}
+
+#[test]
+fn test_sha512_256() {
+    let suites: &[(&[u8], &str)] = &[
+        (b"abc", "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"),
+        (b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu", "3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a"),
+        (&[b'a'; 1000_000], "9a59a052930187a97038cae692f30708aa6491923ef5194394dc68d56c74fb21"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sha512_256(msg)), digest);
+    }
+}
+
+#[test]
+fn test_sha512_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Sha512::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Sha512::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), sha512(&msg));
+    }
+
+    let mut m = Sha384::new();
+    m.update(&msg[..100]);
+    let mut m = Sha384::import_state(&m.export_state()).unwrap();
+    m.update(&msg[100..]);
+    assert_eq!(m.finalize(), sha384(&msg));
+
+    let mut m = Sha512_224::new();
+    m.update(&msg[..100]);
+    let mut m = Sha512_224::import_state(&m.export_state()).unwrap();
+    m.update(&msg[100..]);
+    assert_eq!(m.finalize(), sha512_224(&msg));
+
+    let mut m = Sha512_256::new();
+    m.update(&msg[..100]);
+    let mut m = Sha512_256::import_state(&m.export_state()).unwrap();
+    m.update(&msg[100..]);
+    assert_eq!(m.finalize(), sha512_256(&msg));
+
+    let state = Sha512::new().export_state();
+    assert!(Sha384::import_state(&state).is_none());
+    assert!(Sha512_224::import_state(&state).is_none());
+    assert!(Sha512::import_state(&Sha384::new().export_state()).is_none());
+    assert!(Sha512_256::import_state(&Sha512_224::new().export_state()).is_none());
+}
+
+#[test]
+fn test_sha512_bytewise_update() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+
+    let mut m = Sha512::new();
+    for byte in msg.chunks(1) {
+        m.update(byte);
+    }
+    assert_eq!(m.finalize(), sha512(&msg));
+}
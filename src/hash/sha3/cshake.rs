@@ -0,0 +1,177 @@
+// SHA-3 Derived Functions: cSHAKE, KMAC, TupleHash and ParallelHash
+// https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-185.pdf
+//
+// 3.3 Definition
+// cSHAKE128(X, L, N, S):
+//   1. If N = "" and S = "":
+//        return SHAKE128(X, L);
+//   2. Else:
+//        return KECCAK[256](bytepad(encode_string(N) || encode_string(S), 168) || X || 00, L).
+use super::keccak::Keccak;
+use super::ShakeReader;
+
+
+// NOTE: 在字节序（LSB）下，`X || 00` 再加上 `pad10*1` 的第一个比特即为 `0x04`。
+const CSHAKE_DS: u8 = 0x04;
+const SHAKE_DS: u8  = 0x1f;
+
+
+// 2.3.1 Integer to Byte String Encoding
+//
+// left_encode(x) = O_0 || O_1 || ... || O_n, 其中 O_0 为 x 的字节数 n。
+#[inline]
+fn left_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = core::cmp::max(1, 8 - x.leading_zeros() as usize / 8);
+
+    buf[0] = n as u8;
+    buf[1..n + 1].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+
+    &buf[..n + 1]
+}
+
+// right_encode(x) = O_1 || ... || O_n || O_n+1, 其中 O_n+1 为 x 的字节数 n。
+#[inline]
+fn right_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = core::cmp::max(1, 8 - x.leading_zeros() as usize / 8);
+
+    buf[..n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    buf[n] = n as u8;
+
+    &buf[..n + 1]
+}
+
+// 2.3.2 String Encoding
+// encode_string(S) = left_encode(len(S)) || S.
+#[inline]
+fn absorb_encode_string(sponge: &mut Keccak, s: &[u8]) {
+    let mut buf = [0u8; 9];
+    sponge.absorb(left_encode(s.len() as u64 * 8, &mut buf));
+    sponge.absorb(s);
+}
+
+// 2.3.3 Padding
+// bytepad(X, w) = left_encode(w) || X || 0^*, 其中 w 为 sponge 的 rate。
+#[inline]
+fn absorb_bytepad(sponge: &mut Keccak, strings: &[&[u8]], w: usize) {
+    let mut buf = [0u8; 9];
+    sponge.absorb(left_encode(w as u64, &mut buf));
+    for s in strings.iter() {
+        absorb_encode_string(sponge, s);
+    }
+    sponge.fill_block();
+}
+
+
+macro_rules! impl_cshake {
+    ($name:tt, $bits:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            inner: Keccak,
+            ds: u8,
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize = 200 - 2 * ($bits / 8);
+
+
+            /// `name` 为 NIST 定义的函数名称（N），`custom` 为用户自定义的字符串（S）。
+            pub fn new(name: &[u8], custom: &[u8]) -> Self {
+                let mut inner = Keccak::new(Self::BLOCK_LEN);
+
+                if name.is_empty() && custom.is_empty() {
+                    return Self { inner, ds: SHAKE_DS };
+                }
+
+                absorb_bytepad(&mut inner, &[name, custom], Self::BLOCK_LEN);
+
+                Self { inner, ds: CSHAKE_DS }
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.absorb(data);
+            }
+
+            pub(crate) fn update_bytepad(&mut self, strings: &[&[u8]]) {
+                absorb_bytepad(&mut self.inner, strings, Self::BLOCK_LEN);
+            }
+
+            pub(crate) fn update_encode_string(&mut self, s: &[u8]) {
+                absorb_encode_string(&mut self.inner, s);
+            }
+
+            pub(crate) fn update_left_encode(&mut self, x: u64) {
+                let mut buf = [0u8; 9];
+                self.inner.absorb(left_encode(x, &mut buf));
+            }
+
+            pub(crate) fn update_right_encode(&mut self, x: u64) {
+                let mut buf = [0u8; 9];
+                self.inner.absorb(right_encode(x, &mut buf));
+            }
+
+            pub fn finalize_xof(mut self) -> ShakeReader {
+                self.inner.pad(self.ds);
+
+                ShakeReader::new(self.inner)
+            }
+
+            pub fn finalize(self, output: &mut [u8]) {
+                self.finalize_xof().read(output);
+            }
+
+            pub fn oneshot<T: AsRef<[u8]>>(name: &[u8], custom: &[u8], data: T, output: &mut [u8]) {
+                let mut m = Self::new(name, custom);
+                m.update(data.as_ref());
+                m.finalize(output)
+            }
+        }
+    }
+}
+
+impl_cshake!(CShake128, 128);
+impl_cshake!(CShake256, 256);
+
+
+#[test]
+fn test_left_right_encode() {
+    let mut buf = [0u8; 9];
+    assert_eq!(left_encode(0, &mut buf), &[1, 0]);
+    assert_eq!(left_encode(168, &mut buf), &[1, 168]);
+    assert_eq!(left_encode(256, &mut buf), &[2, 1, 0]);
+    assert_eq!(right_encode(0, &mut buf), &[0, 1]);
+    assert_eq!(right_encode(256, &mut buf), &[1, 0, 2]);
+    assert_eq!(right_encode(u64::MAX, &mut buf), &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 8]);
+}
+
+#[test]
+fn test_cshake128() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let data = hex::decode("00010203").unwrap();
+    let mut output = [0u8; 32];
+    CShake128::oneshot(b"", b"Email Signature", &data, &mut output);
+    assert_eq!(&hex::encode(&output), "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5");
+
+    let data = (0u8..200).collect::<Vec<u8>>();
+    CShake128::oneshot(b"", b"Email Signature", &data, &mut output);
+    assert_eq!(&hex::encode(&output), "c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b");
+
+    // N 和 S 均为空时，cSHAKE 即为 SHAKE。
+    let mut expected = [0u8; 32];
+    super::shake128(&data, &mut expected);
+    CShake128::oneshot(b"", b"", &data, &mut output);
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_cshake256() {
+    let data = hex::decode("00010203").unwrap();
+    let mut output = [0u8; 64];
+    CShake256::oneshot(b"", b"Email Signature", &data, &mut output);
+    assert_eq!(&hex::encode(&output[..]), "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd1\
+64020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c");
+
+    let data = (0u8..200).collect::<Vec<u8>>();
+    CShake256::oneshot(b"", b"Email Signature", &data, &mut output);
+    assert_eq!(&hex::encode(&output[..]), "07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac864302730917\
+27f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb");
+}
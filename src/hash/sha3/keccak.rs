@@ -0,0 +1,212 @@
+// The Keccak-f[1600] permutation and sponge construction
+// https://keccak.team/keccak_specs_summary.html
+//
+// 3.  KECCAK-p[b, nr] / 4.  SPONGE CONSTRUCTION
+// https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+use crate::mem::Zeroize;
+
+
+const KECCAKF_ROUNDS: usize = 24;
+
+// Iota round constants
+const KECCAKF_RNDC: [u64; KECCAKF_ROUNDS] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+// Rho rotation offsets
+const KECCAKF_ROTC: [u32; 24] = [
+    1,  3,  6,  10, 15, 21, 28, 36, 45, 55, 2,  14,
+    27, 41, 56, 8,  25, 43, 62, 18, 39, 61, 20, 44,
+];
+// Pi lane permutation
+const KECCAKF_PILN: [usize; 24] = [
+    10, 7,  11, 17, 18, 3, 5,  16, 8,  21, 24, 4,
+    15, 23, 19, 13, 12, 2, 20, 14, 22, 9,  6,  1,
+];
+
+
+// https://github.com/mjosaarinen/tiny_sha3/blob/master/sha3.c#L17
+#[inline]
+pub fn keccakf(st: &mut [u64; 25]) {
+    let mut bc = [0u64; 5];
+
+    for round in 0..KECCAKF_ROUNDS {
+        // Theta
+        for i in 0..5 {
+            bc[i] = st[i] ^ st[i + 5] ^ st[i + 10] ^ st[i + 15] ^ st[i + 20];
+        }
+        for i in 0..5 {
+            let t = bc[(i + 4) % 5] ^ bc[(i + 1) % 5].rotate_left(1);
+            for j in (0..25).step_by(5) {
+                st[j + i] ^= t;
+            }
+        }
+
+        // Rho Pi
+        let mut t = st[1];
+        for i in 0..24 {
+            let j = KECCAKF_PILN[i];
+            let tmp = st[j];
+            st[j] = t.rotate_left(KECCAKF_ROTC[i]);
+            t = tmp;
+        }
+
+        // Chi
+        for j in (0..25).step_by(5) {
+            bc.copy_from_slice(&st[j..j + 5]);
+            for i in 0..5 {
+                st[j + i] ^= (!bc[(i + 1) % 5]) & bc[(i + 2) % 5];
+            }
+        }
+
+        // Iota
+        st[0] ^= KECCAKF_RNDC[round];
+    }
+}
+
+
+/// Keccak[c] sponge over the Keccak-f[1600] permutation.
+#[derive(Clone)]
+pub struct Keccak {
+    state: [u64; 25],
+    rate: usize,     // in bytes.
+    offset: usize,   // absorbed bytes in the current block.
+}
+
+impl Zeroize for Keccak {
+    fn zeroize(&mut self) {
+        self.state.zeroize();
+        self.offset.zeroize();
+    }
+}
+
+impl Drop for Keccak {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Keccak {
+    pub const STATE_LEN: usize = 200;
+
+
+    pub fn new(rate: usize) -> Self {
+        debug_assert!(rate > 0 && rate < Self::STATE_LEN);
+        debug_assert_eq!(rate % 8, 0);
+
+        Self { state: [0u64; 25], rate, offset: 0 }
+    }
+
+    #[inline]
+    fn xor_bytes(&mut self, offset: usize, data: &[u8]) {
+        for (i, byte) in data.iter().enumerate() {
+            let pos = offset + i;
+            self.state[pos / 8] ^= (*byte as u64) << ((pos % 8) * 8);
+        }
+    }
+
+    #[inline]
+    fn xor_block(&mut self, block: &[u8]) {
+        debug_assert_eq!(block.len(), self.rate);
+
+        for (lane, chunk) in self.state.iter_mut().zip(block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes([
+                chunk[0], chunk[1], chunk[2], chunk[3],
+                chunk[4], chunk[5], chunk[6], chunk[7],
+            ]);
+        }
+    }
+
+    pub fn absorb(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        if self.offset > 0 {
+            let n = core::cmp::min(self.rate - self.offset, data.len());
+            let offset = self.offset;
+            self.xor_bytes(offset, &data[..n]);
+            self.offset += n;
+            data = &data[n..];
+
+            if self.offset < self.rate {
+                return;
+            }
+
+            keccakf(&mut self.state);
+            self.offset = 0;
+        }
+
+        while data.len() >= self.rate {
+            let (block, rem) = data.split_at(self.rate);
+            self.xor_block(block);
+            keccakf(&mut self.state);
+            data = rem;
+        }
+
+        if !data.is_empty() {
+            self.xor_bytes(0, data);
+            self.offset = data.len();
+        }
+    }
+
+    /// Zero pads the absorbed data to a multiple of the rate, `bytepad(X, rate)` uses it.
+    pub fn fill_block(&mut self) {
+        if self.offset > 0 {
+            keccakf(&mut self.state);
+            self.offset = 0;
+        }
+    }
+
+    /// Appends the domain separation bits together with the `pad10*1` rule,
+    /// then applies the permutation to the last block.
+    pub fn pad(&mut self, ds: u8) {
+        let offset = self.offset;
+        let rate = self.rate;
+        self.xor_bytes(offset, &[ds]);
+        self.xor_bytes(rate - 1, &[0x80]);
+
+        keccakf(&mut self.state);
+        self.offset = 0;
+    }
+
+    /// Copies the first `output.len()` bytes of the state, `output.len()` must not exceed the rate.
+    pub fn extract(&self, output: &mut [u8]) {
+        debug_assert!(output.len() <= self.rate);
+
+        for (i, byte) in output.iter_mut().enumerate() {
+            *byte = (self.state[i / 8] >> ((i % 8) * 8)) as u8;
+        }
+    }
+
+    /// Squeezes an arbitrary amount of output, must be called after `pad`.
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        for byte in output.iter_mut() {
+            if self.offset == self.rate {
+                keccakf(&mut self.state);
+                self.offset = 0;
+            }
+
+            let pos = self.offset;
+            *byte = (self.state[pos / 8] >> ((pos % 8) * 8)) as u8;
+            self.offset += 1;
+        }
+    }
+}
+
+
+#[test]
+fn test_keccakf() {
+    // https://github.com/XKCP/XKCP/blob/master/tests/TestVectors/KeccakF-1600-IntermediateValues.txt
+    let mut state = [0u64; 25];
+    keccakf(&mut state);
+    assert_eq!(&state[..5], &[
+        0xF1258F7940E1DDE7, 0x84D5CCF933C0478A, 0xD598261EA65AA9EE, 0xBD1547306F80494D, 0x8B284E056253D057,
+    ]);
+    keccakf(&mut state);
+    assert_eq!(&state[..5], &[
+        0x2D5C954DF96ECB3C, 0x6A332CD07057B56D, 0x093D8D1270D76B6C, 0x8A20D9B25569D094, 0x4F9C4F99E5E7F156,
+    ]);
+}
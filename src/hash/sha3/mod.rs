@@ -1,7 +1,149 @@
-
 // This Standard specifies the Secure Hash Algorithm-3 (SHA-3)
 // https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
-// 
+//
 
 // C code
 // https://github.com/mjosaarinen/tiny_sha3/blob/master/sha3.c
+mod keccak;
+mod shake;
+mod cshake;
+mod tuplehash;
+mod parallelhash;
+
+use self::keccak::Keccak;
+use crate::mem::Zeroize;
+
+pub use self::shake::*;
+pub use self::cshake::*;
+pub use self::tuplehash::*;
+pub use self::parallelhash::*;
+
+
+// 6.1 SHA-3 Hash Functions
+// SHA3-224(M) = KECCAK[448] (M || 01, 224)
+// ...
+//
+// NOTE: 在字节序（LSB）下，`M || 01` 再加上 `pad10*1` 的第一个比特即为 `0x06`。
+const SHA3_DS: u8 = 0x06;
+
+
+macro_rules! impl_sha3 {
+    ($name:tt, $fname:tt, $bits:tt) => {
+        pub fn $fname<T: AsRef<[u8]>>(data: T) -> [u8; $name::DIGEST_LEN] {
+            $name::oneshot(data)
+        }
+
+        #[derive(Clone)]
+        pub struct $name {
+            inner: Keccak,
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.inner.zeroize();
+            }
+        }
+
+        impl $name {
+            // rate = 1600 - 2 * d
+            pub const BLOCK_LEN: usize  = 200 - 2 * ($bits / 8);
+            pub const DIGEST_LEN: usize = $bits / 8;
+
+
+            pub fn new() -> Self {
+                Self { inner: Keccak::new(Self::BLOCK_LEN) }
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.absorb(data);
+            }
+
+            pub fn finalize(mut self) -> [u8; Self::DIGEST_LEN] {
+                self.inner.pad(SHA3_DS);
+
+                let mut output = [0u8; Self::DIGEST_LEN];
+                self.inner.extract(&mut output);
+
+                output
+            }
+
+            pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+                let mut m = Self::new();
+                m.update(data.as_ref());
+                m.finalize()
+            }
+        }
+    }
+}
+
+impl_sha3!(Sha3_224, sha3_224, 224);
+impl_sha3!(Sha3_256, sha3_256, 256);
+impl_sha3!(Sha3_384, sha3_384, 384);
+impl_sha3!(Sha3_512, sha3_512, 512);
+
+
+#[test]
+fn test_sha3_224() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let suites: &[(&[u8], &str)] = &[
+        (b"", "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7"),
+        (b"abc", "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf"),
+        (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "8a24108b154ada21c9fd5574494479ba5c7e7ab76ef264ead0fcce33"),
+        (&[0xa3; 200], "9376816aba503f72f96ce7eb65ac095deee3be4bf9bbc2a1cb7e11e0"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sha3_224(msg)), digest);
+    }
+}
+
+#[test]
+fn test_sha3_256() {
+    let suites: &[(&[u8], &str)] = &[
+        (b"", "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"),
+        (b"abc", "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+        (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376"),
+        (&[0xa3; 200], "79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sha3_256(msg)), digest);
+    }
+}
+
+#[test]
+fn test_sha3_384() {
+    let suites: &[(&[u8], &str)] = &[
+        (b"", "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004"),
+        (b"abc", "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25"),
+        (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "991c665755eb3a4b6bbdfb75c78a492e8c56a22c5c4d7e429bfdbc32b9d4ad5aa04a1f076e62fea19eef51acd0657c22"),
+        (&[0xa3; 200], "1881de2ca7e41ef95dc4732b8f5f002b189cc1e42b74168ed1732649ce1dbcdd76197a31fd55ee989f2d7050dd473e8f"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sha3_384(msg)), digest);
+    }
+}
+
+#[test]
+fn test_sha3_512() {
+    let suites: &[(&[u8], &str)] = &[
+        (b"", "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26"),
+        (b"abc", "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"),
+        (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636dee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e"),
+        (&[0xa3; 200], "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca81b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sha3_512(msg)), digest);
+    }
+}
+
+#[test]
+fn test_sha3_256_incremental() {
+    // 按不同的分块大小输入，结果应当和一次性输入相同。
+    let msg = [0xa3u8; 200];
+    for chunk_size in 1..msg.len() {
+        let mut m = Sha3_256::new();
+        for chunk in msg.chunks(chunk_size) {
+            m.update(chunk);
+        }
+        assert_eq!(m.finalize(), sha3_256(&msg[..]));
+    }
+}
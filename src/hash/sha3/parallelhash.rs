@@ -0,0 +1,151 @@
+// 6 ParallelHash
+// https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-185.pdf
+//
+// ParallelHash128(X, B, L, S):
+//   1. n = ⌈ (len(X)/8) / B ⌉.
+//   2. z = left_encode(B).
+//   3. for i = 0 to n−1:
+//        z = z || cSHAKE128(substring(X, i*B*8, (i+1)*B*8), 256, "", "").
+//   4. z = z || right_encode(n) || right_encode(L).
+//   5. newX = z.
+//   6. return cSHAKE128(newX, L, "ParallelHash", S).
+//
+// NOTE: 各个分块之间互不依赖，可以并行计算，这里按顺序依次处理。
+use super::{ CShake128, CShake256, Shake128, Shake256, ShakeReader, };
+
+
+macro_rules! impl_parallel_hash {
+    ($name:tt, $cshake:tt, $shake:tt, $bits:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            inner: $cshake,
+            buffer: Vec<u8>,
+            block_size: usize,
+            blocks: u64,
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize = $cshake::BLOCK_LEN;
+            // cSHAKE(X_i, 2 * security strength, "", "")
+            const CHAINING_LEN: usize  = $bits * 2 / 8;
+
+
+            /// `block_size` 为每个分块的字节数（B）。
+            pub fn new(block_size: usize, custom: &[u8]) -> Self {
+                assert!(block_size > 0);
+
+                let mut inner = $cshake::new(b"ParallelHash", custom);
+                inner.update_left_encode(block_size as u64);
+
+                Self { inner, buffer: Vec::with_capacity(block_size), block_size, blocks: 0 }
+            }
+
+            #[inline]
+            fn process_block(&mut self, block: &[u8]) {
+                let mut chaining_value = [0u8; Self::CHAINING_LEN];
+                $shake::oneshot(block, &mut chaining_value);
+
+                self.inner.update(&chaining_value);
+                self.blocks += 1;
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                let mut data = data;
+
+                if !self.buffer.is_empty() {
+                    let n = core::cmp::min(self.block_size - self.buffer.len(), data.len());
+                    self.buffer.extend_from_slice(&data[..n]);
+                    data = &data[n..];
+
+                    if self.buffer.len() < self.block_size {
+                        return;
+                    }
+
+                    let mut block = core::mem::take(&mut self.buffer);
+                    self.process_block(&block);
+                    block.clear();
+                    self.buffer = block;
+                }
+
+                while data.len() >= self.block_size {
+                    let (block, rem) = data.split_at(self.block_size);
+                    self.process_block(block);
+                    data = rem;
+                }
+
+                self.buffer.extend_from_slice(data);
+            }
+
+            fn finish(&mut self, output_bits: u64) {
+                if !self.buffer.is_empty() {
+                    let block = core::mem::take(&mut self.buffer);
+                    self.process_block(&block);
+                }
+
+                let blocks = self.blocks;
+                self.inner.update_right_encode(blocks);
+                self.inner.update_right_encode(output_bits);
+            }
+
+            pub fn finalize(mut self, output: &mut [u8]) {
+                self.finish(output.len() as u64 * 8);
+                self.inner.finalize(output)
+            }
+
+            // 6.3.1 ParallelHashXOF: L 编码为 0。
+            pub fn finalize_xof(mut self) -> ShakeReader {
+                self.finish(0);
+                self.inner.finalize_xof()
+            }
+
+            pub fn oneshot(block_size: usize, custom: &[u8], data: &[u8], output: &mut [u8]) {
+                let mut m = Self::new(block_size, custom);
+                m.update(data);
+                m.finalize(output)
+            }
+        }
+    }
+}
+
+impl_parallel_hash!(ParallelHash128, CShake128, Shake128, 128);
+impl_parallel_hash!(ParallelHash256, CShake256, Shake256, 256);
+
+
+#[test]
+fn test_parallel_hash128() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let data = hex::decode("000102030405060710111213141516172021222324252627").unwrap();
+
+    let mut output = [0u8; 32];
+    ParallelHash128::oneshot(8, b"", &data, &mut output);
+    assert_eq!(&hex::encode(&output), "ba8dc1d1d979331d3f813603c67f72609ab5e44b94a0b8f9af46514454a2b4f5");
+
+    ParallelHash128::oneshot(8, b"Parallel Data", &data, &mut output);
+    assert_eq!(&hex::encode(&output), "fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206");
+
+    let mut m = ParallelHash128::new(8, b"Parallel Data");
+    m.update(&data);
+    m.finalize_xof().read(&mut output);
+    assert_eq!(&hex::encode(&output), "ea2a793140820f7a128b8eb70a9439f93257c6e6e79b4a540d291d6dae7098d7");
+
+    // 最后一个分块不完整，且按任意长度分段输入。
+    let data = (0u8..100).collect::<Vec<u8>>();
+    for chunk_size in 1..data.len() {
+        let mut m = ParallelHash128::new(16, b"Parallel Data");
+        for chunk in data.chunks(chunk_size) {
+            m.update(chunk);
+        }
+        m.finalize(&mut output);
+        assert_eq!(&hex::encode(&output), "002279f96c26d3c94783bf50fef54f5b48a87576297bb0329aa6e764bbc932c0");
+    }
+}
+
+#[test]
+fn test_parallel_hash256() {
+    let data = hex::decode("000102030405060710111213141516172021222324252627").unwrap();
+
+    let mut output = [0u8; 64];
+    ParallelHash256::oneshot(8, b"Parallel Data", &data, &mut output);
+    assert_eq!(&hex::encode(&output[..]), "cdf15289b54f6212b4bc270528b49526006dd9b54e2b6add1ef6900dda3963bb\
+33a72491f236969ca8afaea29c682d47a393c065b38e29fae651a2091c833110");
+}
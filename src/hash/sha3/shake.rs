@@ -0,0 +1,127 @@
+// 6.2 SHA-3 Extendable-Output Functions
+// https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+//
+// SHAKE128(M, d) = KECCAK[256] (M || 1111, d)
+// SHAKE256(M, d) = KECCAK[512] (M || 1111, d)
+use super::keccak::Keccak;
+
+
+// NOTE: 在字节序（LSB）下，`M || 1111` 再加上 `pad10*1` 的第一个比特即为 `0x1f`。
+const SHAKE_DS: u8 = 0x1f;
+
+
+/// SHAKE128
+pub fn shake128<T: AsRef<[u8]>>(data: T, output: &mut [u8]) {
+    Shake128::oneshot(data, output)
+}
+
+/// SHAKE256
+pub fn shake256<T: AsRef<[u8]>>(data: T, output: &mut [u8]) {
+    Shake256::oneshot(data, output)
+}
+
+
+/// The squeezing phase of a Keccak based XOF.
+#[derive(Clone)]
+pub struct ShakeReader {
+    inner: Keccak,
+}
+
+impl ShakeReader {
+    pub(super) fn new(inner: Keccak) -> Self {
+        Self { inner }
+    }
+
+    /// Fills `output` with the next `output.len()` bytes of the output stream.
+    pub fn read(&mut self, output: &mut [u8]) {
+        self.inner.squeeze(output);
+    }
+}
+
+
+macro_rules! impl_shake {
+    ($name:tt, $bits:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            inner: Keccak,
+        }
+
+        impl $name {
+            // rate = 1600 - 2 * security strength
+            pub const BLOCK_LEN: usize = 200 - 2 * ($bits / 8);
+
+
+            pub fn new() -> Self {
+                Self { inner: Keccak::new(Self::BLOCK_LEN) }
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.absorb(data);
+            }
+
+            pub fn finalize_xof(mut self) -> ShakeReader {
+                self.inner.pad(SHAKE_DS);
+
+                ShakeReader::new(self.inner)
+            }
+
+            pub fn finalize(self, output: &mut [u8]) {
+                self.finalize_xof().read(output);
+            }
+
+            pub fn oneshot<T: AsRef<[u8]>>(data: T, output: &mut [u8]) {
+                let mut m = Self::new();
+                m.update(data.as_ref());
+                m.finalize(output)
+            }
+        }
+    }
+}
+
+impl_shake!(Shake128, 128);
+impl_shake!(Shake256, 256);
+
+
+#[test]
+fn test_shake128() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let mut output = [0u8; 32];
+    shake128(b"", &mut output);
+    assert_eq!(&hex::encode(&output), "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26");
+
+    // 1600-bit message, 4096-bit output
+    let mut output = [0u8; 512];
+    shake128(&[0xa3; 200][..], &mut output);
+    assert_eq!(&hex::encode(&output[480..]), "44c9fb359fd56ac0a9a75a743cff6862f17d7259ab075216c0699511643b6439");
+}
+
+#[test]
+fn test_shake256() {
+    let mut output = [0u8; 64];
+    shake256(b"", &mut output);
+    assert_eq!(&hex::encode(&output[..]), "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f\
+d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be");
+
+    let mut output = [0u8; 512];
+    shake256(&[0xa3; 200][..], &mut output);
+    assert_eq!(&hex::encode(&output[480..]), "6a1a9d7846436e4dca5728b6f760eef0ca92bf0be5615e96959d767197a0beeb");
+}
+
+#[test]
+fn test_shake_reader() {
+    // 分多次读取的输出应当和一次性读取的输出相同。
+    let mut expected = [0u8; 512];
+    shake128(&[0xa3; 200][..], &mut expected);
+
+    for chunk_size in &[1usize, 7, 168, 200] {
+        let mut m = Shake128::new();
+        m.update(&[0xa3; 200]);
+
+        let mut reader = m.finalize_xof();
+        let mut output = [0u8; 512];
+        for chunk in output.chunks_mut(*chunk_size) {
+            reader.read(chunk);
+        }
+        assert_eq!(&output[..], &expected[..]);
+    }
+}
@@ -0,0 +1,99 @@
+// 5 TupleHash
+// https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-185.pdf
+//
+// TupleHash128(X, L, S):
+//   1. z = "".
+//   2. n = the number of strings in the tuple X.
+//   3. for i = 1 to n:
+//        z = z || encode_string(X[i]).
+//   4. newX = z || right_encode(L).
+//   5. return cSHAKE128(newX, L, "TupleHash", S).
+use super::{ CShake128, CShake256, ShakeReader, };
+
+
+macro_rules! impl_tuple_hash {
+    ($name:tt, $cshake:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            inner: $cshake,
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize = $cshake::BLOCK_LEN;
+
+
+            pub fn new(custom: &[u8]) -> Self {
+                Self { inner: $cshake::new(b"TupleHash", custom) }
+            }
+
+            /// NOTE: 每次调用都会被当作元组里面的一个独立元素，
+            ///       因此 `update(b"ab"); update(b"c");` 和 `update(b"abc");` 的结果不同。
+            pub fn update(&mut self, element: &[u8]) {
+                self.inner.update_encode_string(element);
+            }
+
+            pub fn finalize(mut self, output: &mut [u8]) {
+                self.inner.update_right_encode(output.len() as u64 * 8);
+                self.inner.finalize(output)
+            }
+
+            // 5.3.1 TupleHashXOF: L 编码为 0。
+            pub fn finalize_xof(mut self) -> ShakeReader {
+                self.inner.update_right_encode(0);
+                self.inner.finalize_xof()
+            }
+
+            pub fn oneshot(custom: &[u8], tuple: &[&[u8]], output: &mut [u8]) {
+                let mut m = Self::new(custom);
+                for element in tuple.iter() {
+                    m.update(element);
+                }
+                m.finalize(output)
+            }
+        }
+    }
+}
+
+impl_tuple_hash!(TupleHash128, CShake128);
+impl_tuple_hash!(TupleHash256, CShake256);
+
+
+#[test]
+fn test_tuple_hash128() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let e1 = hex::decode("000102").unwrap();
+    let e2 = hex::decode("101112131415").unwrap();
+    let e3 = hex::decode("202122232425262728").unwrap();
+
+    let mut output = [0u8; 32];
+    TupleHash128::oneshot(b"", &[&e1, &e2], &mut output);
+    assert_eq!(&hex::encode(&output), "c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1");
+
+    TupleHash128::oneshot(b"My Tuple App", &[&e1, &e2], &mut output);
+    assert_eq!(&hex::encode(&output), "75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb");
+
+    TupleHash128::oneshot(b"My Tuple App", &[&e1, &e2, &e3], &mut output);
+    assert_eq!(&hex::encode(&output), "e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84");
+
+    let mut m = TupleHash128::new(b"My Tuple App");
+    m.update(&e1);
+    m.update(&e2);
+    m.finalize_xof().read(&mut output);
+    assert_eq!(&hex::encode(&output), "3fc8ad69453128292859a18b6c67d7ad85f01b32815e22ce839c49ec374e9b9a");
+}
+
+#[test]
+fn test_tuple_hash256() {
+    let e1 = hex::decode("000102").unwrap();
+    let e2 = hex::decode("101112131415").unwrap();
+    let e3 = hex::decode("202122232425262728").unwrap();
+
+    let mut output = [0u8; 64];
+    TupleHash256::oneshot(b"", &[&e1, &e2], &mut output);
+    assert_eq!(&hex::encode(&output[..]), "cfb7058caca5e668f81a12a20a2195ce97a925f1dba3e7449a56f82201ec6073\
+11ac2696b1ab5ea2352df1423bde7bd4bb78c9aed1a853c78672f9eb23bbe194");
+
+    TupleHash256::oneshot(b"My Tuple App", &[&e1, &e2, &e3], &mut output);
+    assert_eq!(&hex::encode(&output[..]), "45000be63f9b6bfd89f54717670f69a9bc763591a4f05c50d68891a744bcc6e7\
+d6d5b5e82c018da999ed35b0bb49c9678e526abd8e85c13ed254021db9e790ce");
+}
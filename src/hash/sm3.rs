@@ -3,6 +3,8 @@
 // 
 // GM/T 0004-2012 SM3 Cryptographic Hash Algorithm （English Version）
 // http://www.gmbz.org.cn/upload/2018-07-24/1532401392982079739.pdf
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
 
 
 const INITIAL_STATE: [u32; 8] = [
@@ -25,6 +27,21 @@ pub struct Sm3 {
     offset: usize,
 }
 
+impl Zeroize for Sm3 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+        self.offset.zeroize();
+    }
+}
+
+impl Drop for Sm3 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 impl Sm3 {
     pub const BLOCK_LEN: usize  = 64;
     pub const DIGEST_LEN: usize = 32;
@@ -75,7 +92,7 @@ impl Sm3 {
 
             let mut last_block = [0u8; Self::BLOCK_LEN];
             last_block[56..64].copy_from_slice(&len_bits.to_be_bytes());
-            transform(&mut self.state, &self.buffer);
+            transform(&mut self.state, &last_block);
         }
 
         let mut output = [0u8; Self::DIGEST_LEN];
@@ -97,6 +114,47 @@ impl Sm3 {
         m.update(data.as_ref());
         m.finalize()
     }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 32 + 8 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in big-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Sm3);
+        for (chunk, word) in output[..32].chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output[32..40].copy_from_slice(&self.len.to_be_bytes());
+
+        let n = (self.len % Self::BLOCK_LEN as u64) as usize;
+        output[40..40 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Sm3)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..32].chunks_exact(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut len = [0u8; 8];
+        len.copy_from_slice(&state[32..40]);
+        m.len = u64::from_be_bytes(len);
+
+        let n = (m.len % Self::BLOCK_LEN as u64) as usize;
+        if state[40 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[40..40 + n]);
+        m.offset = n;
+
+        Some(m)
+    }
 }
 
 
@@ -253,3 +311,32 @@ fn test_sm3() {
         0x29, 0x3d, 0xcb, 0xa3, 0x9c, 0x0c, 0x57, 0x32, 
     ]);
 }
+
+#[test]
+fn test_sm3_padding() {
+    // NOTE: 剩余的消息长度超过 55 字节时，长度字段需要放在额外的一个分组里面。
+    let suites: &[(usize, &str)] = &[
+        (55, "288337eef51eec62e7544d7270424c8dbe656254c99852870a73b2453a6a7fb1"),
+        (56, "ba00ebedaab54065a5fd4f9f56326016203166bcee3eed44ea868d59d67aa3c8"),
+        (63, "587308543551881ebd70d27ad358ff5dcdf24ac54822e2f7b7c3edce0985d21b"),
+        (64, "616ec433c359e7c2b19f360e2b8f2a1b6e9ed76b8dc1a7d207b31a5341c611e9"),
+        (120, "4c9f0fe9f36ffe0191af73560c4afb1b671be02ba2d0e0c161b1e03488c2a45c"),
+    ];
+    for (len, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&sm3(&vec![b'a'; *len])), digest);
+    }
+}
+
+#[test]
+fn test_sm3_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Sm3::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Sm3::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), sm3(&msg));
+    }
+}
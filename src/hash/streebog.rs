@@ -0,0 +1,491 @@
+// GOST R 34.11-2012: Hash Function (Streebog)
+// https://tools.ietf.org/html/rfc6986
+//
+// NOTE: RFC 6986 以大端序的大整数形式给出消息、常量和摘要，而实际的字节序为小端序，
+//       即 RFC 中十六进制字符串的最后一个字节为内存中的第一个字节。
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
+
+
+// 6.1.  Transformation Pi
+const PI: [u8; 256] = [
+    252, 238, 221,  17, 207, 110,  49,  22, 251, 196, 250, 218,  35, 197,   4,  77,
+    233, 119, 240, 219, 147,  46, 153, 186,  23,  54, 241, 187,  20, 205,  95, 193,
+    249,  24, 101,  90, 226,  92, 239,  33, 129,  28,  60,  66, 139,   1, 142,  79,
+      5, 132,   2, 174, 227, 106, 143, 160,   6,  11, 237, 152, 127, 212, 211,  31,
+    235,  52,  44,  81, 234, 200,  72, 171, 242,  42, 104, 162, 253,  58, 206, 204,
+    181, 112,  14,  86,   8,  12, 118,  18, 191, 114,  19,  71, 156, 183,  93, 135,
+     21, 161, 150,  41,  16, 123, 154, 199, 243, 145, 120, 111, 157, 158, 178, 177,
+     50, 117,  25,  61, 255,  53, 138, 126, 109,  84, 198, 128, 195, 189,  13,  87,
+    223, 245,  36, 169,  62, 168,  67, 201, 215, 121, 214, 246, 124,  34, 185,   3,
+    224,  15, 236, 222, 122, 148, 176, 188, 220, 232,  40,  80,  78,  51,  10,  74,
+    167, 151,  96, 115,  30,   0,  98,  68,  26, 184,  56, 130, 100, 159,  38,  65,
+    173,  69,  70, 146,  39,  94,  85,  47, 140, 163, 165, 125, 105, 213, 149,  59,
+      7,  88, 179,  64, 134, 172,  29, 247,  48,  55, 107, 228, 136, 217, 231, 137,
+    225,  27, 131,  73,  76,  63, 248, 254, 141,  83, 170, 144, 202, 216, 133,  97,
+     32, 113, 103, 164,  45,  43,   9,  91, 203, 155,  37, 208, 190, 229, 108,  82,
+     89, 166, 116, 210, 230, 244, 180, 192, 209, 102, 175, 194,  57,  75,  99, 182,
+];
+
+// 6.3.  Linear Transformation of the Set of Binary Vectors
+const A: [u64; 64] = [
+    0x8e20faa72ba0b470, 0x47107ddd9b505a38, 0xad08b0e0c3282d1c, 0xd8045870ef14980e,
+    0x6c022c38f90a4c07, 0x3601161cf205268d, 0x1b8e0b0e798c13c8, 0x83478b07b2468764,
+    0xa011d380818e8f40, 0x5086e740ce47c920, 0x2843fd2067adea10, 0x14aff010bdd87508,
+    0x0ad97808d06cb404, 0x05e23c0468365a02, 0x8c711e02341b2d01, 0x46b60f011a83988e,
+    0x90dab52a387ae76f, 0x486dd4151c3dfdb9, 0x24b86a840e90f0d2, 0x125c354207487869,
+    0x092e94218d243cba, 0x8a174a9ec8121e5d, 0x4585254f64090fa0, 0xaccc9ca9328a8950,
+    0x9d4df05d5f661451, 0xc0a878a0a1330aa6, 0x60543c50de970553, 0x302a1e286fc58ca7,
+    0x18150f14b9ec46dd, 0x0c84890ad27623e0, 0x0642ca05693b9f70, 0x0321658cba93c138,
+    0x86275df09ce8aaa8, 0x439da0784e745554, 0xafc0503c273aa42a, 0xd960281e9d1d5215,
+    0xe230140fc0802984, 0x71180a8960409a42, 0xb60c05ca30204d21, 0x5b068c651810a89e,
+    0x456c34887a3805b9, 0xac361a443d1c8cd2, 0x561b0d22900e4669, 0x2b838811480723ba,
+    0x9bcf4486248d9f5d, 0xc3e9224312c8c1a0, 0xeffa11af0964ee50, 0xf97d86d98a327728,
+    0xe4fa2054a80b329c, 0x727d102a548b194e, 0x39b008152acb8227, 0x9258048415eb419d,
+    0x492c024284fbaec0, 0xaa16012142f35760, 0x550b8e9e21f7a530, 0xa48b474f9ef5dc18,
+    0x70a6a56e2440598e, 0x3853dc371220a247, 0x1ca76e95091051ad, 0x0edd37c48a08a6d8,
+    0x07e095624504536c, 0x8d70c431ac02a736, 0xc83862965601dd1b, 0x641c314b2b8ee083,
+];
+
+// 6.4.  Iteration Constants
+// NOTE: 每个常量按小端序拆分为 8 个 64 位的字，第一个字为最低位。
+const C: [[u64; 8]; 12] = [
+    [
+        0xdd806559f2a64507, 0x05767436cc744d23, 0xa2422a08a460d315, 0x4b7ce09192676901,
+        0x714eb88d7585c4fc, 0x2f6a76432e45d016, 0xebcb2f81c0657c1f, 0xb1085bda1ecadae9,
+    ],
+    [
+        0xe679047021b19bb7, 0x55dda21bd7cbcd56, 0x5cb561c2db0aa7ca, 0x9ab5176b12d69958,
+        0x61d55e0f16b50131, 0xf3feea720a232b98, 0x4fe39d460f70b5d7, 0x6fa3b58aa99d2f1a,
+    ],
+    [
+        0x991e96f50aba0ab2, 0xc2b6f443867adb31, 0xc1c93a376062db09, 0xd3e20fe490359eb1,
+        0xf2ea7514b1297b7b, 0x06f15e5f529c1f8b, 0x0a39fc286a3d8435, 0xf574dcac2bce2fc7,
+    ],
+    [
+        0x220cbebc84e3d12e, 0x3453eaa193e837f1, 0xd8b71333935203be, 0xa9d72c82ed03d675,
+        0x9d721cad685e353f, 0x488e857e335c3c7d, 0xf948e1a05d71e4dd, 0xef1fdfb3e81566d2,
+    ],
+    [
+        0x601758fd7c6cfe57, 0x7a56a27ea9ea63f5, 0xdfff00b723271a16, 0xbfcd1747253af5a3,
+        0x359e35d7800fffbd, 0x7f151c1f1686104a, 0x9a3f410c6ca92363, 0x4bea6bacad474799,
+    ],
+    [
+        0xfa68407a46647d6e, 0xbf71c57236904f35, 0x0af21f66c2bec6b6, 0xcffaa6b71c9ab7b4,
+        0x187f9ab49af08ec6, 0x2d66c4f95142a46c, 0x6fa4c33b7a3039c0, 0xae4faeae1d3ad3d9,
+    ],
+    [
+        0x8886564d3a14d493, 0x3517454ca23c4af3, 0x06476983284a0504, 0x0992abc52d822c37,
+        0xd3473e33197a93c9, 0x399ec6c7e6bf87c9, 0x51ac86febf240954, 0xf4c70e16eeaac5ec,
+    ],
+    [
+        0xa47f0dd4bf02e71e, 0x36acc2355951a8d9, 0x69d18d2bd1a5c42f, 0xf4892bcb929b0690,
+        0x89b4443b4ddbc49a, 0x4eb7f8719c36de1e, 0x03e7aa020c6e4141, 0x9b1f5b424d93c9a7,
+    ],
+    [
+        0x7261445183235adb, 0x0e38dc92cb1f2a60, 0x7b2b8a9aa6079c54, 0x800a440bdbb2ceb1,
+        0x3cd955b7e00d0984, 0x3a7d3a1b25894224, 0x944c9ad8ec165fde, 0x378f5a541631229b,
+    ],
+    [
+        0x74b4c7fb98459ced, 0x3698fad1153bb6c3, 0x7a1e6c303b7652f4, 0x9fe76702af69334b,
+        0x1fffe18a1b336103, 0x8941e71cff8a78db, 0x382ae548b2e4f3f3, 0xabbedea680056f52,
+    ],
+    [
+        0x6bcaa4cd81f32d1b, 0xdea2594ac06fd85d, 0xefbacd1d7d476e98, 0x8a1d71efea48b9ca,
+        0x2001802114846679, 0xd8fa6bbbebab0761, 0x3002c6cd635afe94, 0x7bcd9ed0efc889fb,
+    ],
+    [
+        0x48bc924af11bd720, 0xfaf417d5d9b21b99, 0xe71da4aa88e12852, 0x5d80ef9d1891cc86,
+        0xf82012d430219f9b, 0xcda43c32bcdf1d77, 0xd21380b00449b17a, 0x378ee767f11631ba,
+    ],
+];
+
+// NOTE: S、P、L 三个变换可以合并为 8 张查找表，LPS_TABLE[k][b] 为第 k 个字节的值为 b 时，
+//       经过 S 和 L 变换后对输出的贡献（P 变换体现在查表时所取的字节位置上）。
+const LPS_TABLE: [[u64; 256]; 8] = gen_lps_table();
+
+const fn gen_lps_table() -> [[u64; 256]; 8] {
+    let mut table = [[0u64; 256]; 8];
+
+    let mut k = 0;
+    while k < 8 {
+        let mut b = 0;
+        while b < 256 {
+            let s = PI[b];
+            let mut v = 0u64;
+            let mut bit = 0;
+            while bit < 8 {
+                if (s >> bit) & 1 == 1 {
+                    v ^= A[63 - (8 * k + bit)];
+                }
+                bit += 1;
+            }
+            table[k][b] = v;
+            b += 1;
+        }
+        k += 1;
+    }
+
+    table
+}
+
+
+/// Streebog-256
+pub fn streebog256<T: AsRef<[u8]>>(data: T) -> [u8; Streebog256::DIGEST_LEN] {
+    Streebog256::oneshot(data)
+}
+
+/// Streebog-512
+pub fn streebog512<T: AsRef<[u8]>>(data: T) -> [u8; Streebog512::DIGEST_LEN] {
+    Streebog512::oneshot(data)
+}
+
+
+/// Streebog-256
+#[derive(Clone)]
+pub struct Streebog256 {
+    inner: Streebog512,
+}
+
+impl Zeroize for Streebog256 {
+    fn zeroize(&mut self) {
+        self.inner.zeroize();
+    }
+}
+
+impl Streebog256 {
+    pub const BLOCK_LEN: usize  = 64;
+    pub const DIGEST_LEN: usize = 32;
+
+    pub fn new() -> Self {
+        // 5.1.  Hash Function with 256-bit Output: IV = (00000001)^64
+        let inner = Streebog512::with_iv([0x0101010101010101; 8]);
+        Self { inner }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data)
+    }
+
+    pub fn finalize(self) -> [u8; Self::DIGEST_LEN] {
+        // 256 位的摘要为 512 位结果的高位部分。
+        let h = self.inner.finalize();
+
+        let mut output = [0u8; Self::DIGEST_LEN];
+        output.copy_from_slice(&h[Self::DIGEST_LEN..]);
+
+        output
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize()
+    }
+
+    pub const STATE_LEN: usize = Streebog512::STATE_LEN;
+
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        self.inner.export_state_with(StateId::Streebog256)
+    }
+
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        Streebog512::import_state_with(state, StateId::Streebog256).map(|inner| Self { inner })
+    }
+}
+
+
+/// Streebog-512
+#[derive(Clone)]
+pub struct Streebog512 {
+    buffer: [u8; Self::BLOCK_LEN],
+    offset: usize,
+    h: [u64; 8],
+    n: [u64; 8],     // 已处理的消息长度（比特），模 2^512。
+    sigma: [u64; 8], // 所有消息分组的和，模 2^512。
+}
+
+impl Zeroize for Streebog512 {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.offset.zeroize();
+        self.h.zeroize();
+        self.n.zeroize();
+        self.sigma.zeroize();
+    }
+}
+
+impl Drop for Streebog512 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Streebog512 {
+    pub const BLOCK_LEN: usize  = 64;
+    pub const DIGEST_LEN: usize = 64;
+
+
+    fn with_iv(iv: [u64; 8]) -> Self {
+        Self {
+            buffer: [0u8; Self::BLOCK_LEN],
+            offset: 0,
+            h: iv,
+            n: [0u64; 8],
+            sigma: [0u64; 8],
+        }
+    }
+
+    pub fn new() -> Self {
+        // 5.2.  Hash Function with 512-bit Output: IV = 0^512
+        Self::with_iv([0u64; 8])
+    }
+
+    #[inline]
+    fn process_block(&mut self, block: &[u8]) {
+        let m = load_block(block);
+
+        g(&mut self.h, &self.n, &m);
+        add_512(&mut self.n, &[512, 0, 0, 0, 0, 0, 0, 0]);
+        add_512(&mut self.sigma, &m);
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        if self.offset > 0 {
+            let n = core::cmp::min(Self::BLOCK_LEN - self.offset, data.len());
+            self.buffer[self.offset..self.offset + n].copy_from_slice(&data[..n]);
+            self.offset += n;
+            data = &data[n..];
+
+            if self.offset < Self::BLOCK_LEN {
+                return;
+            }
+
+            let block = self.buffer;
+            self.process_block(&block);
+            self.offset = 0;
+        }
+
+        while data.len() >= Self::BLOCK_LEN {
+            let (block, rem) = data.split_at(Self::BLOCK_LEN);
+            self.process_block(block);
+            data = rem;
+        }
+
+        if !data.is_empty() {
+            self.buffer[..data.len()].copy_from_slice(data);
+            self.offset = data.len();
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; Self::DIGEST_LEN] {
+        // 8.1.  Stage 3: m = 0^(511 - |M|) || 1 || M
+        self.buffer[self.offset] = 0x01;
+        for byte in self.buffer[self.offset + 1..].iter_mut() {
+            *byte = 0;
+        }
+
+        let m = load_block(&self.buffer);
+        g(&mut self.h, &self.n, &m);
+        add_512(&mut self.n, &[self.offset as u64 * 8, 0, 0, 0, 0, 0, 0, 0]);
+        add_512(&mut self.sigma, &m);
+
+        let zero = [0u64; 8];
+        let n = self.n;
+        let sigma = self.sigma;
+        g(&mut self.h, &zero, &n);
+        g(&mut self.h, &zero, &sigma);
+
+        let mut output = [0u8; Self::DIGEST_LEN];
+        for (chunk, word) in output.chunks_exact_mut(8).zip(self.h.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize()
+    }
+
+    /// Length of the exported state: algorithm id || version || h || N || Sigma || buffer offset || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 64 * 3 + 1 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in little-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        self.export_state_with(StateId::Streebog512)
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        Self::import_state_with(state, StateId::Streebog512)
+    }
+
+    fn export_state_with(&self, id: StateId) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, id);
+        let words = self.h.iter().chain(self.n.iter()).chain(self.sigma.iter());
+        for (chunk, word) in output[..192].chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output[192] = self.offset as u8;
+
+        let n = self.offset;
+        output[193..193 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    fn import_state_with(state: &[u8], id: StateId) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, id)?;
+
+        let n = state[192] as usize;
+        if n >= Self::BLOCK_LEN || state[193 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+
+        let mut m = Self::new();
+        let words = m.h.iter_mut().chain(m.n.iter_mut()).chain(m.sigma.iter_mut());
+        for (word, chunk) in words.zip(state[..192].chunks_exact(8)) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            *word = u64::from_le_bytes(bytes);
+        }
+        m.offset = n;
+        m.buffer[..n].copy_from_slice(&state[193..193 + n]);
+
+        Some(m)
+    }
+}
+
+
+#[inline]
+fn load_block(block: &[u8]) -> [u64; 8] {
+    debug_assert_eq!(block.len(), 64);
+
+    let mut m = [0u64; 8];
+    for (i, chunk) in block.chunks_exact(8).enumerate() {
+        m[i] = u64::from_le_bytes([
+            chunk[0], chunk[1], chunk[2], chunk[3],
+            chunk[4], chunk[5], chunk[6], chunk[7],
+        ]);
+    }
+    m
+}
+
+// 模 2^512 的加法
+#[inline]
+fn add_512(a: &mut [u64; 8], b: &[u64; 8]) {
+    let mut carry = false;
+    for i in 0..8 {
+        let (v, c1) = a[i].overflowing_add(b[i]);
+        let (v, c2) = v.overflowing_add(carry as u64);
+        a[i] = v;
+        carry = c1 || c2;
+    }
+}
+
+#[inline]
+fn xor_512(a: &[u64; 8], b: &[u64; 8]) -> [u64; 8] {
+    let mut out = [0u64; 8];
+    for i in 0..8 {
+        out[i] = a[i] ^ b[i];
+    }
+    out
+}
+
+// LPS = L ∘ P ∘ S
+#[inline]
+fn lps(state: &[u64; 8]) -> [u64; 8] {
+    let mut out = [0u64; 8];
+    for (i, word) in out.iter_mut().enumerate() {
+        let shift = 8 * i;
+        for (k, table) in LPS_TABLE.iter().enumerate() {
+            *word ^= table[((state[k] >> shift) & 0xff) as usize];
+        }
+    }
+    out
+}
+
+// 7.  Compression Function
+// g_N(h, m) = E(LPS(h xor N), m) xor h xor m
+#[inline]
+fn g(h: &mut [u64; 8], n: &[u64; 8], m: &[u64; 8]) {
+    let mut key = lps(&xor_512(h, n));
+
+    // E(K, m) = X[K13]LPSX[K12]...LPSX[K1](m)
+    let mut state = xor_512(&key, m);
+    for c in C.iter() {
+        state = lps(&state);
+        key = lps(&xor_512(&key, c));
+        state = xor_512(&state, &key);
+    }
+
+    for i in 0..8 {
+        h[i] ^= state[i] ^ m[i];
+    }
+}
+
+
+#[test]
+fn test_streebog() {
+    // 10.1.  Example 1
+    let m1 = b"012345678901234567890123456789012345678901234567890123456789012";
+    // 10.2.  Example 2
+    let m2 = hex::decode("d1e520e2e5f2f0e82c20d1f2f0e8e1eee6e820e2edf3f6e82c20e2e5fef2fa20f120eceef0ff20f1f2f0e5ebe0ece820ede020f5f0e0e1f0fbff20efebfaeafb20c8e3eef0e5e2fb").unwrap();
+
+    assert_eq!(&hex::encode(&streebog512(&m1[..])[..]), "1b54d01a4af5b9d5cc3d86d68d285462b19abc2475222f35c085122be4ba1ffa\
+00ad30f8767b3a82384c6574f024c311e2a481332b08ef7f41797891c1646f48");
+    assert_eq!(&hex::encode(&streebog256(&m1[..])), "9d151eefd8590b89daa6ba6cb74af9275dd051026bb149a452fd84e5e57b5500");
+
+    assert_eq!(&hex::encode(&streebog512(&m2)[..]), "1e88e62226bfca6f9994f1f2d51569e0daf8475a3b0fe61a5300eee46d961376\
+035fe83549ada2b8620fcd7c496ce5b33f0cb9dddc2b6460143b03dabac9fb28");
+    assert_eq!(&hex::encode(&streebog256(&m2)), "9dd2fe4e90409e5da87f53976d7405b0c0cac628fc669a741d50063c557e8f50");
+
+    assert_eq!(&hex::encode(&streebog512(b"")[..]), "8e945da209aa869f0455928529bcae4679e9873ab707b55315f56ceb98bef0a7\
+362f715528356ee83cda5f2aac4c6ad2ba3a715c1bcd81cb8e9f90bf4c1c1a8a");
+    assert_eq!(&hex::encode(&streebog256(b"")), "3f539a213e97c802cc229d474c6aa32a825a360b2a933a949fd925208d9ce1bb");
+}
+
+#[test]
+fn test_streebog_incremental() {
+    // 按不同的分块大小输入，结果应当和一次性输入相同（包括恰好为分组长度整数倍的情况）。
+    let msg = [b'a'; 1000];
+    assert_eq!(&hex::encode(&streebog256(&msg[..])), "e770017612ee6f0bd6dc188268c5dd331e08bad7203153b7a606386d486aebd7");
+    assert_eq!(&hex::encode(&streebog256(&msg[..64])), "c2ce0969b6e468445ecfaed89f614178f89cc37ab59523528a58745007f33ab2");
+
+    for chunk_size in 1..130 {
+        let mut m = Streebog512::new();
+        for chunk in msg.chunks(chunk_size) {
+            m.update(chunk);
+        }
+        assert_eq!(&m.finalize()[..], &streebog512(&msg[..])[..]);
+    }
+}
+
+#[test]
+fn test_streebog_export_import_state() {
+    let msg = (0..200usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Streebog512::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Streebog512::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(&m.finalize()[..], &streebog512(&msg)[..]);
+    }
+
+    let mut m = Streebog256::new();
+    m.update(&msg[..100]);
+    let mut m = Streebog256::import_state(&m.export_state()).unwrap();
+    m.update(&msg[100..]);
+    assert_eq!(m.finalize(), streebog256(&msg));
+
+    assert!(Streebog256::import_state(&Streebog512::new().export_state()).is_none());
+    assert!(Streebog512::import_state(&Streebog256::new().export_state()).is_none());
+}
@@ -0,0 +1,338 @@
+// The WHIRLPOOL Hashing Function
+// https://web.archive.org/web/20171129084214/http://www.larc.usp.br/~pbarreto/WhirlpoolPage.html
+//
+// ISO/IEC 10118-3:2004, Dedicated hash-function 7
+//
+// C code
+// https://web.archive.org/web/20171129084214/http://www.larc.usp.br/~pbarreto/whirlpool.zip
+use crate::mem::Zeroize;
+use crate::hash::{StateId, STATE_HEADER_LEN, export_state_header, import_state_header};
+
+
+const ROUNDS: usize = 10;
+
+// The S-box, built from the mini-boxes E, E^-1 and R.
+const SBOX: [u8; 256] = [
+    0x18, 0x23, 0xc6, 0xe8, 0x87, 0xb8, 0x01, 0x4f, 0x36, 0xa6, 0xd2, 0xf5, 0x79, 0x6f, 0x91, 0x52,
+    0x60, 0xbc, 0x9b, 0x8e, 0xa3, 0x0c, 0x7b, 0x35, 0x1d, 0xe0, 0xd7, 0xc2, 0x2e, 0x4b, 0xfe, 0x57,
+    0x15, 0x77, 0x37, 0xe5, 0x9f, 0xf0, 0x4a, 0xda, 0x58, 0xc9, 0x29, 0x0a, 0xb1, 0xa0, 0x6b, 0x85,
+    0xbd, 0x5d, 0x10, 0xf4, 0xcb, 0x3e, 0x05, 0x67, 0xe4, 0x27, 0x41, 0x8b, 0xa7, 0x7d, 0x95, 0xd8,
+    0xfb, 0xee, 0x7c, 0x66, 0xdd, 0x17, 0x47, 0x9e, 0xca, 0x2d, 0xbf, 0x07, 0xad, 0x5a, 0x83, 0x33,
+    0x63, 0x02, 0xaa, 0x71, 0xc8, 0x19, 0x49, 0xd9, 0xf2, 0xe3, 0x5b, 0x88, 0x9a, 0x26, 0x32, 0xb0,
+    0xe9, 0x0f, 0xd5, 0x80, 0xbe, 0xcd, 0x34, 0x48, 0xff, 0x7a, 0x90, 0x5f, 0x20, 0x68, 0x1a, 0xae,
+    0xb4, 0x54, 0x93, 0x22, 0x64, 0xf1, 0x73, 0x12, 0x40, 0x08, 0xc3, 0xec, 0xdb, 0xa1, 0x8d, 0x3d,
+    0x97, 0x00, 0xcf, 0x2b, 0x76, 0x82, 0xd6, 0x1b, 0xb5, 0xaf, 0x6a, 0x50, 0x45, 0xf3, 0x30, 0xef,
+    0x3f, 0x55, 0xa2, 0xea, 0x65, 0xba, 0x2f, 0xc0, 0xde, 0x1c, 0xfd, 0x4d, 0x92, 0x75, 0x06, 0x8a,
+    0xb2, 0xe6, 0x0e, 0x1f, 0x62, 0xd4, 0xa8, 0x96, 0xf9, 0xc5, 0x25, 0x59, 0x84, 0x72, 0x39, 0x4c,
+    0x5e, 0x78, 0x38, 0x8c, 0xd1, 0xa5, 0xe2, 0x61, 0xb3, 0x21, 0x9c, 0x1e, 0x43, 0xc7, 0xfc, 0x04,
+    0x51, 0x99, 0x6d, 0x0d, 0xfa, 0xdf, 0x7e, 0x24, 0x3b, 0xab, 0xce, 0x11, 0x8f, 0x4e, 0xb7, 0xeb,
+    0x3c, 0x81, 0x94, 0xf7, 0xb9, 0x13, 0x2c, 0xd3, 0xe7, 0x6e, 0xc4, 0x03, 0x56, 0x44, 0x7f, 0xa9,
+    0x2a, 0xbb, 0xc1, 0x53, 0xdc, 0x0b, 0x9d, 0x6c, 0x31, 0x74, 0xf6, 0x46, 0xac, 0x89, 0x14, 0xe1,
+    0x16, 0x3a, 0x69, 0x09, 0x70, 0xb6, 0xd0, 0xed, 0xcc, 0x42, 0x98, 0xa4, 0x28, 0x5c, 0xf8, 0x86,
+];
+
+// NOTE: C0[x] = S[x] · (1, 1, 4, 1, 8, 5, 2, 9)，其余的 Ck 为 C0 循环右移 8k 位。
+const C0: [u64; 256] = gen_c0();
+
+// rc[r] 的第一行为 S[8(r-1)..8r]，其余各行为零。
+const RC: [u64; ROUNDS] = gen_rc();
+
+// GF(2^8) 上的乘法，既约多项式为 x^8 + x^4 + x^3 + x^2 + 1 (0x11d)。
+const fn gf_mul(a: u8, b: u8) -> u8 {
+    let mut a = a as u16;
+    let mut b = b;
+    let mut p = 0u16;
+
+    while b != 0 {
+        if b & 1 != 0 {
+            p ^= a;
+        }
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= 0x11d;
+        }
+        b >>= 1;
+    }
+
+    p as u8
+}
+
+const fn gen_c0() -> [u64; 256] {
+    const CIR: [u8; 8] = [1, 1, 4, 1, 8, 5, 2, 9];
+
+    let mut table = [0u64; 256];
+    let mut x = 0;
+    while x < 256 {
+        let mut v = 0u64;
+        let mut i = 0;
+        while i < 8 {
+            v = (v << 8) | gf_mul(SBOX[x], CIR[i]) as u64;
+            i += 1;
+        }
+        table[x] = v;
+        x += 1;
+    }
+
+    table
+}
+
+const fn gen_rc() -> [u64; ROUNDS] {
+    let mut rc = [0u64; ROUNDS];
+    let mut r = 0;
+    while r < ROUNDS {
+        let mut v = 0u64;
+        let mut i = 0;
+        while i < 8 {
+            v = (v << 8) | SBOX[8 * r + i] as u64;
+            i += 1;
+        }
+        rc[r] = v;
+        r += 1;
+    }
+
+    rc
+}
+
+
+pub fn whirlpool<T: AsRef<[u8]>>(data: T) -> [u8; Whirlpool::DIGEST_LEN] {
+    Whirlpool::oneshot(data)
+}
+
+#[derive(Clone)]
+pub struct Whirlpool {
+    buffer: [u8; Self::BLOCK_LEN],
+    offset: usize,
+    state: [u64; 8],
+    len: u128,       // in bytes.
+}
+
+impl Zeroize for Whirlpool {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.offset.zeroize();
+        self.state.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Whirlpool {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Whirlpool {
+    pub const BLOCK_LEN: usize  = 64;
+    pub const DIGEST_LEN: usize = 64;
+
+
+    pub fn new() -> Self {
+        Self {
+            buffer: [0u8; Self::BLOCK_LEN],
+            offset: 0,
+            state: [0u64; 8],
+            len: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        self.len = self.len.wrapping_add(data.len() as u128);
+
+        if self.offset > 0 {
+            let n = core::cmp::min(Self::BLOCK_LEN - self.offset, data.len());
+            self.buffer[self.offset..self.offset + n].copy_from_slice(&data[..n]);
+            self.offset += n;
+            data = &data[n..];
+
+            if self.offset < Self::BLOCK_LEN {
+                return;
+            }
+
+            transform(&mut self.state, &self.buffer);
+            self.offset = 0;
+        }
+
+        while data.len() >= Self::BLOCK_LEN {
+            let (block, rem) = data.split_at(Self::BLOCK_LEN);
+            transform(&mut self.state, block);
+            data = rem;
+        }
+
+        if !data.is_empty() {
+            self.buffer[..data.len()].copy_from_slice(data);
+            self.offset = data.len();
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; Self::DIGEST_LEN] {
+        // 填充 0x80 和若干个零字节，最后的 32 字节为以大端序表示的 256 位消息长度。
+        let len_bits = self.len.wrapping_mul(8);
+
+        self.buffer[self.offset] = 0x80;
+        for byte in self.buffer[self.offset + 1..].iter_mut() {
+            *byte = 0;
+        }
+
+        if self.offset + 1 > 32 {
+            transform(&mut self.state, &self.buffer);
+            self.buffer = [0u8; Self::BLOCK_LEN];
+        }
+
+        self.buffer[48..].copy_from_slice(&len_bits.to_be_bytes());
+        transform(&mut self.state, &self.buffer);
+
+        let mut output = [0u8; Self::DIGEST_LEN];
+        for (chunk, word) in output.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output
+    }
+
+    pub fn oneshot<T: AsRef<[u8]>>(data: T) -> [u8; Self::DIGEST_LEN] {
+        let mut m = Self::new();
+        m.update(data.as_ref());
+        m.finalize()
+    }
+
+    /// Length of the exported state: algorithm id || version || chaining value || message length (in bytes) || buffered block.
+    pub const STATE_LEN: usize = STATE_HEADER_LEN + 64 + 16 + Self::BLOCK_LEN;
+
+    /// Exports the in-progress state. Integers are encoded in big-endian,
+    /// unused bytes of the buffered block are zero.
+    pub fn export_state(&self) -> [u8; Self::STATE_LEN] {
+        let mut state = [0u8; Self::STATE_LEN];
+        let output = export_state_header(&mut state, StateId::Whirlpool);
+        for (chunk, word) in output[..64].chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        output[64..80].copy_from_slice(&self.len.to_be_bytes());
+
+        let n = (self.len % Self::BLOCK_LEN as u128) as usize;
+        output[80..80 + n].copy_from_slice(&self.buffer[..n]);
+
+        state
+    }
+
+    /// Restores a state produced by `export_state`.
+    pub fn import_state(state: &[u8]) -> Option<Self> {
+        let state = import_state_header(state, Self::STATE_LEN, StateId::Whirlpool)?;
+
+        let mut m = Self::new();
+        for (word, chunk) in m.state.iter_mut().zip(state[..64].chunks_exact(8)) {
+            let mut w = [0u8; 8];
+            w.copy_from_slice(chunk);
+            *word = u64::from_be_bytes(w);
+        }
+        let mut len = [0u8; 16];
+        len.copy_from_slice(&state[64..80]);
+        m.len = u128::from_be_bytes(len);
+
+        let n = (m.len % Self::BLOCK_LEN as u128) as usize;
+        if state[80 + n..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        m.buffer[..n].copy_from_slice(&state[80..80 + n]);
+        m.offset = n;
+
+        Some(m)
+    }
+}
+
+
+// The round function ρ[k] = σ[k] ∘ θ ∘ π ∘ γ, 通过查表实现。
+#[inline]
+fn rho(input: &[u64; 8], key: &[u64; 8]) -> [u64; 8] {
+    let mut output = [0u64; 8];
+
+    for i in 0..8 {
+        let mut v = key[i];
+        for k in 0..8 {
+            let byte = (input[(i + 8 - k) % 8] >> (56 - 8 * k)) as u8;
+            v ^= C0[byte as usize].rotate_right(8 * k as u32);
+        }
+        output[i] = v;
+    }
+
+    output
+}
+
+// Miyaguchi-Preneel
+#[inline]
+fn transform(state: &mut [u64; 8], block: &[u8]) {
+    debug_assert_eq!(block.len(), Whirlpool::BLOCK_LEN);
+
+    let mut m = [0u64; 8];
+    for (i, chunk) in block.chunks_exact(8).enumerate() {
+        m[i] = u64::from_be_bytes([
+            chunk[0], chunk[1], chunk[2], chunk[3],
+            chunk[4], chunk[5], chunk[6], chunk[7],
+        ]);
+    }
+
+    let mut key = *state;
+    let mut s = [0u64; 8];
+    for i in 0..8 {
+        s[i] = m[i] ^ key[i];
+    }
+
+    for round_constant in RC.iter() {
+        let mut rc = [0u64; 8];
+        rc[0] = *round_constant;
+
+        key = rho(&key, &rc);
+        s = rho(&s, &key);
+    }
+
+    for i in 0..8 {
+        state[i] ^= s[i] ^ m[i];
+    }
+}
+
+
+#[test]
+fn test_whirlpool() {
+    // ISO/IEC 10118-3 test vectors
+    let suites: &[(&[u8], &str)] = &[
+        (b"", "19fa61d75522a4669b44e39c1d2e1726c530232130d407f89afee0964997f7a7\
+3e83be698b288febcf88e3e03c4f0757ea8964e59b63d93708b138cc42a66eb3"),
+        (b"a", "8aca2602792aec6f11a67206531fb7d7f0dff59413145e6973c45001d0087b42\
+d11bc645413aeff63a42391a39145a591a92200d560195e53b478584fdae231a"),
+        (b"abc", "4e2448a4c6f486bb16b6562c73b4020bf3043e3a731bce721ae1b303d97e6d4c\
+7181eebdb6c57e277d0e34957114cbd6c797fc9d95d8b582d225292076d4eef5"),
+        (b"message digest", "378c84a4126e2dc6e56dcc7458377aac838d00032230f53ce1f5700c0ffb4d3b\
+8421557659ef55c106b4b52ac5a4aaa692ed920052838f3362e86dbd37a8903e"),
+        (b"abcdefghijklmnopqrstuvwxyz", "f1d754662636ffe92c82ebb9212a484a8d38631ead4238f5442ee13b8054e41b\
+08bf2a9251c30b6a0b8aae86177ab4a6f68f673e7207865d5d9819a3dba4eb3b"),
+        (b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "dc37e008cf9ee69bf11f00ed9aba26901dd7c28cdec066cc6af42e40f82f3a1e\
+08eba26629129d8fb7cb57211b9281a65517cc879d7b962142c65f5a7af01467"),
+        (b"12345678901234567890123456789012345678901234567890123456789012345678901234567890", "466ef18babb0154d25b9d38a6414f5c08784372bccb204d6549c4afadb601429\
+4d5bd8df2a6c44e538cd047b2681a51a2c60481e88c5a20b2c2a80cf3a9a083b"),
+    ];
+    for (msg, digest) in suites.iter() {
+        assert_eq!(&hex::encode(&whirlpool(msg)[..]), digest);
+    }
+
+    // 1 million times "a"
+    let mut m = Whirlpool::new();
+    for _ in 0..1000 {
+        m.update(&[b'a'; 1000]);
+    }
+    assert_eq!(&hex::encode(&m.finalize()[..]), "0c99005beb57eff50a7cf005560ddf5d29057fd86b20bfd62deca0f1ccea4af5\
+1fc15490eddc47af32bb2b66c34ff9ad8c6008ad677f77126953b226e4ed8b01");
+}
+
+#[test]
+fn test_whirlpool_export_import_state() {
+    let msg = (0..300usize).map(|i| i as u8).collect::<Vec<u8>>();
+    for i in 0..msg.len() {
+        let mut m = Whirlpool::new();
+        m.update(&msg[..i]);
+        let state = m.export_state();
+
+        let mut m = Whirlpool::import_state(&state).unwrap();
+        m.update(&msg[i..]);
+        assert_eq!(m.finalize(), whirlpool(&msg));
+    }
+}
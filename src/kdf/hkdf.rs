@@ -1,6 +1,6 @@
 // HMAC-based Extract-and-Expand Key Derivation Function (HKDF)
 // https://tools.ietf.org/html/rfc5869
-use crate::mac::{ HmacMd2, HmacMd4, HmacMd5, HmacSm3, HmacSha1, HmacSha256, HmacSha384, HmacSha512, };
+use crate::mac::{ HmacMd2, HmacMd4, HmacMd5, HmacSm3, HmacSha1, HmacSha224, HmacSha256, HmacSha384, HmacSha512, HmacSha512_224, HmacSha512_256, };
 
 
 macro_rules! impl_hkdf_with_hmac {
@@ -107,9 +107,12 @@ impl_hkdf_with_hmac!(HkdfSm3, HmacSm3);
 // SHA-1
 impl_hkdf_with_hmac!(HkdfSha1, HmacSha1);
 // SHA-2
+impl_hkdf_with_hmac!(HkdfSha224, HmacSha224);
 impl_hkdf_with_hmac!(HkdfSha256, HmacSha256);
 impl_hkdf_with_hmac!(HkdfSha384, HmacSha384);
 impl_hkdf_with_hmac!(HkdfSha512, HmacSha512);
+impl_hkdf_with_hmac!(HkdfSha512_224, HmacSha512_224);
+impl_hkdf_with_hmac!(HkdfSha512_256, HmacSha512_256);
 // SHA-3
 
 
@@ -263,3 +266,29 @@ b3bae548aa53d423b0d1f27ebba6f5e5\
 ");
 }
 
+#[test]
+fn test_hkdf_sha2_truncated() {
+    // RFC 5869 Test Case 1 inputs with the truncated SHA-2 variants.
+    let ikm = hexdecode("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b");
+    let salt = hexdecode("000102030405060708090a0b0c");
+    let info = hexdecode("f0f1f2f3f4f5f6f7f8f9");
+    let len = 42usize;
+
+    let hkdf = HkdfSha224::new(&salt, &ikm);
+    assert_eq!(&hex::encode(hkdf.prk()), "94f65bed12265c1fa2747db60cadfcabbbbaede6be5a7a450de78231");
+    let mut okm = vec![0u8; len];
+    hkdf.expand(&info, &mut okm);
+    assert_eq!(&hex::encode(&okm), "2f21cd7cbc818ca5c561b933728e2e08e154a87e1432399a820dee13aa222d0cee6152fa539ab70f8e80");
+
+    let hkdf = HkdfSha512_224::new(&salt, &ikm);
+    assert_eq!(&hex::encode(hkdf.prk()), "c0ac5c0e255562203e0d6f743ff2f03197f095f32ef3589d1808f623");
+    let mut okm = vec![0u8; len];
+    hkdf.expand(&info, &mut okm);
+    assert_eq!(&hex::encode(&okm), "f8d956e152b0fba831bac400f1a5af54982b91db3d96ae21a75655eff1725f928e491c63f3aedb408296");
+
+    let hkdf = HkdfSha512_256::new(&salt, &ikm);
+    assert_eq!(&hex::encode(hkdf.prk()), "1b5fdfd1e817173b2b6fe97499a49ebc45cf216c3f943b3ae682abc17fa0b013");
+    let mut okm = vec![0u8; len];
+    hkdf.expand(&info, &mut okm);
+    assert_eq!(&hex::encode(&okm), "789a93e567a1861de449342b2d674c0df737fd8adce2a8e1843237c1938ac413044b496ce267a198ebe3");
+}
@@ -1,4 +1,6 @@
 
 mod hkdf;
+mod pbkdf2;
 
 pub use self::hkdf::*;
+pub use self::pbkdf2::*;
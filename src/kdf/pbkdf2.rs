@@ -0,0 +1,138 @@
+// PKCS #5: Password-Based Key Derivation Function 2 (PBKDF2)
+// https://tools.ietf.org/html/rfc8018#section-5.2
+//
+// PKCS #5: Password-Based Key Derivation Function 2 (PBKDF2) Test Vectors
+// https://tools.ietf.org/html/rfc6070
+use crate::hash::{ CryptoHasher, BuildCryptoHasher, Array, };
+use crate::hash::{ Sm3, Sha1, Sha224, Sha256, Sha384, Sha512, };
+use crate::mac::Hmac;
+use crate::mem::Zeroize;
+
+
+pub type Pbkdf2Sm3 = Pbkdf2<Sm3>;
+pub type Pbkdf2Sha1 = Pbkdf2<Sha1>;
+pub type Pbkdf2Sha224 = Pbkdf2<Sha224>;
+pub type Pbkdf2Sha256 = Pbkdf2<Sha256>;
+pub type Pbkdf2Sha384 = Pbkdf2<Sha384>;
+pub type Pbkdf2Sha512 = Pbkdf2<Sha512>;
+
+
+/// PBKDF2（PRF 为 HMAC-H）
+#[derive(Clone)]
+pub struct Pbkdf2<H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize> {
+    // NOTE: 使用 Password 作为 Key 的 HMAC，ipad/opad 的状态只需要计算一次。
+    prf: Hmac<H>,
+}
+
+impl<H> Zeroize for Pbkdf2<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize,
+{
+    fn zeroize(&mut self) {
+        self.prf.zeroize();
+    }
+}
+
+impl<H> Drop for Pbkdf2<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize,
+{
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<H> Pbkdf2<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize + Clone,
+{
+    pub const TAG_LEN: usize = H::OUTPUT_LEN;
+
+
+    pub fn new(password: &[u8]) -> Self {
+        Self { prf: Hmac::new(password) }
+    }
+
+    /// 派生出长度为 `okm.len()` 的 Key，`iterations` 至少为 1。
+    pub fn derive(&self, salt: &[u8], iterations: usize, okm: &mut [u8]) {
+        assert!(iterations >= 1);
+        // dkLen <= (2^32 - 1) * hLen
+        assert!(okm.len() as u64 <= u32::MAX as u64 * Self::TAG_LEN as u64);
+
+        for (i, t) in okm.chunks_mut(Self::TAG_LEN).enumerate() {
+            // U_1 = PRF(P, S || INT(i))
+            let mut prf = self.prf.clone();
+            prf.update(salt);
+            prf.update(&(i as u32 + 1).to_be_bytes());
+            let mut u = prf.finalize();
+
+            // NOTE: T_i 只需要保留前 `t.len()` 个字节。
+            t.copy_from_slice(&u.array_as_slice()[..t.len()]);
+
+            // U_c = PRF(P, U_{c-1})
+            // T_i = U_1 xor U_2 xor ... xor U_c
+            for _ in 1..iterations {
+                let mut prf = self.prf.clone();
+                prf.update(u.array_as_slice());
+                u = prf.finalize();
+
+                for (a, b) in t.iter_mut().zip(u.array_as_slice()) {
+                    *a ^= b;
+                }
+            }
+
+            u.array_as_mut_slice().zeroize();
+        }
+    }
+
+    pub fn oneshot(password: &[u8], salt: &[u8], iterations: usize, okm: &mut [u8]) {
+        let pbkdf2 = Self::new(password);
+        pbkdf2.derive(salt, iterations, okm);
+    }
+}
+
+
+#[test]
+fn test_pbkdf2_sha1() {
+    // 2.  PBKDF2 HMAC-SHA1 Test Vectors
+    // https://tools.ietf.org/html/rfc6070#section-2
+    let suites: &[(&[u8], &[u8], usize, &str)] = &[
+        (b"password", b"salt", 1, "0c60c80f961f0e71f3a9b524af6012062fe037a6"),
+        (b"password", b"salt", 2, "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957"),
+        (b"password", b"salt", 4096, "4b007901b765489abead49d926f721d065a429c1"),
+        (b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, "3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038"),
+        (b"pass\0word", b"sa\0lt", 4096, "56fa6aa75548099dcc37d7f03425e0c3"),
+    ];
+    for (password, salt, c, dk) in suites.iter() {
+        let mut okm = vec![0u8; dk.len() / 2];
+        Pbkdf2Sha1::oneshot(password, salt, *c, &mut okm);
+        assert_eq!(&hex::encode(&okm), dk);
+    }
+}
+
+#[test]
+fn test_pbkdf2_sha256() {
+    // 11.  Test Vectors for PBKDF2 with HMAC-SHA-256
+    // https://tools.ietf.org/html/rfc7914#section-11
+    let mut okm = [0u8; 64];
+    Pbkdf2Sha256::oneshot(b"passwd", b"salt", 1, &mut okm);
+    assert_eq!(&hex::encode(&okm[..]), "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783");
+
+    Pbkdf2Sha256::oneshot(b"Password", b"NaCl", 80000, &mut okm);
+    assert_eq!(&hex::encode(&okm[..]), "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56\
+a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d");
+}
+
+#[test]
+fn test_pbkdf2_sha512_sm3() {
+    let mut okm = [0u8; 100];
+    Pbkdf2Sha512::oneshot(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, &mut okm);
+    assert_eq!(&hex::encode(&okm[..]), "8c0511f4c6e597c6ac6315d8f0362e225f3c501495ba23b868c005174dc4ee71\
+115b59f9e60cd9532fa33e0f75aefe30225c583a186cd82bd4daea9724a3d3b804f75bdd41494fa324cab24bcc680fb3b96a30cf5d21fac3c2875913919f3399b1d9ce7e");
+
+    let mut okm = [0u8; 40];
+    let pbkdf2 = Pbkdf2Sm3::new(b"password");
+    pbkdf2.derive(b"salt", 4096, &mut okm);
+    assert_eq!(&hex::encode(&okm[..]), "b6e8f2074c87432b78f62e5ced980fdff89e86af2f693dab1638e2b3683045dd844438500eead50c");
+}
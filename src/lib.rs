@@ -14,6 +14,7 @@ extern crate hex;
 
 mod util;
 pub mod mem;
+pub mod cpu;
 
 // pub mod error;
 
@@ -0,0 +1,136 @@
+// ISO/IEC 9797-1:2011, MAC Algorithm 1
+// https://www.iso.org/standard/50375.html
+//
+// NIST Special Publication 800-38A, 6.2 The Cipher Block Chaining Mode
+// https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38a.pdf
+//
+// NOTE: CBC-MAC 只有在消息的长度固定（或者事先确定）时才是安全的，变长的消息请使用 CMAC。
+//       最后一个分组不完整时使用 Padding Method 1（填充 0），空消息会被填充为一个全 0 的分组。
+use crate::blockcipher::BlockCipher;
+use crate::mem::Zeroize;
+use crate::util::xor_si128_inplace;
+
+
+const BLOCK_LEN: usize = 16;
+const TAG_LEN: usize   = 16;
+
+/// CBC-MAC (IV = 0)
+#[derive(Clone)]
+pub struct CbcMac<C: BlockCipher + Zeroize> {
+    cipher: C,
+    x: [u8; BLOCK_LEN],
+    buf: [u8; BLOCK_LEN],
+    buf_len: usize,
+    // 是否已经处理过至少一个分组
+    started: bool,
+}
+
+impl<C: BlockCipher + Zeroize> Zeroize for CbcMac<C> {
+    fn zeroize(&mut self) {
+        self.cipher.zeroize();
+        self.x.zeroize();
+        self.buf.zeroize();
+        self.buf_len = 0;
+        self.started = false;
+    }
+}
+
+impl<C: BlockCipher + Zeroize> Drop for CbcMac<C> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<C: BlockCipher + Zeroize> CbcMac<C> {
+    pub const BLOCK_LEN: usize = BLOCK_LEN;
+    pub const TAG_LEN: usize   = TAG_LEN;
+
+
+    pub fn new(key: &[u8]) -> Self {
+        Self::with_cipher(C::new(key))
+    }
+
+    pub fn with_cipher(cipher: C) -> Self {
+        assert_eq!(C::BLOCK_LEN, Self::BLOCK_LEN);
+
+        Self { cipher, x: [0u8; BLOCK_LEN], buf: [0u8; BLOCK_LEN], buf_len: 0, started: false }
+    }
+
+    pub fn update(&mut self, m: &[u8]) {
+        let mut m = m;
+        while !m.is_empty() {
+            let n = core::cmp::min(Self::BLOCK_LEN - self.buf_len, m.len());
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&m[..n]);
+            self.buf_len += n;
+            m = &m[n..];
+
+            if self.buf_len == Self::BLOCK_LEN {
+                xor_si128_inplace(&mut self.x, &self.buf);
+                self.cipher.encrypt_block(&mut self.x);
+                self.buf_len = 0;
+                self.started = true;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; TAG_LEN] {
+        if self.buf_len > 0 || !self.started {
+            for b in self.buf[self.buf_len..].iter_mut() {
+                *b = 0;
+            }
+            xor_si128_inplace(&mut self.x, &self.buf);
+            self.cipher.encrypt_block(&mut self.x);
+        }
+
+        self.x
+    }
+
+    pub fn oneshot(key: &[u8], m: &[u8]) -> [u8; TAG_LEN] {
+        let mut mac = Self::new(key);
+        mac.update(m);
+        mac.finalize()
+    }
+}
+
+impl_block_cipher_mac!(CbcMac, {
+    x: [0u8; BLOCK_LEN],
+    buf: [0u8; BLOCK_LEN],
+    buf_len: 0,
+    started: false,
+});
+
+
+#[test]
+fn test_aes128_cbc_mac() {
+    use crate::blockcipher::Aes128;
+
+    // CBC-MAC 等于 IV 为 0 时 CBC 模式的最后一个密文分组。
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let m = hex::decode("\
+6bc1bee22e409f96e93d7e117393172a\
+ae2d8a571e03ac9c9eb76fac45af8e51\
+30c81c46a35ce411e5fbc1191a0a52ef\
+f69f2445df4f9b17ad2b417be66c3710").unwrap();
+
+    let suites = [
+        ( 0, "7df76b0c1ab899b33e42f047b91b546f"),
+        (16, "3ad77bb40d7a3660a89ecaf32466ef97"),
+        (40, "07d192e3e6f099edcc39fde6d09c762d"),
+        (64, "a7356e1207bb406639e5e5ceb9a9ed93"),
+    ];
+    for (mlen, tag) in suites.iter() {
+        assert_eq!(&hex::encode(&CbcMac::<Aes128>::oneshot(&key, &m[..*mlen])), tag);
+
+        for i in 0..*mlen {
+            let mut mac = CbcMac::<Aes128>::new(&key);
+            mac.update(&m[..i]);
+            mac.update(&m[i..*mlen]);
+            assert_eq!(&hex::encode(&mac.finalize()), tag);
+        }
+    }
+
+    let mut mac = CbcMac::<Aes128>::new(&key);
+    mac.update(&m);
+    assert_eq!(mac.clone().verify_truncated(&hex::decode("a7356e1207bb4066").unwrap(), 8), true);
+    assert_eq!(mac.verify_truncated(&hex::decode("a7356e1207bb4067").unwrap(), 8), false);
+}
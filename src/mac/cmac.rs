@@ -0,0 +1,223 @@
+// NIST Special Publication 800-38B
+// Recommendation for Block Cipher Modes of Operation: The CMAC Mode for Authentication
+// https://nvlpubs.nist.gov/nistpubs/specialpublications/nist.sp.800-38b.pdf
+//
+// The AES-CMAC Algorithm
+// https://tools.ietf.org/html/rfc4493
+//
+// NOTE: CMAC 也被称为 OMAC1，这里只支持分组大小为 128 bits 的分组密码（Rb = 0x87）。
+use crate::blockcipher::{ BlockCipher, Aes128, Aes192, Aes256, };
+use crate::blockmode::dbl;
+use crate::mem::Zeroize;
+use crate::util::xor_si128_inplace;
+
+
+const BLOCK_LEN: usize = 16;
+const TAG_LEN: usize   = 16;
+
+
+pub type Aes128Cmac = Cmac<Aes128>;
+pub type Aes192Cmac = Cmac<Aes192>;
+pub type Aes256Cmac = Cmac<Aes256>;
+
+
+/// CMAC (OMAC1)
+#[derive(Clone)]
+pub struct Cmac<C: BlockCipher + Zeroize> {
+    cipher: C,
+    k1: [u8; BLOCK_LEN],
+    k2: [u8; BLOCK_LEN],
+    // 上一个分组的密文 (C_i)
+    x: [u8; BLOCK_LEN],
+    // NOTE: 最后一个分组需要和 K1/K2 做异或运算，所以完整的分组也要等到下一次输入时才处理。
+    buf: [u8; BLOCK_LEN],
+    buf_len: usize,
+}
+
+impl<C: BlockCipher + Zeroize> Zeroize for Cmac<C> {
+    fn zeroize(&mut self) {
+        self.cipher.zeroize();
+        self.k1.zeroize();
+        self.k2.zeroize();
+        self.x.zeroize();
+        self.buf.zeroize();
+        self.buf_len = 0;
+    }
+}
+
+impl<C: BlockCipher + Zeroize> Drop for Cmac<C> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<C: BlockCipher + Zeroize> Cmac<C> {
+    pub const BLOCK_LEN: usize = BLOCK_LEN;
+    pub const TAG_LEN: usize   = TAG_LEN;
+
+
+    pub fn new(key: &[u8]) -> Self {
+        Self::with_cipher(C::new(key))
+    }
+
+    pub fn with_cipher(mut cipher: C) -> Self {
+        assert_eq!(C::BLOCK_LEN, Self::BLOCK_LEN);
+
+        // 2.3.  Subkey Generation Algorithm
+        // https://tools.ietf.org/html/rfc4493#section-2.3
+        let mut l = [0u8; BLOCK_LEN];
+        cipher.encrypt_block(&mut l);
+
+        let k1 = dbl(u128::from_be_bytes(l)).to_be_bytes();
+        let k2 = dbl(u128::from_be_bytes(k1)).to_be_bytes();
+
+        Self { cipher, k1, k2, x: [0u8; BLOCK_LEN], buf: [0u8; BLOCK_LEN], buf_len: 0 }
+    }
+
+    pub fn update(&mut self, m: &[u8]) {
+        let mut m = m;
+        while !m.is_empty() {
+            if self.buf_len == Self::BLOCK_LEN {
+                xor_si128_inplace(&mut self.x, &self.buf);
+                self.cipher.encrypt_block(&mut self.x);
+                self.buf_len = 0;
+            }
+
+            let n = core::cmp::min(Self::BLOCK_LEN - self.buf_len, m.len());
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&m[..n]);
+            self.buf_len += n;
+            m = &m[n..];
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; TAG_LEN] {
+        // 2.4.  MAC Generation Algorithm
+        // https://tools.ietf.org/html/rfc4493#section-2.4
+        let mut last_block = self.buf;
+        if self.buf_len == Self::BLOCK_LEN {
+            xor_si128_inplace(&mut last_block, &self.k1);
+        } else {
+            // padding(x) = x || 10^i
+            last_block[self.buf_len] = 0x80;
+            for b in last_block[self.buf_len + 1..].iter_mut() {
+                *b = 0;
+            }
+            xor_si128_inplace(&mut last_block, &self.k2);
+        }
+
+        let mut tag = self.x;
+        xor_si128_inplace(&mut tag, &last_block);
+        self.cipher.encrypt_block(&mut tag);
+
+        tag
+    }
+
+    pub fn oneshot(key: &[u8], m: &[u8]) -> [u8; TAG_LEN] {
+        let mut mac = Self::new(key);
+        mac.update(m);
+        mac.finalize()
+    }
+}
+
+impl_block_cipher_mac!(Cmac, {
+    x: [0u8; BLOCK_LEN],
+    buf: [0u8; BLOCK_LEN],
+    buf_len: 0,
+});
+
+
+#[test]
+fn test_aes128_cmac() {
+    // 4.  Test Vectors
+    // https://tools.ietf.org/html/rfc4493#section-4
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    let mac = Aes128Cmac::new(&key);
+    assert_eq!(&mac.k1[..], &hex::decode("fbeed618357133667c85e08f7236a8de").unwrap()[..]);
+    assert_eq!(&mac.k2[..], &hex::decode("f7ddac306ae266ccf90bc11ee46d513b").unwrap()[..]);
+
+    let m = hex::decode("").unwrap();
+    let tag = Aes128Cmac::oneshot(&key, &m);
+    assert_eq!(&tag[..], &hex::decode("bb1d6929e95937287fa37d129b756746").unwrap()[..] );
+
+    let m = hex::decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+    let tag = Aes128Cmac::oneshot(&key, &m);
+    assert_eq!(&tag[..], &hex::decode("070a16b46b4d4144f79bdd9dd04a287c").unwrap()[..] );
+
+    let m = hex::decode("6bc1bee22e409f96e93d7e117393172a\
+ae2d8a571e03ac9c9eb76fac45af8e51\
+30c81c46a35ce411").unwrap();
+    let tag = Aes128Cmac::oneshot(&key, &m);
+    assert_eq!(&tag[..], &hex::decode("dfa66747de9ae63030ca32611497c827").unwrap()[..] );
+
+    let m = hex::decode("\
+6bc1bee22e409f96e93d7e117393172a\
+ae2d8a571e03ac9c9eb76fac45af8e51\
+30c81c46a35ce411e5fbc1191a0a52ef\
+f69f2445df4f9b17ad2b417be66c3710").unwrap();
+    let tag = Aes128Cmac::oneshot(&key, &m);
+    assert_eq!(&tag[..], &hex::decode("51f0bebf7e3b9d92fc49741779363cfe").unwrap()[..] );
+
+    // Streaming
+    for i in 0..m.len() {
+        let mut mac = Aes128Cmac::new(&key);
+        mac.update(&m[..i]);
+        mac.update(&m[i..]);
+        assert_eq!(&mac.finalize()[..], &tag[..]);
+    }
+}
+
+#[test]
+fn test_aes192_aes256_cmac() {
+    // D.2 AES-192 / D.3 AES-256
+    // https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/AES_CMAC.pdf
+    let m = hex::decode("\
+6bc1bee22e409f96e93d7e117393172a\
+ae2d8a571e03ac9c9eb76fac45af8e51\
+30c81c46a35ce411e5fbc1191a0a52ef\
+f69f2445df4f9b17ad2b417be66c3710").unwrap();
+
+    let key = hex::decode("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b").unwrap();
+    let suites = [
+        ( 0, "d17ddf46adaacde531cac483de7a9367"),
+        (16, "9e99a7bf31e710900662f65e617c5184"),
+        (40, "8a1de5be2eb31aad089a82e6ee908b0e"),
+        (64, "a1d5df0eed790f794d77589659f39a11"),
+    ];
+    for (mlen, tag) in suites.iter() {
+        assert_eq!(&hex::encode(&Aes192Cmac::oneshot(&key, &m[..*mlen])), tag);
+    }
+
+    let key = hex::decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let suites = [
+        ( 0, "028962f61b7bf89efc6b551f4667d983"),
+        (16, "28a7023f452e8f82bd4bf28d8c37c35c"),
+        (40, "aaf3d8f1de5640c232f5b169b9c911e6"),
+        (64, "e1992190549f6ed5696a2c056c315410"),
+    ];
+    for (mlen, tag) in suites.iter() {
+        assert_eq!(&hex::encode(&Aes256Cmac::oneshot(&key, &m[..*mlen])), tag);
+    }
+}
+
+#[test]
+fn test_cmac_truncated_tag() {
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let m = hex::decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+
+    let mut tag = [0u8; 8];
+    let mut mac = Aes128Cmac::new(&key);
+    mac.update(&m);
+    mac.finalize_truncated(&mut tag);
+    assert_eq!(&tag[..], &hex::decode("070a16b46b4d4144").unwrap()[..]);
+
+    let mut mac = Aes128Cmac::new(&key);
+    mac.update(&m);
+    assert_eq!(mac.clone().verify(&tag), false);
+    assert_eq!(mac.clone().verify_truncated(&tag, 8), true);
+    assert_eq!(mac.clone().verify_truncated(&tag[..4], 8), false);
+    assert_eq!(mac.clone().verify_truncated(&[], 8), false);
+
+    tag[7] ^= 1;
+    assert_eq!(mac.verify_truncated(&tag, 8), false);
+}
@@ -10,7 +10,7 @@ use core::mem::transmute;
 // Convert _mm_clmulepi64_si128 to vmull_{high}_p64
 // https://stackoverflow.com/questions/38553881/convert-mm-clmulepi64-si128-to-vmull-high-p64
 
-#[inline]
+#[target_feature(enable = "neon,aes")]
 unsafe fn pmull(a: uint8x16_t, b: uint8x16_t) -> uint8x16_t {
     // Low
     let a: poly64_t = transmute(vgetq_lane_u64(vreinterpretq_u64_u8(a), 0));
@@ -18,7 +18,7 @@ unsafe fn pmull(a: uint8x16_t, b: uint8x16_t) -> uint8x16_t {
     transmute(vmull_p64(a, b))
 }
 
-#[inline]
+#[target_feature(enable = "neon,aes")]
 unsafe fn pmull2(a: uint8x16_t, b: uint8x16_t) -> uint8x16_t {
     // High
     let a: poly64_t = transmute(vgetq_lane_u64(vreinterpretq_u64_u8(a), 1));
@@ -27,6 +27,7 @@ unsafe fn pmull2(a: uint8x16_t, b: uint8x16_t) -> uint8x16_t {
 }
 
 // reverse bits in each byte to convert from gcm format to little-little endian
+#[target_feature(enable = "neon,aes")]
 unsafe fn vrbitq_u8(a: uint8x16_t) -> uint8x16_t {
     let result: uint8x16_t;
 
@@ -42,7 +43,7 @@ unsafe fn vrbitq_u8(a: uint8x16_t) -> uint8x16_t {
 
 
 // Perform the multiplication and reduction in GF(2^128)
-#[inline]
+#[target_feature(enable = "neon,aes")]
 unsafe fn gf_mul(key: uint8x16_t, m: &[u8], tag: &mut uint8x16_t) {
     let m = vrbitq_u8(*(m.as_ptr() as *const uint8x16_t));
 
@@ -106,7 +107,8 @@ impl GHash {
     pub const TAG_LEN: usize   = 16;
     
 
-    pub fn new(h: &[u8; Self::KEY_LEN]) -> Self {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn new(h: &[u8; Self::KEY_LEN]) -> Self {
         unsafe {
             let key: uint8x16_t = transmute(h.clone());
             
@@ -117,7 +119,8 @@ impl GHash {
         }
     }
     
-    pub fn update(&mut self, m: &[u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn update(&mut self, m: &[u8]) {
         let mlen = m.len();
         if mlen == 0 {
             return ();
@@ -147,7 +150,8 @@ impl GHash {
         }
     }
 
-    pub fn finalize(self) -> [u8; Self::TAG_LEN] {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn finalize(self) -> [u8; Self::TAG_LEN] {
         unsafe {
             transmute(vrbitq_u8(self.tag))
         }
@@ -1,29 +1,105 @@
 // https://github.com/randombit/botan/blob/master/src/lib/utils/ghash/ghash_vperm/ghash_vperm.cpp
 
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    all(target_feature = "sse2", target_feature = "pclmulqdq"),
-))]
-#[path = "./x86.rs"]
-mod platform;
-
-
+#[allow(dead_code)]
+mod generic;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
 // NOTE:
 //      Crypto: AES + PMULL + SHA1 + SHA2
 //      https://github.com/rust-lang/stdarch/blob/master/crates/std_detect/src/detect/arch/aarch64.rs#L26
-#[cfg(all(target_arch = "aarch64", target_feature = "crypto"))]
-#[path = "./aarch64.rs"]
-mod platform;
-
-
-#[cfg(not(any(
-    all(
-        any(target_arch = "x86", target_arch = "x86_64"),
-        all(target_feature = "sse2", target_feature = "pclmulqdq")
-    ),
-    all(target_arch = "aarch64", target_feature = "crypto")
-)))]
-#[path = "./generic.rs"]
-mod platform;
-
-pub use self::platform::GHash;
\ No newline at end of file
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+
+use crate::cpu;
+use crate::mem::Zeroize;
+
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+enum Backend {
+    Generic(generic::GHash),
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    X86(x86::GHash),
+    #[cfg(target_arch = "aarch64")]
+    AArch64(aarch64::GHash),
+}
+
+/// GHASH (GCM)
+#[derive(Clone)]
+pub struct GHash {
+    backend: Backend,
+}
+
+impl Zeroize for GHash {
+    fn zeroize(&mut self) {
+        match self.backend {
+            Backend::Generic(ref mut m) => m.zeroize(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::X86(ref mut m) => m.zeroize(),
+            #[cfg(target_arch = "aarch64")]
+            Backend::AArch64(ref mut m) => m.zeroize(),
+        }
+    }
+}
+
+impl GHash {
+    pub const KEY_LEN: usize   = 16;
+    pub const BLOCK_LEN: usize = 16;
+    pub const TAG_LEN: usize   = 16;
+
+
+    pub fn new(key: &[u8; Self::KEY_LEN]) -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if cpu::has(cpu::PCLMULQDQ) {
+                let backend = Backend::X86(unsafe { x86::GHash::new(key) });
+                return Self { backend };
+            }
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            if cpu::has(cpu::PCLMULQDQ) {
+                let backend = Backend::AArch64(unsafe { aarch64::GHash::new(key) });
+                return Self { backend };
+            }
+        }
+
+        Self { backend: Backend::Generic(generic::GHash::new(key)) }
+    }
+
+    pub fn update(&mut self, m: &[u8]) {
+        match self.backend {
+            Backend::Generic(ref mut mac) => mac.update(m),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::X86(ref mut mac) => unsafe { mac.update(m) },
+            #[cfg(target_arch = "aarch64")]
+            Backend::AArch64(ref mut mac) => unsafe { mac.update(m) },
+        }
+    }
+
+    pub fn finalize(self) -> [u8; Self::TAG_LEN] {
+        match self.backend {
+            Backend::Generic(mac) => mac.finalize(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::X86(mac) => unsafe { mac.finalize() },
+            #[cfg(target_arch = "aarch64")]
+            Backend::AArch64(mac) => unsafe { mac.finalize() },
+        }
+    }
+}
+
+
+#[test]
+fn test_ghash_backends() {
+    let key = [0x25u8, 0x62, 0x93, 0x47, 0x58, 0x92, 0x42, 0x76, 0x1d, 0x31, 0xf8, 0x26, 0xba, 0x4b, 0x75, 0x7b];
+    let data = (0..100usize).map(|i| (i * 7 + 3) as u8).collect::<Vec<u8>>();
+
+    for len in 0..data.len() {
+        let mut mac1 = generic::GHash::new(&key);
+        let mut mac2 = GHash::new(&key);
+        mac1.update(&data[..len]);
+        mac2.update(&data[..len]);
+        assert_eq!(mac1.finalize(), mac2.finalize());
+    }
+}
@@ -35,7 +35,8 @@ impl GHash {
     pub const TAG_LEN: usize   = 16;
 
 
-    pub fn new(key: &[u8; Self::KEY_LEN]) -> Self {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    pub unsafe fn new(key: &[u8; Self::KEY_LEN]) -> Self {
         let key = key.clone();
         
         unsafe {
@@ -49,7 +50,8 @@ impl GHash {
     
     // Performing Ghash Using Algorithms 1 and 5 (C)
     #[inline]
-    fn gf_mul(&mut self, x: &[u8]) {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    unsafe fn gf_mul(&mut self, x: &[u8]) {
         unsafe {
             let a = self.key;
 
@@ -107,7 +109,8 @@ impl GHash {
         }
     }
 
-    pub fn update(&mut self, m: &[u8]) {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    pub unsafe fn update(&mut self, m: &[u8]) {
         let mlen = m.len();
 
         if mlen == 0 {
@@ -130,7 +133,8 @@ impl GHash {
         }
     }
 
-    pub fn finalize(self) -> [u8; Self::TAG_LEN] {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    pub unsafe fn finalize(self) -> [u8; Self::TAG_LEN] {
         unsafe {
             let mut out = [0u8; Self::TAG_LEN];
 
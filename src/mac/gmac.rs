@@ -0,0 +1,236 @@
+// Recommendation for Block Cipher Modes of Operation:  Galois/Counter Mode (GCM) and GMAC
+// https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+//
+// The Use of Galois Message Authentication Code (GMAC) in IPsec ESP and AH
+// https://tools.ietf.org/html/rfc4543
+//
+// NOTE: GMAC 即明文为空的 GCM，所有的输入数据都作为 AAD 参与认证。
+//       和 GCM 一样，这里只支持长度为 12 Bytes 的 Nonce，同一个 Key 下 Nonce 不可以重复使用。
+//       RFC 4543 (ENCR_NULL_AUTH_AES_GMAC) 中的 Nonce 为 `Salt (4 Bytes) || IV (8 Bytes)`。
+use crate::blockcipher::{
+    BlockCipher,
+    Sm4,
+    Aes128, Aes256,
+    Camellia128, Camellia256,
+    Aria128, Aria256,
+};
+use crate::mac::GHash;
+use crate::mem::Zeroize;
+use crate::util::xor_si128_inplace;
+
+
+const BLOCK_LEN: usize = 16;
+const TAG_LEN: usize   = 16;
+
+pub type Aes128Gmac = Gmac<Aes128>;
+pub type Aes256Gmac = Gmac<Aes256>;
+pub type Sm4Gmac = Gmac<Sm4>;
+pub type Camellia128Gmac = Gmac<Camellia128>;
+pub type Camellia256Gmac = Gmac<Camellia256>;
+pub type Aria128Gmac = Gmac<Aria128>;
+pub type Aria256Gmac = Gmac<Aria256>;
+
+
+/// GMAC (GCM with empty plaintext)
+#[derive(Clone)]
+pub struct Gmac<C: BlockCipher + Zeroize> {
+    cipher: C,
+    // NOTE: 初始的 GHash 状态，用于 `reset`。
+    ghash: GHash,
+    mac: GHash,
+    // E(K, J0)
+    base_ectr: [u8; BLOCK_LEN],
+    // NOTE: GHash 在每次 `update` 时都会填充不完整的分组，所以这里需要自己缓存不完整的分组。
+    buf: [u8; BLOCK_LEN],
+    buf_len: usize,
+    alen: u64,
+}
+
+impl<C: BlockCipher + Zeroize> Zeroize for Gmac<C> {
+    fn zeroize(&mut self) {
+        self.cipher.zeroize();
+        self.ghash.zeroize();
+        self.mac.zeroize();
+        self.base_ectr.zeroize();
+        self.buf.zeroize();
+        self.buf_len = 0;
+        self.alen = 0;
+    }
+}
+
+impl<C: BlockCipher + Zeroize> Drop for Gmac<C> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<C: BlockCipher + Zeroize> Gmac<C> {
+    pub const BLOCK_LEN: usize = BLOCK_LEN;
+    pub const NONCE_LEN: usize = 12;
+    pub const TAG_LEN: usize   = TAG_LEN;
+
+    pub const A_MAX: usize = 2305843009213693951; // 2 ** 61
+
+
+    pub fn new(key: &[u8], nonce: &[u8]) -> Self {
+        Self::with_cipher(C::new(key), nonce)
+    }
+
+    pub fn with_cipher(mut cipher: C, nonce: &[u8]) -> Self {
+        // NOTE: GMAC 只可以和 块大小为 16 Bytes 的块密码算法协同工作。
+        assert_eq!(C::BLOCK_LEN, Self::BLOCK_LEN);
+
+        // NOTE: 计算 Ghash 初始状态。
+        let mut h = [0u8; BLOCK_LEN];
+        cipher.encrypt_block(&mut h);
+
+        let ghash = GHash::new(&h);
+        let mac = ghash.clone();
+
+        let mut gmac = Self {
+            cipher, ghash, mac,
+            base_ectr: [0u8; BLOCK_LEN],
+            buf: [0u8; BLOCK_LEN],
+            buf_len: 0,
+            alen: 0,
+        };
+        gmac.reset(nonce);
+        gmac
+    }
+
+    /// 使用新的 Nonce 恢复到初始状态（保留 Key），可以继续计算另外一个消息的 MAC。
+    pub fn reset(&mut self, nonce: &[u8]) {
+        assert_eq!(nonce.len(), Self::NONCE_LEN);
+
+        // J0 = IV || 0^31 || 1
+        let mut counter_block = [0u8; BLOCK_LEN];
+        counter_block[..Self::NONCE_LEN].copy_from_slice(nonce);
+        counter_block[15] = 1;
+        self.cipher.encrypt_block(&mut counter_block);
+
+        self.base_ectr = counter_block;
+        self.mac = self.ghash.clone();
+        self.buf = [0u8; BLOCK_LEN];
+        self.buf_len = 0;
+        self.alen = 0;
+    }
+
+    pub fn update(&mut self, m: &[u8]) {
+        let mut m = m;
+        self.alen += m.len() as u64;
+        debug_assert!(self.alen <= Self::A_MAX as u64);
+
+        if self.buf_len > 0 {
+            let n = core::cmp::min(BLOCK_LEN - self.buf_len, m.len());
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&m[..n]);
+            self.buf_len += n;
+            m = &m[n..];
+
+            if self.buf_len < BLOCK_LEN {
+                return;
+            }
+
+            self.mac.update(&self.buf);
+            self.buf_len = 0;
+        }
+
+        let n = m.len() - m.len() % BLOCK_LEN;
+        self.mac.update(&m[..n]);
+
+        let rem = &m[n..];
+        self.buf[..rem.len()].copy_from_slice(rem);
+        self.buf_len = rem.len();
+    }
+
+    pub fn finalize(self) -> [u8; TAG_LEN] {
+        // NOTE: GHash 会自动填充最后一个不完整的分组。
+        let mut mac = self.mac.clone();
+        mac.update(&self.buf[..self.buf_len]);
+
+        // len(A) || len(C)
+        let mut octets = [0u8; BLOCK_LEN];
+        octets[0..8].copy_from_slice(&(self.alen * 8).to_be_bytes());
+        mac.update(&octets);
+
+        let mut tag = self.base_ectr;
+        xor_si128_inplace(&mut tag, &mac.finalize());
+
+        tag
+    }
+
+    pub fn oneshot(key: &[u8], nonce: &[u8], m: &[u8]) -> [u8; TAG_LEN] {
+        let mut mac = Self::new(key, nonce);
+        mac.update(m);
+        mac.finalize()
+    }
+}
+
+impl_block_cipher_mac!(Gmac);
+
+
+#[test]
+fn test_aes128_gmac() {
+    // Test  Case  1
+    // https://csrc.nist.rip/groups/ST/toolkit/BCM/documents/proposedmodes/gcm/gcm-spec.pdf
+    let key = hex::decode("00000000000000000000000000000000").unwrap();
+    let nonce = hex::decode("000000000000000000000000").unwrap();
+    let tag = Aes128Gmac::oneshot(&key, &nonce, &[]);
+    assert_eq!(&tag[..], &hex::decode("58e2fccefa7e3061367f1d57a4e7455a").unwrap()[..]);
+
+    // gcmEncryptExtIV128.rsp, [Keylen = 128] [IVlen = 96] [PTlen = 0] [AADlen = 128]
+    // https://csrc.nist.gov/Projects/Cryptographic-Algorithm-Validation-Program/CAVP-TESTING-BLOCK-CIPHER-MODES
+    let key = hex::decode("77be63708971c4e240d1cb79e8d77feb").unwrap();
+    let nonce = hex::decode("e0e00f19fed7ba0136a797f3").unwrap();
+    let aad = hex::decode("7a43ec1d9c0a5a78a0b16533a6213cab").unwrap();
+    let tag = Aes128Gmac::oneshot(&key, &nonce, &aad);
+    assert_eq!(&tag[..], &hex::decode("209fcc8d3675ed938e9c7166709dd946").unwrap()[..]);
+
+    let mut mac = Aes128Gmac::new(&key, &nonce);
+    mac.update(&aad);
+    assert_eq!(mac.clone().verify(&tag), true);
+    assert_eq!(mac.clone().verify(&tag[..12]), false);
+    assert_eq!(mac.clone().verify_truncated(&tag[..12], 12), true);
+    assert_eq!(mac.clone().verify_truncated(&tag[..8], 12), false);
+    assert_eq!(mac.verify(&[0u8; 16]), false);
+}
+
+#[test]
+fn test_aes256_gmac_streaming() {
+    let key = (0u8..32).collect::<Vec<u8>>();
+    let nonce = (0u8..12).collect::<Vec<u8>>();
+    let aad = (0u8..100).collect::<Vec<u8>>();
+    let tag = hex::decode("837984b03c0ee37e96472c6425e51037").unwrap();
+
+    assert_eq!(&Aes256Gmac::oneshot(&key, &nonce, &aad)[..], &tag[..]);
+
+    let mut mac = Aes256Gmac::new(&key, &nonce);
+    for i in 0..aad.len() {
+        for j in i..aad.len() {
+            mac.reset(&nonce);
+            mac.update(&aad[..i]);
+            mac.update(&aad[i..j]);
+            mac.update(&aad[j..]);
+            assert_eq!(&mac.clone().finalize()[..], &tag[..]);
+        }
+    }
+}
+
+#[test]
+fn test_gmac_eq_gcm() {
+    use crate::blockmode::{ Sm4Gcm, Camellia128Gcm, Aria256Gcm, };
+
+    let nonce = hex::decode("cafebabefacedbaddecaf888").unwrap();
+    let aad = (0u8..45).collect::<Vec<u8>>();
+
+    let key = [7u8; 16];
+    let mut tag = [0u8; 16];
+    Sm4Gcm::new(&key).encrypt_slice_detached(&nonce, &aad, &mut [], &mut tag);
+    assert_eq!(Sm4Gmac::oneshot(&key, &nonce, &aad), tag);
+
+    Camellia128Gcm::new(&key).encrypt_slice_detached(&nonce, &aad, &mut [], &mut tag);
+    assert_eq!(Camellia128Gmac::oneshot(&key, &nonce, &aad), tag);
+
+    let key = [9u8; 32];
+    Aria256Gcm::new(&key).encrypt_slice_detached(&nonce, &aad, &mut [], &mut tag);
+    assert_eq!(Aria256Gmac::oneshot(&key, &nonce, &aad), tag);
+}
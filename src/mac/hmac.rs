@@ -1,89 +1,182 @@
 // HMAC: Keyed-Hashing for Message Authentication
 // https://tools.ietf.org/html/rfc2104
-use crate::hash::{ Md2, Md4, Md5, Sm3, Sha1, Sha256, Sha384, Sha512, };
+use crate::hash::{ CryptoHasher, BuildCryptoHasher, Array, };
+use crate::hash::{ Md2, Md4, Md5, Ripemd160, Whirlpool, Sm3, Streebog256, Streebog512, Sha1, Sha224, Sha256, Sha384, Sha512, Sha512_224, Sha512_256, };
+use crate::hash::{ Sha3_224, Sha3_256, Sha3_384, Sha3_512, };
+use crate::mem::Zeroize;
+use crate::mem::constant_time_eq;
 
 
 const IPAD: u8 = 0x36;
 const OPAD: u8 = 0x5C;
+// SHA3-224 的分组长度
+const MAX_BLOCK_LEN: usize = 144;
 
 
-macro_rules! impl_hmac_with_hasher {
-    ($name:tt, $hasher:tt) => {
-        #[derive(Clone)]
-        pub struct $name {
-            okey: [u8; Self::BLOCK_LEN],
-            hasher: $hasher,
-        }
-
-        impl $name {
-            pub const BLOCK_LEN: usize = $hasher::BLOCK_LEN;
-            pub const TAG_LEN: usize   = $hasher::DIGEST_LEN;
+pub type HmacMd2 = Hmac<Md2>;
+pub type HmacMd4 = Hmac<Md4>;
+pub type HmacMd5 = Hmac<Md5>;
+pub type HmacRipemd160 = Hmac<Ripemd160>;
+pub type HmacWhirlpool = Hmac<Whirlpool>;
+pub type HmacSm3 = Hmac<Sm3>;
+pub type HmacStreebog256 = Hmac<Streebog256>;
+pub type HmacStreebog512 = Hmac<Streebog512>;
 
+pub type HmacSha1 = Hmac<Sha1>;
 
-            pub fn new(key: &[u8]) -> Self {
-                let mut ikey = [0u8; Self::BLOCK_LEN];
-                let mut okey = [0u8; Self::BLOCK_LEN];
+// SHA-2
+pub type HmacSha224 = Hmac<Sha224>;
+pub type HmacSha256 = Hmac<Sha256>;
+pub type HmacSha384 = Hmac<Sha384>;
+pub type HmacSha512 = Hmac<Sha512>;
+pub type HmacSha512_224 = Hmac<Sha512_224>;
+pub type HmacSha512_256 = Hmac<Sha512_256>;
 
-                if key.len() > Self::BLOCK_LEN {
-                    let hkey = $hasher::oneshot(key);
-                    
-                    ikey[..Self::TAG_LEN].copy_from_slice(&hkey[..Self::TAG_LEN]);
-                    okey[..Self::TAG_LEN].copy_from_slice(&hkey[..Self::TAG_LEN]);
-                } else {
-                    ikey[..key.len()].copy_from_slice(&key);
-                    okey[..key.len()].copy_from_slice(&key);
-                }
+// SHA-3
+pub type HmacSha3_224 = Hmac<Sha3_224>;
+pub type HmacSha3_256 = Hmac<Sha3_256>;
+pub type HmacSha3_384 = Hmac<Sha3_384>;
+pub type HmacSha3_512 = Hmac<Sha3_512>;
+
+
+/// HMAC
+// NOTE: 创建时就计算好 `H(K xor ipad)` 和 `H(K xor opad)` 的压缩函数状态，
+//       `finalize` 和 `reset` 时直接复制状态即可，不需要重新计算 Key 所在的分组。
+#[derive(Clone)]
+pub struct Hmac<H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize> {
+    // H(K xor ipad)
+    istate: H,
+    // H(K xor opad)
+    ostate: H,
+    hasher: H,
+}
 
-                for idx in 0..Self::BLOCK_LEN {
-                    ikey[idx] ^= IPAD;
-                    okey[idx] ^= OPAD;
-                }
+impl<H> Zeroize for Hmac<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize,
+{
+    fn zeroize(&mut self) {
+        self.istate.zeroize();
+        self.ostate.zeroize();
+        self.hasher.zeroize();
+    }
+}
 
-                let mut hasher = $hasher::new();
-                hasher.update(&ikey);
+impl<H> Drop for Hmac<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize,
+{
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
 
-                Self { okey, hasher }
-            }
+impl<H> Hmac<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize + Clone,
+{
+    pub const BLOCK_LEN: usize = H::BLOCK_LEN;
+    pub const TAG_LEN: usize   = H::OUTPUT_LEN;
 
-            pub fn update(&mut self, m: &[u8]) {
-                self.hasher.update(m);
-            }
 
-            pub fn finalize(self) -> [u8; Self::TAG_LEN] {
-                let h1 = self.hasher.finalize();
+    pub fn new(key: &[u8]) -> Self {
+        // NOTE: 泛型参数的关联常量目前不可以用作数组的长度，这里使用最大的分组长度（SHA3-224）。
+        assert!(Self::BLOCK_LEN <= MAX_BLOCK_LEN);
 
-                let mut hasher = $hasher::new();
-                hasher.update(&self.okey);
-                hasher.update(&h1);
+        let mut ikey = [0u8; MAX_BLOCK_LEN];
+        let mut okey = [0u8; MAX_BLOCK_LEN];
 
-                let h2 = hasher.finalize();
+        if key.len() > Self::BLOCK_LEN {
+            let hkey = H::oneshot(key);
+            let hkey = hkey.array_as_slice();
 
-                return h2;
-            }
+            ikey[..hkey.len()].copy_from_slice(hkey);
+            okey[..hkey.len()].copy_from_slice(hkey);
+        } else {
+            ikey[..key.len()].copy_from_slice(key);
+            okey[..key.len()].copy_from_slice(key);
+        }
 
-            pub fn oneshot(key: &[u8], m: &[u8]) -> [u8; Self::TAG_LEN] {
-                let mut mac = Self::new(key);
-                mac.update(m);
-                mac.finalize()
-            }
+        for idx in 0..Self::BLOCK_LEN {
+            ikey[idx] ^= IPAD;
+            okey[idx] ^= OPAD;
         }
+
+        let mut istate = H::build_hasher();
+        istate.write(&ikey[..Self::BLOCK_LEN]);
+
+        let mut ostate = H::build_hasher();
+        ostate.write(&okey[..Self::BLOCK_LEN]);
+
+        ikey.zeroize();
+        okey.zeroize();
+
+        let hasher = istate.clone();
+
+        Self { istate, ostate, hasher }
     }
-}
 
+    /// 恢复到初始状态（保留 Key），可以继续计算另外一个消息的 MAC。
+    pub fn reset(&mut self) {
+        self.hasher = self.istate.clone();
+    }
 
-impl_hmac_with_hasher!(HmacMd2, Md2);
-impl_hmac_with_hasher!(HmacMd4, Md4);
-impl_hmac_with_hasher!(HmacMd5, Md5);
-impl_hmac_with_hasher!(HmacSm3, Sm3);
+    pub fn update(&mut self, m: &[u8]) {
+        self.hasher.write(m);
+    }
 
-impl_hmac_with_hasher!(HmacSha1, Sha1);
+    // NOTE: 传给 `digest` 的副本由 Hasher 自己在 Drop 时清零，`self` 中的状态在返回时清零。
+    pub fn finalize(self) -> H::Output {
+        let h1 = self.hasher.clone().digest();
 
-// SHA-2
-impl_hmac_with_hasher!(HmacSha256, Sha256);
-impl_hmac_with_hasher!(HmacSha384, Sha384);
-impl_hmac_with_hasher!(HmacSha512, Sha512);
+        let mut hasher = self.ostate.clone();
+        hasher.write(h1.array_as_slice());
 
-// SHA-3
+        hasher.digest()
+    }
+
+    /// 验证完整的 Tag。
+    pub fn verify(self, tag: &[u8]) -> bool {
+        let expected = self.finalize();
+        constant_time_eq(tag, expected.array_as_slice())
+    }
+
+    /// 验证截断的 Tag，`tag` 的长度不可以小于调用方指定的 `min_len`。
+    ///
+    /// NOTE: 截断长度由调用方决定，而不是由 `tag` 本身决定，并且不可以小于 80 位，
+    ///       也不可以小于 Tag 长度的一半（RFC 2104 Section 5）。
+    pub fn verify_truncated(self, tag: &[u8], min_len: usize) -> bool {
+        assert!((10..=Self::TAG_LEN).contains(&min_len) && min_len * 2 >= Self::TAG_LEN);
+
+        if !(min_len..=Self::TAG_LEN).contains(&tag.len()) {
+            return false;
+        }
+
+        let expected = self.finalize();
+        constant_time_eq(tag, &expected.array_as_slice()[..tag.len()])
+    }
+
+    pub fn oneshot(key: &[u8], m: &[u8]) -> H::Output {
+        let mut mac = Self::new(key);
+        mac.update(m);
+        mac.finalize()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H> std::io::Write for Hmac<H>
+where
+    H: CryptoHasher + BuildCryptoHasher<Hasher = H> + Zeroize + Clone,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
 
 
 pub fn hmac_md2(key: &[u8], m: &[u8]) -> [u8; HmacMd2::TAG_LEN] {
@@ -98,14 +191,33 @@ pub fn hmac_md5(key: &[u8], m: &[u8]) -> [u8; HmacMd5::TAG_LEN] {
     HmacMd5::oneshot(key, m)
 }
 
+pub fn hmac_ripemd160(key: &[u8], m: &[u8]) -> [u8; HmacRipemd160::TAG_LEN] {
+    HmacRipemd160::oneshot(key, m)
+}
+
+pub fn hmac_whirlpool(key: &[u8], m: &[u8]) -> [u8; HmacWhirlpool::TAG_LEN] {
+    HmacWhirlpool::oneshot(key, m)
+}
+
 pub fn hmac_sm3(key: &[u8], m: &[u8]) -> [u8; HmacSm3::TAG_LEN] {
     HmacSm3::oneshot(key, m)
 }
 
+pub fn hmac_streebog256(key: &[u8], m: &[u8]) -> [u8; HmacStreebog256::TAG_LEN] {
+    HmacStreebog256::oneshot(key, m)
+}
+
+pub fn hmac_streebog512(key: &[u8], m: &[u8]) -> [u8; HmacStreebog512::TAG_LEN] {
+    HmacStreebog512::oneshot(key, m)
+}
+
 pub fn hmac_sha1(key: &[u8], m: &[u8]) -> [u8; HmacSha1::TAG_LEN] {
     HmacSha1::oneshot(key, m)
 }
 
+pub fn hmac_sha224(key: &[u8], m: &[u8]) -> [u8; HmacSha224::TAG_LEN] {
+    HmacSha224::oneshot(key, m)
+}
 pub fn hmac_sha256(key: &[u8], m: &[u8]) -> [u8; HmacSha256::TAG_LEN] {
     HmacSha256::oneshot(key, m)
 }
@@ -115,6 +227,12 @@ pub fn hmac_sha384(key: &[u8], m: &[u8]) -> [u8; HmacSha384::TAG_LEN] {
 pub fn hmac_sha512(key: &[u8], m: &[u8]) -> [u8; HmacSha512::TAG_LEN] {
     HmacSha512::oneshot(key, m)
 }
+pub fn hmac_sha512_224(key: &[u8], m: &[u8]) -> [u8; HmacSha512_224::TAG_LEN] {
+    HmacSha512_224::oneshot(key, m)
+}
+pub fn hmac_sha512_256(key: &[u8], m: &[u8]) -> [u8; HmacSha512_256::TAG_LEN] {
+    HmacSha512_256::oneshot(key, m)
+}
 
 // TODO: hmac-drbg
 // https://github.com/sorpaas/rust-hmac-drbg/blob/master/src/lib.rs
@@ -140,6 +258,47 @@ fn test_hmac_md5() {
         assert_eq!(&hex::encode(&HmacMd5::oneshot(key, data)), result);
     }
 }
+
+#[test]
+fn test_hmac_ripemd160() {
+    // Test Cases for HMAC-RIPEMD160 and HMAC-RIPEMD128
+    // https://tools.ietf.org/html/rfc2286#section-2
+    let b20  = [0x0b; 20];
+    let aa80 = [0xaa; 80];
+
+    let suites: &[(&[u8], &[u8], &str)] = &[
+        (b"key", b"The quick brown fox jumps over the lazy dog", "50278a77d4d7670561ab72e867383aef6ce50b3e"),
+        (&b20, b"Hi There", "24cb4bd67d20fc1a5d2ed7732dcc39377f0a5668"),
+        (b"Jefe", b"what do ya want for nothing?", "dda6c0213a485a9e24f4742064a7f033b43c4069"),
+        (&aa80, b"Test Using Larger Than Block-Size Key - Hash Key First", "6466ca07ac5eac29e1bd523e5ada7605b791fd8b"),
+    ];
+    for (key, data, result) in suites.iter() {
+        assert_eq!(&hex::encode(&HmacRipemd160::oneshot(key, data)), result);
+    }
+}
+
+#[test]
+fn test_hmac_whirlpool() {
+    let key = b"key";
+    let data = b"The quick brown fox jumps over the lazy dog";
+    let result = "7f7192e3a155cb6a8171584ba146882f26821658112dfd2601272db013517a31\
+e573637d146584596f86a884eb0decc9514dde000ecf2476dc5d436a92197527";
+
+    assert_eq!(&hex::encode(&HmacWhirlpool::oneshot(key, data)[..]), result);
+}
+
+#[test]
+fn test_hmac_streebog() {
+    // 4.1.1.  HMAC_GOSTR3411_2012_256 / 4.1.2.  HMAC_GOSTR3411_2012_512
+    // https://tools.ietf.org/html/rfc7836#section-4.1
+    let key = hex::decode("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").unwrap();
+    let data = hex::decode("0126bdb87800af214341456563780100").unwrap();
+
+    assert_eq!(&hex::encode(&HmacStreebog256::oneshot(&key, &data)), "a1aa5f7de402d7b3d323f2991c8d4534013137010a83754fd0af6d7cd4922ed9");
+    assert_eq!(&hex::encode(&HmacStreebog512::oneshot(&key, &data)[..]), "a59bab22ecae19c65fbde6e5f4e9f5d8549d31f037f9df9b905500e171923a77\
+3d5f1530f2ed7e964cb2eedc29e9ad2f3afe93b2814f79f5000ffc0366c251e6");
+}
+
 #[test]
 fn test_hmac_sha1() {
     let key = b"key";
@@ -149,6 +308,21 @@ fn test_hmac_sha1() {
     assert_eq!(&hex::encode(&HmacSha1::oneshot(key, data)), result);
 }
 
+#[test]
+fn test_hmac_sha2_224() {
+    let key = b"key";
+    let data = b"The quick brown fox jumps over the lazy dog";
+    let result = "88ff8b54675d39b8f72322e65ff945c52d96379988ada25639747e69";
+
+    assert_eq!(&hex::encode(&HmacSha224::oneshot(key, data)), result);
+
+    // RFC 4231, 4.2. Test Case 1
+    let key = [0x0b; 20];
+    let data = b"Hi There";
+    let result = "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22";
+
+    assert_eq!(&hex::encode(&HmacSha224::oneshot(&key, data)), result);
+}
 #[test]
 fn test_hmac_sha2_256() {
     let key = b"key";
@@ -172,4 +346,65 @@ fn test_hmac_sha2_512() {
     let result = "b42af09057bac1e2d41708e48a902e09b5ff7f12ab428a4fe86653c73dd248fb82f948a549f7b791a5b41915ee4d1ec3935357e4e2317250d0372afa2ebeeb3a";
 
     assert_eq!(&hex::encode(&HmacSha512::oneshot(key, data)), result);
-}
\ No newline at end of file
+}
+#[test]
+fn test_hmac_sha2_512_224() {
+    let key = b"key";
+    let data = b"The quick brown fox jumps over the lazy dog";
+    let result = "a1afb4f708cb63570639195121785ada3dc615989cc3c73f38e306a3";
+
+    assert_eq!(&hex::encode(&HmacSha512_224::oneshot(key, data)), result);
+}
+#[test]
+fn test_hmac_sha2_512_256() {
+    let key = b"key";
+    let data = b"The quick brown fox jumps over the lazy dog";
+    let result = "7fb65e03577da9151a1016e9c2e514d4d48842857f13927f348588173dca6d89";
+
+    assert_eq!(&hex::encode(&HmacSha512_256::oneshot(key, data)), result);
+}
+
+#[test]
+fn test_hmac_sha3() {
+    let key = b"key";
+    let data = b"The quick brown fox jumps over the lazy dog";
+
+    assert_eq!(&hex::encode(&HmacSha3_224::oneshot(key, data)), "ff6fa8447ce10fb1efdccfe62caf8b640fe46c4fb1007912bf85100f");
+    assert_eq!(&hex::encode(&HmacSha3_256::oneshot(key, data)), "8c6e0683409427f8931711b10ca92a506eb1fafa48fadd66d76126f47ac2c333");
+    assert_eq!(&hex::encode(&HmacSha3_384::oneshot(key, data)[..]), "aa739ad9fcdf9be4a04f06680ade7a1bd1e01a0af64accb04366234cf9f6934a0f8589772f857681fcde8acc256091a2");
+    assert_eq!(&hex::encode(&HmacSha3_512::oneshot(key, data)[..]), "237a35049c40b3ef5ddd960b3dc893d8284953b9a4756611b1b61bffcf53edd9\
+79f93547db714b06ef0a692062c609b70208ab8d4a280ceee40ed8100f293063");
+
+    // Key 长于 BLOCK_LEN（144 Bytes）
+    let key = (0u8..200).collect::<Vec<u8>>();
+    assert_eq!(&hex::encode(&HmacSha3_224::oneshot(&key, data)), "bc92b6b85c768d188bb51c807d9be5f9e7de0f32c1c47ee67761b6db");
+}
+
+#[test]
+fn test_hmac_generic() {
+    use crate::hash::{ Blake2b256, Blake2b512, Blake2s256, };
+
+    let key = b"key";
+    let data = b"The quick brown fox jumps over the lazy dog";
+
+    assert_eq!(&hex::encode(&Hmac::<Blake2s256>::oneshot(key, data)), "f93215bb90d4af4c3061cd932fb169fb8bb8a91d0b4022baea1271e1323cd9a0");
+    assert_eq!(&hex::encode(&Hmac::<Blake2b512>::oneshot(key, data)[..]), "92294f92c0dfb9b00ec9ae8bd94d7e7d8a036b885a499f149dfe2fd2199394aa\
+af6b8894a1730cccb2cd050f9bcf5062a38b51b0dab33207f8ef35ae2c9df51b");
+    assert_eq!(&hex::encode(&Hmac::<Blake2b256>::oneshot(key, data)), "bb3e1cd6f38b5df1cb87983ec29d6116587c1b9bf6e5cd167ac7f2bc741d3817");
+
+    // Reset
+    let tag = HmacSha256::oneshot(key, data);
+    let mut mac = HmacSha256::new(key);
+    mac.update(b"garbage");
+    mac.reset();
+    for chunk in data.chunks(5) {
+        mac.update(chunk);
+    }
+    assert_eq!(mac.clone().finalize(), tag);
+    assert_eq!(mac.clone().verify(&tag), true);
+    assert_eq!(mac.clone().verify(&tag[..16]), false);
+    assert_eq!(mac.clone().verify_truncated(&tag[..16], 16), true);
+    assert_eq!(mac.clone().verify_truncated(&tag[..1], 16), false);
+    assert_eq!(mac.clone().verify(&[]), false);
+    assert_eq!(mac.verify(&[0u8; 32]), false);
+}
@@ -0,0 +1,127 @@
+// KMAC: KECCAK Message Authentication Code
+// https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-185.pdf
+//
+// 4.3 Definition
+// KMAC128(K, X, L, S):
+//   1. newX = bytepad(encode_string(K), 168) || X || right_encode(L).
+//   2. return cSHAKE128(newX, L, "KMAC", S).
+use crate::hash::{ CShake128, CShake256, ShakeReader, };
+
+
+macro_rules! impl_kmac_with_cshake {
+    ($name:tt, $cshake:tt, $tlen:tt) => {
+        #[derive(Clone)]
+        pub struct $name {
+            inner: $cshake,
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize = $cshake::BLOCK_LEN;
+            pub const TAG_LEN: usize   = $tlen;
+
+
+            pub fn new(key: &[u8]) -> Self {
+                Self::with_customization(key, b"")
+            }
+
+            /// `custom` 为用户自定义的字符串（S），用于区分不同的用途（Domain Separation）。
+            pub fn with_customization(key: &[u8], custom: &[u8]) -> Self {
+                let mut inner = $cshake::new(b"KMAC", custom);
+                inner.update_bytepad(&[key]);
+
+                Self { inner }
+            }
+
+            pub fn update(&mut self, m: &[u8]) {
+                self.inner.update(m);
+            }
+
+            pub fn finalize(self) -> [u8; Self::TAG_LEN] {
+                let mut tag = [0u8; Self::TAG_LEN];
+                self.finalize_into(&mut tag);
+                tag
+            }
+
+            /// 输出任意长度的 Tag，注意：L 参与了计算，因此不同长度的 Tag 之间没有前缀关系。
+            pub fn finalize_into(mut self, tag: &mut [u8]) {
+                self.inner.update_right_encode(tag.len() as u64 * 8);
+                self.inner.finalize(tag)
+            }
+
+            // 4.3.1 KMAC with Arbitrary-Length Output (KMACXOF)
+            pub fn finalize_xof(mut self) -> ShakeReader {
+                self.inner.update_right_encode(0);
+                self.inner.finalize_xof()
+            }
+
+            pub fn oneshot(key: &[u8], m: &[u8]) -> [u8; Self::TAG_LEN] {
+                let mut mac = Self::new(key);
+                mac.update(m);
+                mac.finalize()
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl std::io::Write for $name {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.update(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    }
+}
+
+impl_kmac_with_cshake!(Kmac128, CShake128, 32);
+impl_kmac_with_cshake!(Kmac256, CShake256, 64);
+
+
+pub fn kmac128(key: &[u8], m: &[u8]) -> [u8; Kmac128::TAG_LEN] {
+    Kmac128::oneshot(key, m)
+}
+
+pub fn kmac256(key: &[u8], m: &[u8]) -> [u8; Kmac256::TAG_LEN] {
+    Kmac256::oneshot(key, m)
+}
+
+
+#[test]
+fn test_kmac128() {
+    // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let key = hex::decode("404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f").unwrap();
+    let data = hex::decode("00010203").unwrap();
+
+    // Sample #1
+    assert_eq!(&hex::encode(&Kmac128::oneshot(&key, &data)), "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e");
+
+    // Sample #2
+    let mut mac = Kmac128::with_customization(&key, b"My Tagged Application");
+    mac.update(&data);
+    assert_eq!(&hex::encode(&mac.finalize()), "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5");
+
+    // KMACXOF128
+    let mut mac = Kmac128::with_customization(&key, b"My Tagged Application");
+    mac.update(&data);
+    let mut tag = [0u8; 32];
+    mac.finalize_xof().read(&mut tag);
+    assert_eq!(&hex::encode(&tag), "31a44527b4ed9f5c6101d11de6d26f0620aa5c341def41299657fe9df1a3b16c");
+}
+
+#[test]
+fn test_kmac256() {
+    let key = hex::decode("404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f").unwrap();
+    let data = (0u8..200).collect::<Vec<u8>>();
+
+    // Sample #4
+    let mut mac = Kmac256::with_customization(&key, b"My Tagged Application");
+    mac.update(&data);
+    assert_eq!(&hex::encode(&mac.finalize()[..]), "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9\
+70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965");
+
+    // Sample #5
+    assert_eq!(&hex::encode(&kmac256(&key, &data)[..]), "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691\
+589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69");
+}
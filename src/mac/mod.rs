@@ -1,12 +1,89 @@
+// NOTE: 基于分组密码的 MAC（CMAC、CBC-MAC、GMAC、PMAC）的截断 Tag、验证 Tag 以及 `std::io::Write` 的实现都是相同的，
+//       `reset` 只需要把每个消息相关的状态恢复为初始值（Key 相关的状态保持不变）。
+macro_rules! impl_block_cipher_mac {
+    ($name:tt, { $($field:ident: $init:expr),+ $(,)? }) => {
+        impl<C: crate::blockcipher::BlockCipher + crate::mem::Zeroize> $name<C> {
+            /// 恢复到初始状态（保留 Key），可以继续计算另外一个消息的 MAC。
+            pub fn reset(&mut self) {
+                $( self.$field = $init; )+
+            }
+        }
+
+        impl_block_cipher_mac!($name);
+    };
+    ($name:tt) => {
+        impl<C: crate::blockcipher::BlockCipher + crate::mem::Zeroize> $name<C> {
+            /// 截断的 Tag（取 T 最左边的 `tag_out.len()` 个字节）。
+            pub fn finalize_truncated(self, tag_out: &mut [u8]) {
+                assert!(tag_out.len() <= Self::TAG_LEN);
+
+                let tag = self.finalize();
+                tag_out.copy_from_slice(&tag[..tag_out.len()]);
+            }
+
+            /// 验证完整的 Tag。
+            pub fn verify(self, tag: &[u8]) -> bool {
+                let expected = self.finalize();
+                crate::mem::constant_time_eq(tag, &expected)
+            }
+
+            /// 验证截断的 Tag，`tag` 的长度不可以小于调用方指定的 `min_len`。
+            ///
+            /// NOTE: 截断长度由调用方决定，而不是由 `tag` 本身决定，
+            ///       并且不可以小于 64 位（SP 800-38B Appendix A）。
+            pub fn verify_truncated(self, tag: &[u8], min_len: usize) -> bool {
+                assert!((8..=Self::TAG_LEN).contains(&min_len));
+
+                if !(min_len..=Self::TAG_LEN).contains(&tag.len()) {
+                    return false;
+                }
+
+                let expected = self.finalize();
+                crate::mem::constant_time_eq(tag, &expected[..tag.len()])
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl<C: crate::blockcipher::BlockCipher + crate::mem::Zeroize> std::io::Write for $name<C> {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.update(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    };
+}
+
 mod hmac;
+mod kmac;
 mod ghash;
 mod polyval;
 mod poly1305;
+mod cmac;
+mod cbc_mac;
+mod gmac;
+mod pmac;
+mod siphash;
+mod zuc;
+mod umac;
+mod vmac;
 
 pub use self::hmac::*;
+pub use self::kmac::*;
 pub use self::ghash::GHash;
 pub use self::polyval::Polyval;
 pub use self::poly1305::Poly1305;
+pub use self::cmac::*;
+pub use self::cbc_mac::*;
+pub use self::gmac::*;
+pub use self::pmac::*;
+pub use self::siphash::*;
+pub use self::zuc::*;
+pub use self::umac::*;
+pub use self::vmac::*;
 
 
 
@@ -29,6 +106,55 @@ fn bench_poly1305(b: &mut test::Bencher) {
     })
 }
 
+#[cfg(test)]
+#[bench]
+fn bench_poly1305_16k(b: &mut test::Bencher) {
+    let key = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 
+        0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 
+        0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+    ];
+    let message = test::black_box(vec![1u8; 16 * 1024]);
+
+    let mut poly1305 = Poly1305::new(&key);
+
+    b.bytes = message.len() as u64;
+    b.iter(|| {
+        test::black_box(poly1305.update(&message))
+    })
+}
+
+#[cfg(test)]
+#[bench]
+fn bench_aes128_cmac(b: &mut test::Bencher) {
+    let key = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 
+    ];
+    let message = test::black_box([1u8; 4096]);
+
+    b.bytes = message.len() as u64;
+    b.iter(|| {
+        test::black_box(Aes128Cmac::oneshot(&key, &message))
+    })
+}
+
+#[cfg(test)]
+#[bench]
+fn bench_aes128_pmac(b: &mut test::Bencher) {
+    let key = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 
+    ];
+    let message = test::black_box([1u8; 4096]);
+
+    b.bytes = message.len() as u64;
+    b.iter(|| {
+        test::black_box(Aes128Pmac::oneshot(&key, &message))
+    })
+}
+
 #[cfg(test)]
 #[bench]
 fn bench_polyval(b: &mut test::Bencher) {
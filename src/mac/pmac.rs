@@ -0,0 +1,230 @@
+// PMAC: A Parallelizable Message Authentication Code
+// https://web.cs.ucdavis.edu/~rogaway/ocb/pmac.htm
+//
+// Efficient Instantiations of Tweakable Blockciphers and Refinements to Modes OCB and PMAC (PMAC1)
+// https://web.cs.ucdavis.edu/~rogaway/papers/offsets.pdf
+//
+// NOTE: 这里实现的是 PMAC1（Offset 使用 ntz(i) 而不是 Gray Code 计算），和 Miscreant 的 PMAC 相同。
+//       除了最后一个分组之外，每个分组的加密都是相互独立的，所以可以并行计算。
+//       `BlockCipher` 只提供单分组的 `encrypt_block`，这里每次先算好 4 个分组的 Offset，
+//       再连续加密这 4 个相互独立的分组，让 CPU 可以重叠执行它们（例如 AES-NI 的流水线）。
+use crate::blockcipher::{ BlockCipher, Aes128, Aes192, Aes256, };
+use crate::blockmode::{ dbl, inv_dbl, dbl_table, };
+use crate::mem::Zeroize;
+use crate::util::xor_si128_inplace;
+
+
+const BLOCK_LEN: usize = 16;
+const TAG_LEN: usize   = 16;
+
+pub type Aes128Pmac = Pmac<Aes128>;
+pub type Aes192Pmac = Pmac<Aes192>;
+pub type Aes256Pmac = Pmac<Aes256>;
+
+
+/// PMAC (PMAC1)
+#[derive(Clone)]
+pub struct Pmac<C: BlockCipher + Zeroize> {
+    cipher: C,
+    // L(0), L(1), ..., L(31)
+    table: [[u8; BLOCK_LEN]; 32],
+    // L(-1)
+    l_inv: [u8; BLOCK_LEN],
+    offset: [u8; BLOCK_LEN],
+    sum: [u8; BLOCK_LEN],
+    // NOTE: 最后一个分组的处理方式不同，所以完整的分组也要等到下一次输入时才处理。
+    buf: [u8; BLOCK_LEN],
+    buf_len: usize,
+    // 下一个分组的序号（从 1 开始）
+    block_idx: usize,
+}
+
+impl<C: BlockCipher + Zeroize> Zeroize for Pmac<C> {
+    fn zeroize(&mut self) {
+        self.cipher.zeroize();
+        self.table.zeroize();
+        self.l_inv.zeroize();
+        self.offset.zeroize();
+        self.sum.zeroize();
+        self.buf.zeroize();
+        self.buf_len = 0;
+        self.block_idx = 1;
+    }
+}
+
+impl<C: BlockCipher + Zeroize> Drop for Pmac<C> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<C: BlockCipher + Zeroize> Pmac<C> {
+    pub const BLOCK_LEN: usize = BLOCK_LEN;
+    pub const TAG_LEN: usize   = TAG_LEN;
+
+
+    pub fn new(key: &[u8]) -> Self {
+        Self::with_cipher(C::new(key))
+    }
+
+    pub fn with_cipher(mut cipher: C) -> Self {
+        assert_eq!(C::BLOCK_LEN, Self::BLOCK_LEN);
+
+        // L = E(K, 0^n)
+        let mut l = [0u8; BLOCK_LEN];
+        cipher.encrypt_block(&mut l);
+
+        let table = dbl_table(l);
+        let l_inv = inv_dbl(u128::from_be_bytes(l)).to_be_bytes();
+
+        Self {
+            cipher, table, l_inv,
+            offset: [0u8; BLOCK_LEN],
+            sum: [0u8; BLOCK_LEN],
+            buf: [0u8; BLOCK_LEN],
+            buf_len: 0,
+            block_idx: 1,
+        }
+    }
+
+    // Offset = Offset xor L(ntz(i))
+    #[inline]
+    fn next_offset(&mut self) -> [u8; BLOCK_LEN] {
+        let ntz = self.block_idx.trailing_zeros() as usize;
+        if ntz < self.table.len() {
+            xor_si128_inplace(&mut self.offset, &self.table[ntz]);
+        } else {
+            let mut tmp = self.table[31];
+            for _ in 31..ntz {
+                tmp = dbl(u128::from_be_bytes(tmp)).to_be_bytes();
+            }
+            xor_si128_inplace(&mut self.offset, &tmp);
+        }
+
+        self.block_idx += 1;
+        self.offset
+    }
+
+    #[inline]
+    fn process_block(&mut self, block: &[u8]) {
+        // Sum = Sum xor E(K, M[i] xor Offset)
+        let mut tmp = self.next_offset();
+        xor_si128_inplace(&mut tmp, block);
+        self.cipher.encrypt_block(&mut tmp);
+        xor_si128_inplace(&mut self.sum, &tmp);
+    }
+
+    #[inline]
+    fn process_blocks4(&mut self, blocks: &[u8]) {
+        debug_assert_eq!(blocks.len(), BLOCK_LEN * 4);
+
+        let mut tmp = [[0u8; BLOCK_LEN]; 4];
+        for (t, block) in tmp.iter_mut().zip(blocks.chunks_exact(BLOCK_LEN)) {
+            *t = self.next_offset();
+            xor_si128_inplace(t, block);
+        }
+        for t in tmp.iter_mut() {
+            self.cipher.encrypt_block(t);
+        }
+        for t in tmp.iter() {
+            xor_si128_inplace(&mut self.sum, t);
+        }
+    }
+
+    pub fn update(&mut self, m: &[u8]) {
+        let mut m = m;
+        while !m.is_empty() {
+            if self.buf_len == BLOCK_LEN {
+                let block = self.buf;
+                self.process_block(&block);
+                self.buf_len = 0;
+            }
+
+            if self.buf_len == 0 && m.len() > BLOCK_LEN * 4 {
+                let (blocks, rem) = m.split_at(BLOCK_LEN * 4);
+                self.process_blocks4(blocks);
+                m = rem;
+                continue;
+            }
+
+            if self.buf_len == 0 && m.len() > BLOCK_LEN {
+                let (block, rem) = m.split_at(BLOCK_LEN);
+                self.process_block(block);
+                m = rem;
+                continue;
+            }
+
+            let n = core::cmp::min(BLOCK_LEN - self.buf_len, m.len());
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&m[..n]);
+            self.buf_len += n;
+            m = &m[n..];
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; TAG_LEN] {
+        let mut last_block = self.buf;
+        if self.buf_len == BLOCK_LEN {
+            // Sum = Sum xor M[m] xor L(-1)
+            xor_si128_inplace(&mut last_block, &self.l_inv);
+        } else {
+            // Sum = Sum xor pad(M[m])
+            last_block[self.buf_len] = 0x80;
+            for b in last_block[self.buf_len + 1..].iter_mut() {
+                *b = 0;
+            }
+        }
+
+        let mut tag = self.sum;
+        xor_si128_inplace(&mut tag, &last_block);
+        self.cipher.encrypt_block(&mut tag);
+
+        tag
+    }
+
+    pub fn oneshot(key: &[u8], m: &[u8]) -> [u8; TAG_LEN] {
+        let mut mac = Self::new(key);
+        mac.update(m);
+        mac.finalize()
+    }
+}
+
+impl_block_cipher_mac!(Pmac, {
+    offset: [0u8; BLOCK_LEN],
+    sum: [0u8; BLOCK_LEN],
+    buf: [0u8; BLOCK_LEN],
+    buf_len: 0,
+    block_idx: 1,
+});
+
+
+#[test]
+fn test_aes128_pmac() {
+    // PMAC-AES-128 Test Vectors
+    // https://github.com/miscreant/meta/blob/master/vectors/aes_pmac.tjson
+    let key = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let suites: &[(Vec<u8>, &str)] = &[
+        ((0u8.. 0).collect(), "4399572cd6ea5341b8d35876a7098af7"),
+        ((0u8.. 3).collect(), "256ba5193c1b991b4df0c51f388a9e27"),
+        ((0u8..16).collect(), "ebbd822fa458daf6dfdad7c27da76338"),
+        ((0u8..20).collect(), "0412ca150bbf79058d8c75a58c993f55"),
+        ((0u8..32).collect(), "e97ac04e9e5e3399ce5355cd7407bc75"),
+        ((0u8..34).collect(), "5cba7d5eb24f7c86ccc54604e53d5512"),
+        (vec![0u8; 1000],     "c2c9fa1d9985f6f0d2aff915a0e8d910"),
+    ];
+    for (m, tag) in suites.iter() {
+        assert_eq!(&hex::encode(&Aes128Pmac::oneshot(&key, m)), tag);
+
+        // Streaming
+        for i in 0..m.len() {
+            let mut mac = Aes128Pmac::new(&key);
+            mac.update(&m[..i]);
+            mac.update(&m[i..]);
+            assert_eq!(&hex::encode(&mac.finalize()), tag);
+        }
+    }
+
+    let mut mac = Aes128Pmac::new(&key);
+    mac.update(&[0u8; 1000]);
+    assert_eq!(mac.clone().verify_truncated(&hex::decode("c2c9fa1d9985f6f0").unwrap(), 8), true);
+    assert_eq!(mac.verify_truncated(&hex::decode("c2c9fa1d9985f6f1").unwrap(), 8), false);
+}
@@ -0,0 +1,145 @@
+use core::arch::aarch64::*;
+
+// NOTE: 每个 64 位的寄存器保存 2 个分组的同一个 Limb（每个 Lane 32 位），
+//       一次处理 4 个分组（两组寄存器），4 个 Lane 分别独立计算 `H_i = H_i * r^4 + M_{4j+i}`，
+//       最后再分别乘以 r^4、r^3、r^2、r，然后相加。
+
+
+// 分组转换为 26 位的 Limb（包含 hibit）
+#[inline(always)]
+fn limbs(m: &[u8]) -> [u32; 5] {
+    [
+        (u32::from_le_bytes([m[ 0], m[ 1], m[ 2], m[ 3]])     ) & 0x3ffffff,
+        (u32::from_le_bytes([m[ 3], m[ 4], m[ 5], m[ 6]]) >> 2) & 0x3ffffff,
+        (u32::from_le_bytes([m[ 6], m[ 7], m[ 8], m[ 9]]) >> 4) & 0x3ffffff,
+        (u32::from_le_bytes([m[ 9], m[10], m[11], m[12]]) >> 6) & 0x3ffffff,
+        (u32::from_le_bytes([m[12], m[13], m[14], m[15]]) >> 8) | (1 << 24),
+    ]
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn pair(a: u32, b: u32) -> uint32x2_t {
+    let v = [a, b];
+    vld1_u32(v.as_ptr())
+}
+
+// 两个分组
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn load(m: &[u8]) -> [uint32x2_t; 5] {
+    let m0 = limbs(&m[ 0..16]);
+    let m1 = limbs(&m[16..32]);
+
+    let mut out = [vdup_n_u32(0); 5];
+    for i in 0..5 {
+        out[i] = pair(m0[i], m1[i]);
+    }
+    out
+}
+
+// h * r (partial) mod p，`s` 为 `r * 5`。
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn mul(h: &mut [uint32x2_t; 5], r: &[uint32x2_t; 5], s: &[uint32x2_t; 5]) {
+    let d0 = vmlal_u32(vmlal_u32(vmlal_u32(vmlal_u32(vmull_u32(h[0], r[0]), h[1], s[4]), h[2], s[3]), h[3], s[2]), h[4], s[1]);
+    let d1 = vmlal_u32(vmlal_u32(vmlal_u32(vmlal_u32(vmull_u32(h[0], r[1]), h[1], r[0]), h[2], s[4]), h[3], s[3]), h[4], s[2]);
+    let d2 = vmlal_u32(vmlal_u32(vmlal_u32(vmlal_u32(vmull_u32(h[0], r[2]), h[1], r[1]), h[2], r[0]), h[3], s[4]), h[4], s[3]);
+    let d3 = vmlal_u32(vmlal_u32(vmlal_u32(vmlal_u32(vmull_u32(h[0], r[3]), h[1], r[2]), h[2], r[1]), h[3], r[0]), h[4], s[4]);
+    let d4 = vmlal_u32(vmlal_u32(vmlal_u32(vmlal_u32(vmull_u32(h[0], r[4]), h[1], r[3]), h[2], r[2]), h[3], r[1]), h[4], r[0]);
+
+    // NOTE: r^2、r^3、r^4 并不是 clamp 之后的值，`c * 5` 需要使用 64 位计算。
+    let mask = vdupq_n_u64(0x3ffffff);
+
+    let mut c;
+    c = vshrq_n_u64(d0, 26); let h0 = vandq_u64(d0, mask);
+    let d1 = vaddq_u64(d1, c);
+    c = vshrq_n_u64(d1, 26); h[1] = vmovn_u64(vandq_u64(d1, mask));
+    let d2 = vaddq_u64(d2, c);
+    c = vshrq_n_u64(d2, 26); h[2] = vmovn_u64(vandq_u64(d2, mask));
+    let d3 = vaddq_u64(d3, c);
+    c = vshrq_n_u64(d3, 26); h[3] = vmovn_u64(vandq_u64(d3, mask));
+    let d4 = vaddq_u64(d4, c);
+    c = vshrq_n_u64(d4, 26); h[4] = vmovn_u64(vandq_u64(d4, mask));
+
+    // h0 += c * 5
+    let h0 = vaddq_u64(h0, vaddq_u64(c, vshlq_n_u64(c, 2)));
+    let c = vmovn_u64(vshrq_n_u64(h0, 26));
+    h[0] = vmovn_u64(vandq_u64(h0, mask));
+    h[1] = vadd_u32(h[1], c);
+}
+
+/// 一次处理 4 个分组，`m` 的长度必须是 64 的倍数。
+#[target_feature(enable = "neon")]
+pub unsafe fn blocks4(h: &mut [u32; 5], r: &[u32; 5], powers: &[[u32; 5]; 3], m: &[u8]) {
+    debug_assert!(m.len() >= 64 && m.len() % 64 == 0);
+
+    unsafe {
+        let r4 = &powers[2];
+
+        let mut r4v = [vdup_n_u32(0); 5];
+        let mut s4v = [vdup_n_u32(0); 5];
+        for i in 0..5 {
+            r4v[i] = vdup_n_u32(r4[i]);
+            s4v[i] = vdup_n_u32(r4[i] * 5);
+        }
+
+        // Lane 0, 1
+        let mut lo = load(&m[ 0..32]);
+        // Lane 2, 3
+        let mut hi = load(&m[32..64]);
+        for i in 0..5 {
+            // Lane 0: h + M_0
+            lo[i] = vadd_u32(lo[i], pair(h[i], 0));
+        }
+
+        for chunk in m[64..].chunks_exact(64) {
+            mul(&mut lo, &r4v, &s4v);
+            mul(&mut hi, &r4v, &s4v);
+
+            let mlo = load(&chunk[ 0..32]);
+            let mhi = load(&chunk[32..64]);
+            for i in 0..5 {
+                lo[i] = vadd_u32(lo[i], mlo[i]);
+                hi[i] = vadd_u32(hi[i], mhi[i]);
+            }
+        }
+
+        // Lane 0: r^4, Lane 1: r^3, Lane 2: r^2, Lane 3: r
+        let mut rlo = [vdup_n_u32(0); 5];
+        let mut slo = [vdup_n_u32(0); 5];
+        let mut rhi = [vdup_n_u32(0); 5];
+        let mut shi = [vdup_n_u32(0); 5];
+        for i in 0..5 {
+            rlo[i] = pair(powers[2][i], powers[1][i]);
+            slo[i] = pair(powers[2][i] * 5, powers[1][i] * 5);
+            rhi[i] = pair(powers[0][i], r[i]);
+            shi[i] = pair(powers[0][i] * 5, r[i] * 5);
+        }
+        mul(&mut lo, &rlo, &slo);
+        mul(&mut hi, &rhi, &shi);
+
+        // 4 个 Lane 相加
+        let mut d = [0u64; 5];
+        for i in 0..5 {
+            let mut lanes = [0u32; 4];
+            vst1_u32(lanes.as_mut_ptr(), lo[i]);
+            vst1_u32(lanes.as_mut_ptr().add(2), hi[i]);
+            d[i] = lanes[0] as u64 + lanes[1] as u64 + lanes[2] as u64 + lanes[3] as u64;
+        }
+
+        // (partial) h %= p
+        let mut c;
+                    c = d[0] >> 26; d[0] &= 0x3ffffff;
+        d[1] += c; c = d[1] >> 26; d[1] &= 0x3ffffff;
+        d[2] += c; c = d[2] >> 26; d[2] &= 0x3ffffff;
+        d[3] += c; c = d[3] >> 26; d[3] &= 0x3ffffff;
+        d[4] += c; c = d[4] >> 26; d[4] &= 0x3ffffff;
+        d[0] += c * 5; c = d[0] >> 26; d[0] &= 0x3ffffff;
+        d[1] += c;
+
+        for i in 0..5 {
+            h[i] = d[i] as u32;
+        }
+    }
+}
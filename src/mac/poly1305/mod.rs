@@ -21,6 +21,16 @@
 // 参考 bigint: 
 //      https://github.com/sorpaas/etcommon-rs/blob/master/bigint/src/uint/mod.rs
 // 
+// 多分组（AVX2/NEON）的实现参考:
+//      Shay Gueron, Martin Goll, Vectorization of Poly1305 Message Authentication Code
+//      https://eprint.iacr.org/2015/475.pdf
+// 
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+
+use crate::cpu;
 use crate::mem::Zeroize;
 
 
@@ -33,6 +43,9 @@ pub struct Poly1305 {
     pad      : [u32; 4], // s: le_bytes_to_num(key[16..31])
     leftover : usize,
     buffer   : [u8; Poly1305::BLOCK_LEN],
+    // NOTE: r^2, r^3, r^4，多分组（4 Blocks）并行计算时使用。
+    powers   : [[u32; 5]; 3],
+    simd     : bool,
 }
 
 impl Zeroize for Poly1305 {
@@ -42,6 +55,7 @@ impl Zeroize for Poly1305 {
         self.pad.zeroize();
         self.leftover.zeroize();
         self.buffer.zeroize();
+        self.powers.zeroize();
     }
 }
 
@@ -57,6 +71,69 @@ impl core::fmt::Debug for Poly1305 {
     }
 }
 
+// h * r (partial) mod p
+// 
+// NOTE: `r` 的 Limb 必须小于 2^26，`h` 的 Limb 必须小于 2^27，并且 `r` 必须是经过 clamp 之后的值，
+//       否则 `c * 5` 可能会溢出（u32）。
+#[inline(always)]
+fn mul(h: &[u32; 5], r: &[u32; 5]) -> [u32; 5] {
+    let mut h0 = h[0];
+    let mut h1 = h[1];
+    let mut h2 = h[2];
+    let mut h3 = h[3];
+    let mut h4 = h[4];
+
+    let r0 = r[0];
+    let r1 = r[1];
+    let r2 = r[2];
+    let r3 = r[3];
+    let r4 = r[4];
+
+    let s1 = r1 * 5;
+    let s2 = r2 * 5;
+    let s3 = r3 * 5;
+    let s4 = r4 * 5;
+
+    // h *= r
+    let     d0 = (h0 as u64 * r0 as u64) 
+               + (h1 as u64 * s4 as u64) 
+               + (h2 as u64 * s3 as u64) 
+               + (h3 as u64 * s2 as u64) 
+               + (h4 as u64 * s1 as u64);
+    let mut d1 = (h0 as u64 * r1 as u64) 
+               + (h1 as u64 * r0 as u64) 
+               + (h2 as u64 * s4 as u64) 
+               + (h3 as u64 * s3 as u64) 
+               + (h4 as u64 * s2 as u64);
+    let mut d2 = (h0 as u64 * r2 as u64) 
+               + (h1 as u64 * r1 as u64) 
+               + (h2 as u64 * r0 as u64) 
+               + (h3 as u64 * s4 as u64) 
+               + (h4 as u64 * s3 as u64);
+    let mut d3 = (h0 as u64 * r3 as u64) 
+               + (h1 as u64 * r2 as u64) 
+               + (h2 as u64 * r1 as u64) 
+               + (h3 as u64 * r0 as u64) 
+               + (h4 as u64 * s4 as u64);
+    let mut d4 = (h0 as u64 * r4 as u64) 
+               + (h1 as u64 * r3 as u64) 
+               + (h2 as u64 * r2 as u64) 
+               + (h3 as u64 * r1 as u64) 
+               + (h4 as u64 * r0 as u64);
+
+    // (partial) h %= p
+    let mut c : u32;
+                    c = (d0 >> 26) as u32; h0 = d0 as u32 & 0x3ffffff;
+    d1 += c as u64; c = (d1 >> 26) as u32; h1 = d1 as u32 & 0x3ffffff;
+    d2 += c as u64; c = (d2 >> 26) as u32; h2 = d2 as u32 & 0x3ffffff;
+    d3 += c as u64; c = (d3 >> 26) as u32; h3 = d3 as u32 & 0x3ffffff;
+    d4 += c as u64; c = (d4 >> 26) as u32; h4 = d4 as u32 & 0x3ffffff;
+    h0 += c * 5;    c = h0 >> 26; h0 = h0 & 0x3ffffff;
+    h1 += c;
+
+    [h0, h1, h2, h3, h4]
+}
+
 impl Poly1305 {
     pub const KEY_LEN: usize   = 32;
     pub const BLOCK_LEN: usize = 16;
@@ -84,7 +161,20 @@ impl Poly1305 {
         pad[2] = u32::from_le_bytes([key[24], key[25], key[26], key[27]]);
         pad[3] = u32::from_le_bytes([key[28], key[29], key[30], key[31]]);
 
-        Self { r, h, pad, leftover: 0, buffer: [0u8; 16] }
+        // r^2, r^3, r^4
+        let r2 = mul(&r, &r);
+        let r3 = mul(&r2, &r);
+        let r4 = mul(&r3, &r);
+        let powers = [r2, r3, r4];
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        let simd = cpu::has(cpu::AVX2);
+        #[cfg(target_arch = "aarch64")]
+        let simd = cpu::has(cpu::NEON);
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        let simd = false;
+
+        Self { r, h, pad, leftover: 0, buffer: [0u8; 16], powers, simd }
     }
 
     #[inline]
@@ -102,72 +192,40 @@ impl Poly1305 {
         
         let hibit : u32 = if is_last { 0 } else { 1 << 24 };
 
-        let r0 = self.r[0];
-        let r1 = self.r[1];
-        let r2 = self.r[2];
-        let r3 = self.r[3];
-        let r4 = self.r[4];
-
-        let s1 = r1 * 5;
-        let s2 = r2 * 5;
-        let s3 = r3 * 5;
-        let s4 = r4 * 5;
-
-        let mut h0 = self.h[0];
-        let mut h1 = self.h[1];
-        let mut h2 = self.h[2];
-        let mut h3 = self.h[3];
-        let mut h4 = self.h[4];
+        let mut h = self.h;
 
         // h += m
-        h0 += (u32::from_le_bytes([m[ 0], m[ 1], m[ 2], m[ 3]])     ) & 0x3ffffff;
-        h1 += (u32::from_le_bytes([m[ 3], m[ 4], m[ 5], m[ 6]]) >> 2) & 0x3ffffff;
-        h2 += (u32::from_le_bytes([m[ 6], m[ 7], m[ 8], m[ 9]]) >> 4) & 0x3ffffff;
-        h3 += (u32::from_le_bytes([m[ 9], m[10], m[11], m[12]]) >> 6) & 0x3ffffff;
-        h4 += (u32::from_le_bytes([m[12], m[13], m[14], m[15]]) >> 8) | hibit;
+        h[0] += (u32::from_le_bytes([m[ 0], m[ 1], m[ 2], m[ 3]])     ) & 0x3ffffff;
+        h[1] += (u32::from_le_bytes([m[ 3], m[ 4], m[ 5], m[ 6]]) >> 2) & 0x3ffffff;
+        h[2] += (u32::from_le_bytes([m[ 6], m[ 7], m[ 8], m[ 9]]) >> 4) & 0x3ffffff;
+        h[3] += (u32::from_le_bytes([m[ 9], m[10], m[11], m[12]]) >> 6) & 0x3ffffff;
+        h[4] += (u32::from_le_bytes([m[12], m[13], m[14], m[15]]) >> 8) | hibit;
 
         // h *= r
-        let     d0 = (h0 as u64 * r0 as u64) 
-                   + (h1 as u64 * s4 as u64) 
-                   + (h2 as u64 * s3 as u64) 
-                   + (h3 as u64 * s2 as u64) 
-                   + (h4 as u64 * s1 as u64);
-        let mut d1 = (h0 as u64 * r1 as u64) 
-                   + (h1 as u64 * r0 as u64) 
-                   + (h2 as u64 * s4 as u64) 
-                   + (h3 as u64 * s3 as u64) 
-                   + (h4 as u64 * s2 as u64);
-        let mut d2 = (h0 as u64 * r2 as u64) 
-                   + (h1 as u64 * r1 as u64) 
-                   + (h2 as u64 * r0 as u64) 
-                   + (h3 as u64 * s4 as u64) 
-                   + (h4 as u64 * s3 as u64);
-        let mut d3 = (h0 as u64 * r3 as u64) 
-                   + (h1 as u64 * r2 as u64) 
-                   + (h2 as u64 * r1 as u64) 
-                   + (h3 as u64 * r0 as u64) 
-                   + (h4 as u64 * s4 as u64);
-        let mut d4 = (h0 as u64 * r4 as u64) 
-                   + (h1 as u64 * r3 as u64) 
-                   + (h2 as u64 * r2 as u64) 
-                   + (h3 as u64 * r1 as u64) 
-                   + (h4 as u64 * r0 as u64);
-
-        // (partial) h %= p
-        let mut c : u32;
-                        c = (d0 >> 26) as u32; h0 = d0 as u32 & 0x3ffffff;
-        d1 += c as u64; c = (d1 >> 26) as u32; h1 = d1 as u32 & 0x3ffffff;
-        d2 += c as u64; c = (d2 >> 26) as u32; h2 = d2 as u32 & 0x3ffffff;
-        d3 += c as u64; c = (d3 >> 26) as u32; h3 = d3 as u32 & 0x3ffffff;
-        d4 += c as u64; c = (d4 >> 26) as u32; h4 = d4 as u32 & 0x3ffffff;
-        h0 += c * 5;    c = h0 >> 26; h0 = h0 & 0x3ffffff;
-        h1 += c;
+        self.h = mul(&h, &self.r);
+    }
 
-        self.h[0] = h0;
-        self.h[1] = h1;
-        self.h[2] = h2;
-        self.h[3] = h3;
-        self.h[4] = h4;
+    // 一次处理 4 个分组，`m` 的长度必须是 64 的倍数。
+    #[inline]
+    fn blocks4(&mut self, m: &[u8]) {
+        debug_assert_eq!(m.len() % (Self::BLOCK_LEN * 4), 0);
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        unsafe {
+            x86::blocks4(&mut self.h, &self.r, &self.powers, m);
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        unsafe {
+            aarch64::blocks4(&mut self.h, &self.r, &self.powers, m);
+        }
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        {
+            for chunk in m.chunks_exact(Self::BLOCK_LEN) {
+                self.block(chunk, false);
+            }
+        }
     }
 
     pub fn update(&mut self, data: &[u8]) {
@@ -191,6 +249,12 @@ impl Poly1305 {
             self.leftover = 0;
         }
 
+        if self.simd && m.len() >= Self::BLOCK_LEN * 4 {
+            let n = m.len() - m.len() % (Self::BLOCK_LEN * 4);
+            self.blocks4(&m[..n]);
+            m = &m[n..];
+        }
+
         while m.len() >= 16 {
             self.block(&m[0..16], false);
             m = &m[16..];
@@ -277,6 +341,18 @@ impl Poly1305 {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::io::Write for Poly1305 {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 
 #[test]
 fn test_poly1305_donna() {
@@ -323,3 +399,29 @@ fn test_poly1305() {
 }
 
 
+
+#[test]
+fn test_poly1305_simd() {
+    let keys = [
+        (0..32u8).collect::<Vec<u8>>(),
+        vec![0xffu8; 32],
+    ];
+    let data1 = (0..600usize).map(|i| (i * 7 + 3) as u8).collect::<Vec<u8>>();
+    let data2 = vec![0xffu8; 600];
+
+    for key in keys.iter() {
+        for data in [&data1, &data2].iter() {
+            for len in 0..data.len() {
+                let mut mac1 = Poly1305::new(key);
+                let mut mac2 = Poly1305::new(key);
+                mac1.simd = false;
+                mac1.update(&data[..len]);
+
+                let n = len / 3;
+                mac2.update(&data[..n]);
+                mac2.update(&data[n..len]);
+                assert_eq!(mac1.finalize(), mac2.finalize());
+            }
+        }
+    }
+}
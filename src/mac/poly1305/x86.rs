@@ -0,0 +1,141 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+// NOTE: 每个 256 位的寄存器保存 4 个分组的同一个 Limb（每个 Lane 64 位），
+//       4 个 Lane 分别独立计算 `H_i = H_i * r^4 + M_{4j+i}`，
+//       最后再分别乘以 r^4、r^3、r^2、r，然后相加。
+
+
+macro_rules! mul_add {
+    ($($a:expr, $b:expr);+) => {
+        {
+            let mut acc = _mm256_setzero_si256();
+            $(
+                acc = _mm256_add_epi64(acc, _mm256_mul_epu32($a, $b));
+            )+
+            acc
+        }
+    }
+}
+
+// 分组转换为 26 位的 Limb（包含 hibit）
+#[inline(always)]
+fn limbs(m: &[u8]) -> [u32; 5] {
+    [
+        (u32::from_le_bytes([m[ 0], m[ 1], m[ 2], m[ 3]])     ) & 0x3ffffff,
+        (u32::from_le_bytes([m[ 3], m[ 4], m[ 5], m[ 6]]) >> 2) & 0x3ffffff,
+        (u32::from_le_bytes([m[ 6], m[ 7], m[ 8], m[ 9]]) >> 4) & 0x3ffffff,
+        (u32::from_le_bytes([m[ 9], m[10], m[11], m[12]]) >> 6) & 0x3ffffff,
+        (u32::from_le_bytes([m[12], m[13], m[14], m[15]]) >> 8) | (1 << 24),
+    ]
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load(m: &[u8]) -> [__m256i; 5] {
+    let m0 = limbs(&m[ 0..16]);
+    let m1 = limbs(&m[16..32]);
+    let m2 = limbs(&m[32..48]);
+    let m3 = limbs(&m[48..64]);
+
+    let mut out = [_mm256_setzero_si256(); 5];
+    for i in 0..5 {
+        out[i] = _mm256_set_epi64x(m3[i] as i64, m2[i] as i64, m1[i] as i64, m0[i] as i64);
+    }
+    out
+}
+
+// h * r (partial) mod p，`s` 为 `r * 5`。
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mul(h: &mut [__m256i; 5], r: &[__m256i; 5], s: &[__m256i; 5]) {
+    let d0 = mul_add!(h[0], r[0]; h[1], s[4]; h[2], s[3]; h[3], s[2]; h[4], s[1]);
+    let d1 = mul_add!(h[0], r[1]; h[1], r[0]; h[2], s[4]; h[3], s[3]; h[4], s[2]);
+    let d2 = mul_add!(h[0], r[2]; h[1], r[1]; h[2], r[0]; h[3], s[4]; h[4], s[3]);
+    let d3 = mul_add!(h[0], r[3]; h[1], r[2]; h[2], r[1]; h[3], r[0]; h[4], s[4]);
+    let d4 = mul_add!(h[0], r[4]; h[1], r[3]; h[2], r[2]; h[3], r[1]; h[4], r[0]);
+
+    // NOTE: r^2、r^3、r^4 并不是 clamp 之后的值，`c * 5` 需要使用 64 位计算。
+    let mask = _mm256_set1_epi64x(0x3ffffff);
+
+    let mut c;
+    c = _mm256_srli_epi64(d0, 26); h[0] = _mm256_and_si256(d0, mask);
+    let d1 = _mm256_add_epi64(d1, c);
+    c = _mm256_srli_epi64(d1, 26); h[1] = _mm256_and_si256(d1, mask);
+    let d2 = _mm256_add_epi64(d2, c);
+    c = _mm256_srli_epi64(d2, 26); h[2] = _mm256_and_si256(d2, mask);
+    let d3 = _mm256_add_epi64(d3, c);
+    c = _mm256_srli_epi64(d3, 26); h[3] = _mm256_and_si256(d3, mask);
+    let d4 = _mm256_add_epi64(d4, c);
+    c = _mm256_srli_epi64(d4, 26); h[4] = _mm256_and_si256(d4, mask);
+
+    // h0 += c * 5
+    h[0] = _mm256_add_epi64(h[0], _mm256_add_epi64(c, _mm256_slli_epi64(c, 2)));
+    c = _mm256_srli_epi64(h[0], 26); h[0] = _mm256_and_si256(h[0], mask);
+    h[1] = _mm256_add_epi64(h[1], c);
+}
+
+/// 一次处理 4 个分组，`m` 的长度必须是 64 的倍数。
+#[target_feature(enable = "avx2")]
+pub unsafe fn blocks4(h: &mut [u32; 5], r: &[u32; 5], powers: &[[u32; 5]; 3], m: &[u8]) {
+    debug_assert!(m.len() >= 64 && m.len() % 64 == 0);
+
+    unsafe {
+        let r4 = &powers[2];
+
+        let mut r4v = [_mm256_setzero_si256(); 5];
+        let mut s4v = [_mm256_setzero_si256(); 5];
+        for i in 0..5 {
+            r4v[i] = _mm256_set1_epi64x(r4[i] as i64);
+            s4v[i] = _mm256_set1_epi64x(r4[i] as i64 * 5);
+        }
+
+        let mut acc = load(&m[..64]);
+        for i in 0..5 {
+            // Lane 0: h + M_0
+            acc[i] = _mm256_add_epi64(acc[i], _mm256_set_epi64x(0, 0, 0, h[i] as i64));
+        }
+
+        for chunk in m[64..].chunks_exact(64) {
+            mul(&mut acc, &r4v, &s4v);
+
+            let mv = load(chunk);
+            for i in 0..5 {
+                acc[i] = _mm256_add_epi64(acc[i], mv[i]);
+            }
+        }
+
+        // Lane 0: r^4, Lane 1: r^3, Lane 2: r^2, Lane 3: r
+        let mut rv = [_mm256_setzero_si256(); 5];
+        let mut sv = [_mm256_setzero_si256(); 5];
+        for i in 0..5 {
+            rv[i] = _mm256_set_epi64x(r[i] as i64, powers[0][i] as i64, powers[1][i] as i64, powers[2][i] as i64);
+            sv[i] = _mm256_add_epi64(rv[i], _mm256_slli_epi64(rv[i], 2));
+        }
+        mul(&mut acc, &rv, &sv);
+
+        // 4 个 Lane 相加
+        let mut d = [0u64; 5];
+        for i in 0..5 {
+            let mut lanes = [0u64; 4];
+            _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, acc[i]);
+            d[i] = lanes[0] + lanes[1] + lanes[2] + lanes[3];
+        }
+
+        // (partial) h %= p
+        let mut c;
+                    c = d[0] >> 26; d[0] &= 0x3ffffff;
+        d[1] += c; c = d[1] >> 26; d[1] &= 0x3ffffff;
+        d[2] += c; c = d[2] >> 26; d[2] &= 0x3ffffff;
+        d[3] += c; c = d[3] >> 26; d[3] &= 0x3ffffff;
+        d[4] += c; c = d[4] >> 26; d[4] &= 0x3ffffff;
+        d[0] += c * 5; c = d[0] >> 26; d[0] &= 0x3ffffff;
+        d[1] += c;
+
+        for i in 0..5 {
+            h[i] = d[i] as u32;
+        }
+    }
+}
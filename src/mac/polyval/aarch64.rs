@@ -5,7 +5,7 @@ use core::arch::aarch64::*;
 use core::mem::transmute;
 
 
-#[inline]
+#[target_feature(enable = "neon,aes")]
 unsafe fn _mm_clmulepi64_si128(a: uint8x16_t, b: uint8x16_t, imm8: u8) -> uint8x16_t {
     match imm8 {
         0x00 => {
@@ -63,7 +63,8 @@ impl Polyval {
     pub const TAG_LEN: usize   = 16;
 
 
-    pub fn new(k: &[u8]) -> Self {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn new(k: &[u8]) -> Self {
         assert_eq!(k.len(), Self::KEY_LEN);
         
         unsafe {
@@ -74,8 +75,8 @@ impl Polyval {
         }
     }
 
-    #[inline]
-    fn gf_mul(&mut self, block: &[u8]) {
+    #[target_feature(enable = "neon,aes")]
+    unsafe fn gf_mul(&mut self, block: &[u8]) {
         unsafe {
             let mask: uint8x16_t = transmute([1u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 194]);
 
@@ -122,7 +123,8 @@ impl Polyval {
         }
     }
 
-    pub fn update(&mut self, m: &[u8]) {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn update(&mut self, m: &[u8]) {
         let mlen = m.len();
 
         for chunk in m.chunks_exact(Self::BLOCK_LEN) {
@@ -138,7 +140,8 @@ impl Polyval {
         }
     }
 
-    pub fn finalize(self) -> [u8; Self::TAG_LEN] {
+    #[target_feature(enable = "neon,aes")]
+    pub unsafe fn finalize(self) -> [u8; Self::TAG_LEN] {
         unsafe {
             transmute(self.h)
         }
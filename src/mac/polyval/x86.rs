@@ -42,7 +42,8 @@ impl Polyval {
     pub const TAG_LEN: usize   = 16;
 
 
-    pub fn new(k: &[u8]) -> Self {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    pub unsafe fn new(k: &[u8]) -> Self {
         assert_eq!(k.len(), Self::KEY_LEN);
         
         unsafe {
@@ -54,7 +55,8 @@ impl Polyval {
     }
 
     #[inline]
-    fn gf_mul(&mut self, block: &[u8]) {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    unsafe fn gf_mul(&mut self, block: &[u8]) {
         unsafe {
             let a = _mm_loadu_si128(block.as_ptr() as *const __m128i);
             let mask = _mm_setr_epi32(0x1, 0, 0, 0xc2000000u32 as _);
@@ -81,7 +83,8 @@ impl Polyval {
         }
     }
 
-    pub fn update(&mut self, m: &[u8]) {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    pub unsafe fn update(&mut self, m: &[u8]) {
         let mlen = m.len();
 
         for chunk in m.chunks_exact(Self::BLOCK_LEN) {
@@ -97,7 +100,8 @@ impl Polyval {
         }
     }
 
-    pub fn finalize(self) -> [u8; Self::TAG_LEN] {
+    #[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+    pub unsafe fn finalize(self) -> [u8; Self::TAG_LEN] {
         unsafe {
             let mut tag = [0u8; Self::TAG_LEN];
             _mm_storeu_si128(tag.as_mut_ptr() as *mut __m128i, self.h);
@@ -0,0 +1,515 @@
+// UMAC: Message Authentication Code using Universal Hashing
+// https://tools.ietf.org/html/rfc4418
+//
+// UMAC Message Authentication Code Algorithm for the Secure Shell (SSH) Protocol
+// https://tools.ietf.org/html/draft-miller-secsh-umac-01
+//
+// NOTE: 每 4 个字节的 Tag 对应一个独立的 UHASH 计算流（Stream），UMAC-128 有 4 个计算流。
+//       和 GMAC 一样，同一个 Key 下 Nonce 不可以重复使用。
+use crate::blockcipher::Aes128;
+use crate::mem::Zeroize;
+use crate::mem::constant_time_eq;
+
+
+const BLOCK_LEN: usize = 16;
+const MAX_STREAMS: usize = 4;
+
+// L1-HASH 的分块长度
+const L1_CHUNK_LEN: usize = 1024;
+// NH 每次处理 32 个字节
+const NH_BLOCK_LEN: usize = 32;
+// L1 Key 的长度（In words）：1024 + (streams - 1) * 16
+const L1_KEY_WORDS: usize = (L1_CHUNK_LEN + (MAX_STREAMS - 1) * 16) / 4;
+
+// L2-HASH 在处理了 2^14 个 64 位的字之后，改为使用 POLY128。
+const POLY64_MAX_WORDS: u64 = 1 << 14;
+
+const P36: u64   = (1 << 36) - 5;
+const P64: u64   = 0u64.wrapping_sub(59);    // 2^64 - 59
+const P128: u128 = 0u128.wrapping_sub(159);  // 2^128 - 159
+
+const MASK64: u64   = 0x01ffffff01ffffff;
+const MASK128: u128 = 0x01ffffff01ffffff01ffffff01ffffff;
+
+
+// KDF(K, index, numbytes)
+fn kdf(cipher: &Aes128, index: u64, out: &mut [u8]) {
+    for (i, chunk) in out.chunks_mut(BLOCK_LEN).enumerate() {
+        let mut block = [0u8; BLOCK_LEN];
+        block[..8].copy_from_slice(&index.to_be_bytes());
+        block[8..].copy_from_slice(&(i as u64 + 1).to_be_bytes());
+        cipher.encrypt(&mut block);
+
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+}
+
+// POLY(64, 2^64 - 2^32, k, M) 处理一个字
+#[inline]
+fn poly64(k: u64, y: u64, m: u64) -> u64 {
+    // if m >= maxwordrange: y = k * y + marker; m = m - offset
+    let (y, m) = if m >= 0xffffffff00000000 {
+        let y = ((k as u128 * y as u128 + (P64 - 1) as u128) % P64 as u128) as u64;
+        (y, m - 59)
+    } else {
+        (y, m)
+    };
+
+    ((k as u128 * y as u128 + m as u128) % P64 as u128) as u64
+}
+
+// 128 位乘法，返回 (hi, lo)
+#[inline]
+pub(super) fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let (a1, a0) = (a >> 64, a & 0xffffffffffffffff);
+    let (b1, b0) = (b >> 64, b & 0xffffffffffffffff);
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+
+    let mid = (p00 >> 64) + (p01 & 0xffffffffffffffff) + (p10 & 0xffffffffffffffff);
+    let lo = (p00 & 0xffffffffffffffff) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+
+    (hi, lo)
+}
+
+// (hi * 2^128 + lo) mod (2^128 - 159)
+#[inline]
+fn reduce128(mut hi: u128, mut lo: u128) -> u128 {
+    // 2^128 = 159 (mod p)
+    while hi != 0 {
+        let (h, l) = mul_wide(hi, 159);
+        let (l, carry) = l.overflowing_add(lo);
+        hi = h + carry as u128;
+        lo = l;
+    }
+
+    if lo >= P128 { lo - P128 } else { lo }
+}
+
+// POLY(128, 2^128 - 2^96, k, M) 处理一个字
+#[inline]
+fn poly128(k: u128, y: u128, m: u128) -> u128 {
+    let (y, m) = if m >= 0xffffffff000000000000000000000000 {
+        let (hi, lo) = mul_wide(k, y);
+        let (lo, carry) = lo.overflowing_add(P128 - 1);
+        (reduce128(hi + carry as u128, lo), m - 159)
+    } else {
+        (y, m)
+    };
+
+    let (hi, lo) = mul_wide(k, y);
+    let (lo, carry) = lo.overflowing_add(m);
+    reduce128(hi + carry as u128, lo)
+}
+
+
+#[derive(Clone)]
+struct Uhash {
+    streams: usize,
+
+    l1key: [u32; L1_KEY_WORDS],
+    // (k64, k128)
+    l2key: [(u64, u128); MAX_STREAMS],
+    l3key1: [[u64; 8]; MAX_STREAMS],
+    l3key2: [u32; MAX_STREAMS],
+
+    // NH 的累加值
+    nh: [u64; MAX_STREAMS],
+    // POLY64 / POLY128 的状态
+    y64: [u64; MAX_STREAMS],
+    y128: [u128; MAX_STREAMS],
+    // POLY128 是按 128 位处理的，需要缓存前一半
+    pending: [u64; MAX_STREAMS],
+    // 已经输入 L2-HASH 的字数
+    l2_words: u64,
+
+    buf: [u8; NH_BLOCK_LEN],
+    buf_len: usize,
+    // 当前分块已经处理的字节数
+    chunk_len: usize,
+    msg_len: u64,
+}
+
+impl Zeroize for Uhash {
+    fn zeroize(&mut self) {
+        self.l1key.zeroize();
+        for (k64, k128) in self.l2key.iter_mut() {
+            k64.zeroize();
+            k128.zeroize();
+        }
+        self.l3key1.zeroize();
+        self.l3key2.zeroize();
+        self.nh.zeroize();
+        self.y64.zeroize();
+        self.y128.zeroize();
+        self.pending.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+impl Uhash {
+    fn new(cipher: &Aes128, streams: usize) -> Self {
+        let mut l1key = [0u32; L1_KEY_WORDS];
+        let mut tmp = [0u8; L1_KEY_WORDS * 4];
+        let l1key_len = L1_CHUNK_LEN + (streams - 1) * 16;
+        kdf(cipher, 1, &mut tmp[..l1key_len]);
+        for (k, bytes) in l1key.iter_mut().zip(tmp.chunks_exact(4)) {
+            *k = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        tmp.zeroize();
+
+        let mut l2key = [(0u64, 0u128); MAX_STREAMS];
+        let mut tmp = [0u8; MAX_STREAMS * 24];
+        kdf(cipher, 2, &mut tmp[..streams * 24]);
+        for (k, bytes) in l2key.iter_mut().zip(tmp.chunks_exact(24)) {
+            let mut k64 = [0u8; 8];
+            let mut k128 = [0u8; 16];
+            k64.copy_from_slice(&bytes[..8]);
+            k128.copy_from_slice(&bytes[8..]);
+            *k = (u64::from_be_bytes(k64) & MASK64, u128::from_be_bytes(k128) & MASK128);
+        }
+        tmp.zeroize();
+
+        let mut l3key1 = [[0u64; 8]; MAX_STREAMS];
+        let mut tmp = [0u8; MAX_STREAMS * 64];
+        kdf(cipher, 3, &mut tmp[..streams * 64]);
+        for (k, bytes) in l3key1.iter_mut().flatten().zip(tmp.chunks_exact(8)) {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(bytes);
+            *k = u64::from_be_bytes(word) % P36;
+        }
+        tmp.zeroize();
+
+        let mut l3key2 = [0u32; MAX_STREAMS];
+        let mut tmp = [0u8; MAX_STREAMS * 4];
+        kdf(cipher, 4, &mut tmp[..streams * 4]);
+        for (k, bytes) in l3key2.iter_mut().zip(tmp.chunks_exact(4)) {
+            *k = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        tmp.zeroize();
+
+        Self {
+            streams,
+            l1key, l2key, l3key1, l3key2,
+            nh: [0u64; MAX_STREAMS],
+            y64: [1u64; MAX_STREAMS],
+            y128: [1u128; MAX_STREAMS],
+            pending: [0u64; MAX_STREAMS],
+            l2_words: 0,
+            buf: [0u8; NH_BLOCK_LEN],
+            buf_len: 0,
+            chunk_len: 0,
+            msg_len: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.nh = [0u64; MAX_STREAMS];
+        self.y64 = [1u64; MAX_STREAMS];
+        self.y128 = [1u128; MAX_STREAMS];
+        self.pending = [0u64; MAX_STREAMS];
+        self.l2_words = 0;
+        self.buf = [0u8; NH_BLOCK_LEN];
+        self.buf_len = 0;
+        self.chunk_len = 0;
+        self.msg_len = 0;
+    }
+
+    // NH，消息按小端序读取，Key 按大端序读取。
+    #[inline]
+    fn nh_block(&mut self, block: &[u8]) {
+        let mut m = [0u32; 8];
+        for (w, bytes) in m.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        let base = self.chunk_len / 4;
+        for s in 0..self.streams {
+            let k = &self.l1key[base + s * 4..base + s * 4 + 8];
+            let mut y = self.nh[s];
+            for j in 0..4 {
+                let a = m[j].wrapping_add(k[j]) as u64;
+                let b = m[j + 4].wrapping_add(k[j + 4]) as u64;
+                y = y.wrapping_add(a * b);
+            }
+            self.nh[s] = y;
+        }
+
+        self.chunk_len += NH_BLOCK_LEN;
+    }
+
+    // L2-HASH
+    fn l2_update(&mut self, len: u64) {
+        for s in 0..self.streams {
+            let a = self.nh[s].wrapping_add(len);
+            let (k64, k128) = self.l2key[s];
+
+            if self.l2_words < POLY64_MAX_WORDS {
+                self.y64[s] = poly64(k64, self.y64[s], a);
+            } else {
+                if self.l2_words == POLY64_MAX_WORDS {
+                    self.y128[s] = poly128(k128, 1, self.y64[s] as u128);
+                }
+
+                if (self.l2_words - POLY64_MAX_WORDS) % 2 == 0 {
+                    self.pending[s] = a;
+                } else {
+                    let m = ((self.pending[s] as u128) << 64) | a as u128;
+                    self.y128[s] = poly128(k128, self.y128[s], m);
+                }
+            }
+
+            self.nh[s] = 0;
+        }
+
+        self.l2_words += 1;
+        self.chunk_len = 0;
+    }
+
+    fn update(&mut self, m: &[u8]) {
+        let mut m = m;
+        self.msg_len += m.len() as u64;
+
+        while !m.is_empty() {
+            // NOTE: 只有确定后面还有数据时才把完整的分块输入 L2-HASH。
+            if self.chunk_len == L1_CHUNK_LEN {
+                self.l2_update(L1_CHUNK_LEN as u64 * 8);
+            }
+
+            if self.buf_len == 0 && m.len() >= NH_BLOCK_LEN {
+                let (block, rem) = m.split_at(NH_BLOCK_LEN);
+                self.nh_block(block);
+                m = rem;
+                continue;
+            }
+
+            let n = core::cmp::min(NH_BLOCK_LEN - self.buf_len, m.len());
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&m[..n]);
+            self.buf_len += n;
+            m = &m[n..];
+
+            if self.buf_len == NH_BLOCK_LEN {
+                let block = self.buf;
+                self.nh_block(&block);
+                self.buf_len = 0;
+            }
+        }
+    }
+
+    fn finalize(&mut self, out: &mut [u8]) {
+        // 最后一个分块（消息为空时，填充为 32 个字节的 0）
+        let last_len = self.chunk_len + self.buf_len;
+        if self.buf_len > 0 || self.msg_len == 0 {
+            let mut block = self.buf;
+            for b in block[self.buf_len..].iter_mut() {
+                *b = 0;
+            }
+            self.nh_block(&block);
+        }
+
+        let len = last_len as u64 * 8;
+        let short = self.msg_len <= L1_CHUNK_LEN as u64;
+        if !short {
+            self.l2_update(len);
+        }
+
+        for s in 0..self.streams {
+            let b = if short {
+                // B = zeroes(8) || A
+                self.nh[s].wrapping_add(len) as u128
+            } else if self.l2_words <= POLY64_MAX_WORDS {
+                self.y64[s] as u128
+            } else {
+                // M_2 || 0x80，再填充到 16 字节的整数倍。
+                let m = if (self.l2_words - POLY64_MAX_WORDS) % 2 == 1 {
+                    ((self.pending[s] as u128) << 64) | (0x80 << 56)
+                } else {
+                    0x80 << 120
+                };
+                poly128(self.l2key[s].1, self.y128[s], m)
+            };
+
+            // L3-HASH
+            let mut y = 0u64;
+            for (i, k) in self.l3key1[s].iter().enumerate() {
+                let m = (b >> (112 - 16 * i)) as u16;
+                y += m as u64 * k;
+            }
+            let y = ((y % P36) as u32) ^ self.l3key2[s];
+
+            out[s * 4..s * 4 + 4].copy_from_slice(&y.to_be_bytes());
+        }
+    }
+}
+
+
+macro_rules! impl_umac {
+    ($name:tt, $tag_len:tt) => {
+        /// UMAC (UMAC-AES)
+        #[derive(Clone)]
+        pub struct $name {
+            // K' = KDF(K, 0, 16)
+            pdf: Aes128,
+            uhash: Uhash,
+            pad: [u8; $tag_len],
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.pdf.zeroize();
+                self.uhash.zeroize();
+                self.pad.zeroize();
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.zeroize();
+            }
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize       = 16;
+            pub const MIN_NONCE_LEN: usize = 1;
+            pub const MAX_NONCE_LEN: usize = 16;
+            pub const TAG_LEN: usize       = $tag_len;
+
+
+            pub fn new(key: &[u8], nonce: &[u8]) -> Self {
+                assert_eq!(key.len(), Self::KEY_LEN);
+
+                let cipher = Aes128::new(key);
+
+                let mut pdf_key = [0u8; 16];
+                kdf(&cipher, 0, &mut pdf_key);
+                let pdf = Aes128::new(&pdf_key);
+                pdf_key.zeroize();
+
+                let uhash = Uhash::new(&cipher, Self::TAG_LEN / 4);
+
+                let mut umac = Self { pdf, uhash, pad: [0u8; $tag_len] };
+                umac.reset(nonce);
+                umac
+            }
+
+            /// 使用新的 Nonce 恢复到初始状态（保留 Key），可以继续计算另外一个消息的 MAC。
+            pub fn reset(&mut self, nonce: &[u8]) {
+                assert!(nonce.len() >= Self::MIN_NONCE_LEN && nonce.len() <= Self::MAX_NONCE_LEN);
+
+                // PDF(K, Nonce, taglen)
+                let mut block = [0u8; BLOCK_LEN];
+                block[..nonce.len()].copy_from_slice(nonce);
+
+                // NOTE: UMAC-32 和 UMAC-64 只使用 E(K', Nonce) 中的一部分，
+                //       这样连续的 Nonce 可以共享同一次加密的结果（这里没有缓存）。
+                let index = match Self::TAG_LEN {
+                    4 => block[nonce.len() - 1] & 3,
+                    8 => block[nonce.len() - 1] & 1,
+                    _ => 0,
+                };
+                block[nonce.len() - 1] ^= index;
+                self.pdf.encrypt(&mut block);
+
+                let offset = index as usize * Self::TAG_LEN;
+                self.pad.copy_from_slice(&block[offset..offset + Self::TAG_LEN]);
+
+                self.uhash.reset();
+            }
+
+            pub fn update(&mut self, m: &[u8]) {
+                self.uhash.update(m);
+            }
+
+            pub fn finalize(mut self) -> [u8; $tag_len] {
+                let mut tag = [0u8; $tag_len];
+                self.uhash.finalize(&mut tag);
+
+                for (t, p) in tag.iter_mut().zip(self.pad.iter()) {
+                    *t ^= p;
+                }
+
+                tag
+            }
+
+            pub fn verify(self, tag: &[u8]) -> bool {
+                let expected = self.finalize();
+                constant_time_eq(tag, &expected)
+            }
+
+            pub fn oneshot(key: &[u8], nonce: &[u8], m: &[u8]) -> [u8; $tag_len] {
+                let mut mac = Self::new(key, nonce);
+                mac.update(m);
+                mac.finalize()
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl std::io::Write for $name {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.update(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    }
+}
+
+impl_umac!(Umac32, 4);
+impl_umac!(Umac64, 8);
+impl_umac!(Umac96, 12);
+impl_umac!(Umac128, 16);
+
+
+#[test]
+fn test_umac() {
+    // Appendix. Test Vectors
+    // https://tools.ietf.org/html/rfc4418#appendix-A
+    let key = b"abcdefghijklmnop";
+    let nonce = b"bcdefghi";
+
+    let suites: &[(Vec<u8>, &str, &str, &str, &str)] = &[
+        (vec![],                  "113145fb", "6e155fad26900be1", "32fedb100c79ad58f07ff764", "32fedb100c79ad58f07ff7643cc60465"),
+        (vec![b'a'; 3],           "3b91d102", "44b5cb542f220104", "185e4fe905cba7bd85e4c2dc", "185e4fe905cba7bd85e4c2dc3d117d8d"),
+        (vec![b'a'; 1 << 10],     "599b350b", "26bf2f5d60118bd9", "7a54abe04af82d60fb298c3c", "7a54abe04af82d60fb298c3cbd195bcb"),
+        (vec![b'a'; 1 << 15],     "58dcf532", "27f8ef643b0d118d", "7b136bd911e4b734286ef2be", "7b136bd911e4b734286ef2be501f2c3c"),
+        (vec![b'a'; 1 << 20],     "db6364d1", "a4477e87e9f55853", "f8acfa3ac31cfeea047f7b11", "f8acfa3ac31cfeea047f7b115b03bef5"),
+        (vec![b'a'; 1 << 25],     "85ee5cae", "faca46f856e9b45f", "a621c2457c0012e64f3fdae9", "a621c2457c0012e64f3fdae9e7e1870c"),
+        (b"abc".to_vec(),         "abf3a3a0", "d4d7b9f6bd4fbfcf", "883c3d4b97a61976ffcf2323", "883c3d4b97a61976ffcf232308cba5a5"),
+        (b"abc".repeat(500),      "abeb3c8b", "d4cf26ddefd5c01a", "8824a260c53c66a36c9260a6", "8824a260c53c66a36c9260a62cb83aa1"),
+    ];
+    for (m, t32, t64, t96, t128) in suites.iter() {
+        assert_eq!(&hex::encode(&Umac32::oneshot(key, nonce, m)), t32);
+        assert_eq!(&hex::encode(&Umac64::oneshot(key, nonce, m)), t64);
+        assert_eq!(&hex::encode(&Umac96::oneshot(key, nonce, m)), t96);
+        assert_eq!(&hex::encode(&Umac128::oneshot(key, nonce, m)), t128);
+    }
+}
+
+#[test]
+fn test_umac_streaming() {
+    let key = b"abcdefghijklmnop";
+    let nonce = b"bcdefghi";
+    let m = b"abc".repeat(500);
+    let tag = hex::decode("8824a260c53c66a36c9260a62cb83aa1").unwrap();
+
+    let mut mac = Umac128::new(key, b"other nonce");
+    for i in (0..m.len()).step_by(7) {
+        mac.reset(nonce);
+        mac.update(&m[..i]);
+        mac.update(&m[i..]);
+        assert_eq!(mac.clone().verify(&tag), true);
+    }
+
+    let mut mac = Umac128::new(key, nonce);
+    for chunk in m.chunks(33) {
+        mac.update(chunk);
+    }
+    assert_eq!(&mac.finalize()[..], &tag[..]);
+}
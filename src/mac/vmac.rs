@@ -0,0 +1,367 @@
+// VMAC: Message Authentication Code using Universal Hashing
+// https://tools.ietf.org/html/draft-krovetz-vmac-01
+//
+// NOTE: 每 8 个字节的 Tag 对应一个独立的 VHASH 计算流（Stream），VMAC-128 有 2 个计算流，
+//       第二个计算流的 NH Key 相对于第一个向后偏移 16 个字节（Toeplitz）。
+//       和 UMAC 一样，同一个 Key 下 Nonce 不可以重复使用。
+use super::umac::mul_wide;
+use crate::blockcipher::Aes128;
+use crate::mem::Zeroize;
+use crate::mem::constant_time_eq;
+
+
+const BLOCK_LEN: usize = 16;
+const MAX_STREAMS: usize = 2;
+
+// NH 每次处理 128 个字节（16 个 64 位的字）
+const NH_BLOCK_LEN: usize = 128;
+// NH Key 的长度（In words）：128 + (streams - 1) * 16
+const NH_KEY_WORDS: usize = (NH_BLOCK_LEN + (MAX_STREAMS - 1) * 16) / 8;
+
+const P127: u128 = (1 << 127) - 1;
+const P64: u64   = 0u64.wrapping_sub(257);  // 2^64 - 257
+
+const MPOLY: u128 = 0x1fffffff1fffffff1fffffff1fffffff;
+const M126: u128  = (1 << 126) - 1;
+
+
+// (a * b) mod (2^127 - 1)，a 和 b 都小于 2^127
+#[inline]
+fn mul_mod_p127(a: u128, b: u128) -> u128 {
+    // 2^127 = 1 (mod p)
+    let (hi, lo) = mul_wide(a, b);
+    let y = ((hi << 1) | (lo >> 127)) + (lo & P127);
+    let y = (y >> 127) + (y & P127);
+    if y >= P127 { y - P127 } else { y }
+}
+
+#[inline]
+fn add_mod_p127(a: u128, b: u128) -> u128 {
+    let y = a + b;
+    let y = (y >> 127) + (y & P127);
+    if y >= P127 { y - P127 } else { y }
+}
+
+
+#[derive(Clone)]
+struct Vhash {
+    streams: usize,
+
+    nhkey: [u64; NH_KEY_WORDS],
+    polykey: [u128; MAX_STREAMS],
+    l3key: [(u64, u64); MAX_STREAMS],
+
+    // L2-HASH（POLY）的状态
+    y: [u128; MAX_STREAMS],
+
+    buf: [u8; NH_BLOCK_LEN],
+    buf_len: usize,
+    msg_len: u64,
+}
+
+impl Zeroize for Vhash {
+    fn zeroize(&mut self) {
+        self.nhkey.zeroize();
+        self.polykey.zeroize();
+        for (k1, k2) in self.l3key.iter_mut() {
+            k1.zeroize();
+            k2.zeroize();
+        }
+        self.y.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+impl Vhash {
+    fn new(cipher: &Aes128, streams: usize) -> Self {
+        // NH Key: E(K, 0x80 || 0^112 || i), ...
+        let mut nhkey = [0u64; NH_KEY_WORDS];
+        for (i, k) in nhkey.chunks_exact_mut(2).enumerate() {
+            let mut block = [0u8; BLOCK_LEN];
+            block[0] = 0x80;
+            block[15] = i as u8;
+            cipher.encrypt(&mut block);
+
+            let w = u128::from_be_bytes(block);
+            k[0] = (w >> 64) as u64;
+            k[1] = w as u64;
+            block.zeroize();
+        }
+
+        // Poly Key: E(K, 0xc0 || 0^112 || i) and mpoly
+        let mut polykey = [0u128; MAX_STREAMS];
+        for (i, k) in polykey.iter_mut().enumerate().take(streams) {
+            let mut block = [0u8; BLOCK_LEN];
+            block[0] = 0xc0;
+            block[15] = i as u8;
+            cipher.encrypt(&mut block);
+
+            *k = u128::from_be_bytes(block) & MPOLY;
+            block.zeroize();
+        }
+
+        // L3 Key: E(K, 0xe0 || 0^112 || i)，两个 64 位的字都必须小于 p64，否则继续使用下一个计数器。
+        let mut l3key = [(0u64, 0u64); MAX_STREAMS];
+        let mut counter = 0u8;
+        for k in l3key.iter_mut().take(streams) {
+            loop {
+                let mut block = [0u8; BLOCK_LEN];
+                block[0] = 0xe0;
+                block[15] = counter;
+                cipher.encrypt(&mut block);
+                counter += 1;
+
+                let w = u128::from_be_bytes(block);
+                block.zeroize();
+
+                *k = ((w >> 64) as u64, w as u64);
+                if k.0 < P64 && k.1 < P64 {
+                    break;
+                }
+            }
+        }
+
+        Self {
+            streams,
+            nhkey, polykey, l3key,
+            y: [1u128; MAX_STREAMS],
+            buf: [0u8; NH_BLOCK_LEN],
+            buf_len: 0,
+            msg_len: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.y = [1u128; MAX_STREAMS];
+        self.buf = [0u8; NH_BLOCK_LEN];
+        self.buf_len = 0;
+        self.msg_len = 0;
+    }
+
+    // NH + L2-HASH，消息按小端序读取，`block` 的长度为 16 的整数倍（最后一个分组以零填充）。
+    #[inline]
+    fn nh_block(&mut self, block: &[u8]) {
+        debug_assert!(block.len() <= NH_BLOCK_LEN && block.len() % 16 == 0);
+
+        let mut m = [0u64; NH_BLOCK_LEN / 8];
+        for (w, bytes) in m.iter_mut().zip(block.chunks_exact(8)) {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(bytes);
+            *w = u64::from_le_bytes(word);
+        }
+        let words = block.len() / 8;
+
+        for s in 0..self.streams {
+            let k = &self.nhkey[s * 2..s * 2 + words];
+            let mut nh = 0u128;
+            for j in (0..words).step_by(2) {
+                let a = m[j].wrapping_add(k[j]) as u128;
+                let b = m[j + 1].wrapping_add(k[j + 1]) as u128;
+                nh = nh.wrapping_add(a * b);
+            }
+
+            // y = y * k + (NH mod 2^126) mod p127
+            self.y[s] = add_mod_p127(mul_mod_p127(self.y[s], self.polykey[s]), nh & M126);
+        }
+    }
+
+    fn update(&mut self, m: &[u8]) {
+        let mut m = m;
+        self.msg_len += m.len() as u64;
+
+        while !m.is_empty() {
+            if self.buf_len == 0 && m.len() >= NH_BLOCK_LEN {
+                let (block, rem) = m.split_at(NH_BLOCK_LEN);
+                self.nh_block(block);
+                m = rem;
+                continue;
+            }
+
+            let n = core::cmp::min(NH_BLOCK_LEN - self.buf_len, m.len());
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&m[..n]);
+            self.buf_len += n;
+            m = &m[n..];
+
+            if self.buf_len == NH_BLOCK_LEN {
+                let block = self.buf;
+                self.nh_block(&block);
+                self.buf_len = 0;
+            }
+        }
+    }
+
+    fn finalize(&mut self, out: &mut [u64]) {
+        // 最后一个不完整的分组填充到 16 字节的整数倍（消息为空时，NH 的结果为 0）。
+        let rem = self.buf_len;
+        if rem > 0 || self.msg_len == 0 {
+            let padded_len = (rem + 15) & !15;
+            let mut block = self.buf;
+            for b in block[rem..padded_len].iter_mut() {
+                *b = 0;
+            }
+            self.nh_block(&block[..padded_len]);
+        }
+
+        for (s, h) in out.iter_mut().enumerate().take(self.streams) {
+            // y = y + ((bitlength mod 1024) * 2^64) mod p127
+            let y = add_mod_p127(self.y[s], (rem as u128 * 8) << 64);
+
+            // L3-HASH
+            let (k1, k2) = self.l3key[s];
+            let y1 = (y / 0xffffffff00000000) as u64;
+            let y2 = (y % 0xffffffff00000000) as u64;
+            let a = (y1 as u128 + k1 as u128) % P64 as u128;
+            let b = (y2 as u128 + k2 as u128) % P64 as u128;
+
+            *h = (a * b % P64 as u128) as u64;
+        }
+    }
+}
+
+
+macro_rules! impl_vmac {
+    ($name:tt, $tag_len:tt) => {
+        /// VMAC (VMAC-AES)
+        #[derive(Clone)]
+        pub struct $name {
+            cipher: Aes128,
+            vhash: Vhash,
+            pad: [u64; MAX_STREAMS],
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.cipher.zeroize();
+                self.vhash.zeroize();
+                self.pad.zeroize();
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.zeroize();
+            }
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize       = 16;
+            pub const MIN_NONCE_LEN: usize = 1;
+            pub const MAX_NONCE_LEN: usize = 16;
+            pub const TAG_LEN: usize       = $tag_len;
+
+
+            pub fn new(key: &[u8], nonce: &[u8]) -> Self {
+                assert_eq!(key.len(), Self::KEY_LEN);
+
+                let cipher = Aes128::new(key);
+                let vhash = Vhash::new(&cipher, Self::TAG_LEN / 8);
+
+                let mut vmac = Self { cipher, vhash, pad: [0u64; MAX_STREAMS] };
+                vmac.reset(nonce);
+                vmac
+            }
+
+            /// 使用新的 Nonce 恢复到初始状态（保留 Key），可以继续计算另外一个消息的 MAC。
+            pub fn reset(&mut self, nonce: &[u8]) {
+                assert!(nonce.len() >= Self::MIN_NONCE_LEN && nonce.len() <= Self::MAX_NONCE_LEN);
+
+                // NOTE: 和 UMAC 不同，Nonce 在前面填充 0。
+                let mut block = [0u8; BLOCK_LEN];
+                block[BLOCK_LEN - nonce.len()..].copy_from_slice(nonce);
+
+                // NOTE: VMAC-64 只使用 E(K, Nonce) 中的一半，由 Nonce 的最后一位决定。
+                let index = if Self::TAG_LEN == 8 { block[BLOCK_LEN - 1] & 1 } else { 0 };
+                block[BLOCK_LEN - 1] ^= index;
+                self.cipher.encrypt(&mut block);
+
+                let pad = u128::from_be_bytes(block);
+                block.zeroize();
+                self.pad = if index == 0 { [(pad >> 64) as u64, pad as u64] } else { [pad as u64, 0] };
+
+                self.vhash.reset();
+            }
+
+            pub fn update(&mut self, m: &[u8]) {
+                self.vhash.update(m);
+            }
+
+            pub fn finalize(mut self) -> [u8; $tag_len] {
+                let mut hash = [0u64; MAX_STREAMS];
+                self.vhash.finalize(&mut hash);
+
+                let mut tag = [0u8; $tag_len];
+                for (i, t) in tag.chunks_exact_mut(8).enumerate() {
+                    t.copy_from_slice(&hash[i].wrapping_add(self.pad[i]).to_be_bytes());
+                }
+
+                tag
+            }
+
+            pub fn verify(self, tag: &[u8]) -> bool {
+                let expected = self.finalize();
+                constant_time_eq(tag, &expected)
+            }
+
+            pub fn oneshot(key: &[u8], nonce: &[u8], m: &[u8]) -> [u8; $tag_len] {
+                let mut mac = Self::new(key, nonce);
+                mac.update(m);
+                mac.finalize()
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl std::io::Write for $name {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.update(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    }
+}
+
+impl_vmac!(Vmac64, 8);
+impl_vmac!(Vmac128, 16);
+
+
+#[test]
+fn test_vmac() {
+    // Test Vectors
+    // https://tools.ietf.org/html/draft-krovetz-vmac-01
+    let key = b"abcdefghijklmnop";
+    let nonce = b"bcdefghi";
+
+    let suites: &[(Vec<u8>, &str, &str)] = &[
+        (vec![],                  "2576be1c56d8b81b", "472766c70f74ed23481d6d7de4e80dac"),
+        (b"abc".to_vec(),         "2d376cf5b1813ce5", "4ee815a06a1d71edd36fc75d51188a42"),
+        (b"abc".repeat(16),       "e8421f61d573d298", "09f2c80c8e1007a0c12fae19fe4504ae"),
+        (b"abc".repeat(100),      "4492df6c5cac1bbe", "66438817154850c61d8a412164803bcb"),
+        (b"abc".repeat(1000000),  "09ba597dd7601113", "2b6b02288ffc461b75485de893c629dc"),
+    ];
+    for (m, t64, t128) in suites.iter() {
+        assert_eq!(&hex::encode(&Vmac64::oneshot(key, nonce, m)), t64);
+        assert_eq!(&hex::encode(&Vmac128::oneshot(key, nonce, m)), t128);
+    }
+}
+
+#[test]
+fn test_vmac_streaming() {
+    let key = b"abcdefghijklmnop";
+    let nonce = b"bcdefghi";
+    let m = b"abc".repeat(100);
+    let tag = hex::decode("66438817154850c61d8a412164803bcb").unwrap();
+
+    let mut mac = Vmac128::new(key, b"other nonce");
+    for i in (0..m.len()).step_by(7) {
+        mac.reset(nonce);
+        mac.update(&m[..i]);
+        mac.update(&m[i..]);
+        assert_eq!(mac.clone().verify(&tag), true);
+    }
+
+    // 相邻的两个 Nonce 共享同一次 AES 加密的结果
+    assert_eq!(&hex::encode(&Vmac64::oneshot(key, b"bcdefghh", &b"abc".repeat(7))), "09ea2bee331533a5");
+}
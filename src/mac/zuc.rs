@@ -0,0 +1,307 @@
+// GMT 0001.3-2012 祖冲之序列密码算法第3部分：基于祖冲之算法的完整性算法
+// https://github.com/guanzhi/GM-Standards/blob/master/GMT%E6%AD%A3%E5%BC%8F%E6%A0%87%E5%87%86/GMT%200001.3-2012%20%E7%A5%96%E5%86%B2%E4%B9%8B%E5%BA%8F%E5%88%97%E5%AF%86%E7%A0%81%E7%AE%97%E6%B3%95%E7%AC%AC3%E9%83%A8%E5%88%86%EF%BC%9A%E5%9F%BA%E4%BA%8E%E7%A5%96%E5%86%B2%E4%B9%8B%E7%AE%97%E6%B3%95%E7%9A%84%E5%AE%8C%E6%95%B4%E6%80%A7%E7%AE%97%E6%B3%95.pdf
+//
+// Specification of the 3GPP Confidentiality and Integrity Algorithms 128-EEA3 & 128-EIA3
+// https://www.gsma.com/security/wp-content/uploads/2019/05/EEA3_EIA3_specification_v1_8.pdf
+//
+// ZUC-256 Stream Cipher and Its Integrity Protection Algorithm
+// http://www.is.cas.cn/ztzl2016/zouchongzhi/201801/W020180126529970733243.pdf
+//
+// NOTE: 消息的长度以位（Bit）为单位，`update` 只处理完整的字节，
+//       最后一个不完整的字节需要使用 `update_bits`，之后不可以再输入数据。
+use crate::streamcipher::{ Zuc128, Zuc256, };
+use crate::mem::Zeroize;
+use crate::mem::constant_time_eq;
+
+
+trait Keystream {
+    fn generate(&mut self) -> u32;
+}
+
+impl Keystream for Zuc128 {
+    fn generate(&mut self) -> u32 {
+        self.generate()
+    }
+}
+
+impl Keystream for Zuc256 {
+    fn generate(&mut self) -> u32 {
+        self.generate()
+    }
+}
+
+
+// NOTE: 对于消息的第 i 位，使用密钥流从第 i 位开始的 t 位作为 Key（W_i），
+//       每处理一位消息，窗口向后滑动一位。
+#[derive(Clone)]
+struct ZucMacState<Z: Keystream> {
+    zuc: Z,
+    tag: u128,
+    // W_i
+    win: u128,
+    mask: u128,
+    // 还未进入窗口的密钥字
+    next: u32,
+    next_bits: u32,
+    // 已经输入了不完整的字节
+    finished: bool,
+}
+
+impl<Z: Keystream> Zeroize for ZucMacState<Z> {
+    fn zeroize(&mut self) {
+        self.tag.zeroize();
+        self.win.zeroize();
+        self.next.zeroize();
+        self.next_bits = 0;
+    }
+}
+
+impl<Z: Keystream> Drop for ZucMacState<Z> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<Z: Keystream> ZucMacState<Z> {
+    fn new(zuc: Z, tag: u128, win: u128, tag_bits: u32) -> Self {
+        let mask = if tag_bits == 128 { u128::MAX } else { (1u128 << tag_bits) - 1 };
+
+        Self { zuc, tag, win, mask, next: 0, next_bits: 0, finished: false }
+    }
+
+    #[inline]
+    fn slide(&mut self) {
+        if self.next_bits == 0 {
+            self.next = self.zuc.generate();
+            self.next_bits = 32;
+        }
+
+        let bit = self.next >> 31;
+        self.next <<= 1;
+        self.next_bits -= 1;
+
+        self.win = ((self.win << 1) | bit as u128) & self.mask;
+    }
+
+    fn update_bits(&mut self, m: &[u8], bit_len: usize) {
+        assert!(bit_len <= m.len() * 8);
+        assert!(!self.finished);
+
+        for i in 0..bit_len {
+            let bit = (m[i / 8] >> (7 - i % 8)) & 1;
+            // if bit == 1 { T = T xor W_i }
+            self.tag ^= self.win & (bit as u128).wrapping_neg();
+            self.slide();
+        }
+
+        if bit_len % 8 != 0 {
+            self.finished = true;
+        }
+    }
+}
+
+
+/// 128-EIA3（ZUC-128 MAC）
+#[derive(Clone)]
+pub struct Zuc128Mac {
+    state: ZucMacState<Zuc128>,
+}
+
+impl Zuc128Mac {
+    pub const KEY_LEN: usize = Zuc128::KEY_LEN;
+    pub const TAG_LEN: usize = 4;
+
+
+    /// `bearer` 为 5 位，`direction` 为 1 位。
+    pub fn new(key: &[u8], count: u32, bearer: u8, direction: u8) -> Self {
+        debug_assert!(bearer < 32);
+        debug_assert!(direction < 2);
+
+        let count = count.to_be_bytes();
+        let mut iv = [0u8; 16];
+        iv[0..4].copy_from_slice(&count);
+        iv[4] = bearer << 3;
+        iv[8..12].copy_from_slice(&count);
+        iv[8] ^= direction << 7;
+        iv[12] = bearer << 3;
+        iv[14] = direction << 7;
+
+        let mut zuc = Zuc128::new(key, &iv);
+        // NOTE: 第一个密钥字作为 W_0，最后一个密钥字在 `finalize` 时再读取。
+        let z0 = zuc.generate();
+
+        Self { state: ZucMacState::new(zuc, 0, z0 as u128, 32) }
+    }
+
+    pub fn update(&mut self, m: &[u8]) {
+        self.state.update_bits(m, m.len() * 8);
+    }
+
+    /// 输入 `m` 的前 `bit_len` 位。
+    pub fn update_bits(&mut self, m: &[u8], bit_len: usize) {
+        self.state.update_bits(m, bit_len);
+    }
+
+    pub fn finalize(mut self) -> [u8; 4] {
+        // T = T xor W_LENGTH xor z_{L-1}，L = ceil(LENGTH / 32) + 2
+        let state = &mut self.state;
+        let last = if state.next_bits == 32 { state.next } else { state.zuc.generate() };
+        let tag = (state.tag ^ state.win) as u32 ^ last;
+
+        tag.to_be_bytes()
+    }
+
+    pub fn verify(self, tag: &[u8]) -> bool {
+        let expected = self.finalize();
+        constant_time_eq(tag, &expected)
+    }
+
+    pub fn oneshot(key: &[u8], count: u32, bearer: u8, direction: u8, m: &[u8]) -> [u8; 4] {
+        let mut mac = Self::new(key, count, bearer, direction);
+        mac.update(m);
+        mac.finalize()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for Zuc128Mac {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+
+macro_rules! impl_zuc256_mac {
+    ($name:tt, $tag_len:tt) => {
+        /// ZUC-256 MAC
+        #[derive(Clone)]
+        pub struct $name {
+            state: ZucMacState<Zuc256>,
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize = Zuc256::KEY_LEN;
+            pub const IV_LEN: usize  = Zuc256::IV_LEN;
+            pub const TAG_LEN: usize = $tag_len;
+
+
+            pub fn new(key: &[u8], iv: &[u8]) -> Self {
+                let tag_bits = Self::TAG_LEN as u32 * 8;
+
+                let mut zuc = Zuc256::with_tag_len(key, iv, Self::TAG_LEN);
+                // 前 t 位作为 Tag 的初始值，之后的 t 位作为 W_0
+                let mut tag = 0u128;
+                let mut win = 0u128;
+                for _ in 0..tag_bits / 32 {
+                    tag = (tag << 32) | zuc.generate() as u128;
+                }
+                for _ in 0..tag_bits / 32 {
+                    win = (win << 32) | zuc.generate() as u128;
+                }
+
+                Self { state: ZucMacState::new(zuc, tag, win, tag_bits) }
+            }
+
+            pub fn update(&mut self, m: &[u8]) {
+                self.state.update_bits(m, m.len() * 8);
+            }
+
+            /// 输入 `m` 的前 `bit_len` 位。
+            pub fn update_bits(&mut self, m: &[u8], bit_len: usize) {
+                self.state.update_bits(m, bit_len);
+            }
+
+            pub fn finalize(self) -> [u8; $tag_len] {
+                // T = T xor W_LENGTH
+                let tag = (self.state.tag ^ self.state.win).to_be_bytes();
+
+                let mut out = [0u8; $tag_len];
+                out.copy_from_slice(&tag[16 - $tag_len..]);
+                out
+            }
+
+            pub fn verify(self, tag: &[u8]) -> bool {
+                let expected = self.finalize();
+                constant_time_eq(tag, &expected)
+            }
+
+            pub fn oneshot(key: &[u8], iv: &[u8], m: &[u8]) -> [u8; $tag_len] {
+                let mut mac = Self::new(key, iv);
+                mac.update(m);
+                mac.finalize()
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl std::io::Write for $name {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.update(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    }
+}
+
+impl_zuc256_mac!(Zuc256Mac32, 4);
+impl_zuc256_mac!(Zuc256Mac64, 8);
+impl_zuc256_mac!(Zuc256Mac128, 16);
+
+
+#[test]
+fn test_zuc128_mac() {
+    // 128-EIA3 Test Set 1 ~ 3
+    // https://www.gsma.com/security/wp-content/uploads/2019/05/EEA3_EIA3_specification_v1_8.pdf
+    let key = hex::decode("00000000000000000000000000000000").unwrap();
+    let mut mac = Zuc128Mac::new(&key, 0, 0, 0);
+    mac.update_bits(&[0u8; 4], 1);
+    assert_eq!(&mac.finalize(), &hex::decode("c8a9595e").unwrap()[..]);
+
+    let key = hex::decode("47054125561eb2dda94059da05097850").unwrap();
+    let mut mac = Zuc128Mac::new(&key, 0x561eb2dd, 0x14, 0);
+    mac.update_bits(&[0u8; 12], 90);
+    assert_eq!(&mac.finalize(), &hex::decode("6719a088").unwrap()[..]);
+
+    let key = hex::decode("c9e6cec4607c72db000aefa88385ab0a").unwrap();
+    let m = hex::decode("983b41d47d780c9e1ad11d7eb70391b1de0b35da2dc62f83e7b78d6306ca0ea0\
+7e941b7be91348f9fcb170e2217fecd97f9f68adb16e5d7d21e569d280ed775c\
+ebde3f4093c5388100000000").unwrap();
+    let mut mac = Zuc128Mac::new(&key, 0xa94059da, 0x0a, 1);
+    mac.update(&m[..72]);
+    mac.update_bits(&m[72..], 1);
+    let tag = hex::decode("fae8ff0b").unwrap();
+    assert_eq!(mac.clone().verify(&tag), true);
+    assert_eq!(&mac.finalize(), &tag[..]);
+}
+
+#[test]
+fn test_zuc256_mac() {
+    // ZUC-256 Stream Cipher and Its Integrity Protection Algorithm, Test Vectors
+    let suites: &[(u8, u8, &[u8], &str, &str, &str)] = &[
+        (0x00, 0x00, &[0x00; 50],  "9b972a74", "673e54990034d38c", "d85e54bbcb9600967084c952a1654b26"),
+        (0x00, 0x00, &[0x11; 500], "8754f5cf", "130dc225e72240cc", "df1e8307b31cc62beca1ac6f8190c22f"),
+        (0xff, 0xff, &[0x00; 50],  "1f3079b4", "8c71394d39957725", "a35bb274b567c48b28319f111af34fbd"),
+        (0xff, 0xff, &[0x11; 500], "5c7c8b88", "ea1dee544bb6223b", "3a83b554be408ca5494124ed9d473205"),
+    ];
+    for (k, v, m, t32, t64, t128) in suites.iter() {
+        let key = [*k; 32];
+        let iv = [*v; 25];
+        assert_eq!(&hex::encode(&Zuc256Mac32::oneshot(&key, &iv, m)), t32);
+        assert_eq!(&hex::encode(&Zuc256Mac64::oneshot(&key, &iv, m)), t64);
+        assert_eq!(&hex::encode(&Zuc256Mac128::oneshot(&key, &iv, m)), t128);
+
+        let mut mac = Zuc256Mac128::new(&key, &iv);
+        for chunk in m.chunks(7) {
+            mac.update(chunk);
+        }
+        assert_eq!(&hex::encode(&mac.finalize()), t128);
+    }
+}
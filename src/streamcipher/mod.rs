@@ -28,9 +28,11 @@ pub use crate::blockmode::{
 
 mod rc4;
 mod chacha20;
+mod zuc;
 
 pub use self::rc4::*;
 pub use self::chacha20::*;
+pub use self::zuc::*;
 
 
 #[allow(non_camel_case_types)]
@@ -8,4 +8,377 @@
 // https://github.com/guanzhi/GM-Standards/blob/master/GMT%E6%AD%A3%E5%BC%8F%E6%A0%87%E5%87%86/GMT%200001.2-2012%20%E7%A5%96%E5%86%B2%E4%B9%8B%E5%BA%8F%E5%88%97%E5%AF%86%E7%A0%81%E7%AE%97%E6%B3%95%E7%AC%AC2%E9%83%A8%E5%88%86%EF%BC%9A%E5%9F%BA%E4%BA%8E%E7%A5%96%E5%86%B2%E4%B9%8B%E7%AE%97%E6%B3%95%E7%9A%84%E6%9C%BA%E5%AF%86%E6%80%A7%E7%AE%97%E6%B3%95.pdf
 // 
 // GMT 0001.3-2012 祖冲之序列密码算法第3部分：基于祖冲之算法的完整性算法
-// https://github.com/guanzhi/GM-Standards/blob/master/GMT%E6%AD%A3%E5%BC%8F%E6%A0%87%E5%87%86/GMT%200001.3-2012%20%E7%A5%96%E5%86%B2%E4%B9%8B%E5%BA%8F%E5%88%97%E5%AF%86%E7%A0%81%E7%AE%97%E6%B3%95%E7%AC%AC3%E9%83%A8%E5%88%86%EF%BC%9A%E5%9F%BA%E4%BA%8E%E7%A5%96%E5%86%B2%E4%B9%8B%E7%AE%97%E6%B3%95%E7%9A%84%E5%AE%8C%E6%95%B4%E6%80%A7%E7%AE%97%E6%B3%95.pdf
\ No newline at end of file
+// https://github.com/guanzhi/GM-Standards/blob/master/GMT%E6%AD%A3%E5%BC%8F%E6%A0%87%E5%87%86/GMT%200001.3-2012%20%E7%A5%96%E5%86%B2%E4%B9%8B%E5%BA%8F%E5%88%97%E5%AF%86%E7%A0%81%E7%AE%97%E6%B3%95%E7%AC%AC3%E9%83%A8%E5%88%86%EF%BC%9A%E5%9F%BA%E4%BA%8E%E7%A5%96%E5%86%B2%E4%B9%8B%E7%AE%97%E6%B3%95%E7%9A%84%E5%AE%8C%E6%95%B4%E6%80%A7%E7%AE%97%E6%B3%95.pdf
+//
+// ZUC-256 Stream Cipher and Its Integrity Protection Algorithm
+// http://www.is.cas.cn/ztzl2016/zouchongzhi/201801/W020180126529970733243.pdf
+use crate::mem::Zeroize;
+
+
+const S0: [u8; 256] = [
+    0x3e, 0x72, 0x5b, 0x47, 0xca, 0xe0, 0x00, 0x33, 0x04, 0xd1, 0x54, 0x98, 0x09, 0xb9, 0x6d, 0xcb,
+    0x7b, 0x1b, 0xf9, 0x32, 0xaf, 0x9d, 0x6a, 0xa5, 0xb8, 0x2d, 0xfc, 0x1d, 0x08, 0x53, 0x03, 0x90,
+    0x4d, 0x4e, 0x84, 0x99, 0xe4, 0xce, 0xd9, 0x91, 0xdd, 0xb6, 0x85, 0x48, 0x8b, 0x29, 0x6e, 0xac,
+    0xcd, 0xc1, 0xf8, 0x1e, 0x73, 0x43, 0x69, 0xc6, 0xb5, 0xbd, 0xfd, 0x39, 0x63, 0x20, 0xd4, 0x38,
+    0x76, 0x7d, 0xb2, 0xa7, 0xcf, 0xed, 0x57, 0xc5, 0xf3, 0x2c, 0xbb, 0x14, 0x21, 0x06, 0x55, 0x9b,
+    0xe3, 0xef, 0x5e, 0x31, 0x4f, 0x7f, 0x5a, 0xa4, 0x0d, 0x82, 0x51, 0x49, 0x5f, 0xba, 0x58, 0x1c,
+    0x4a, 0x16, 0xd5, 0x17, 0xa8, 0x92, 0x24, 0x1f, 0x8c, 0xff, 0xd8, 0xae, 0x2e, 0x01, 0xd3, 0xad,
+    0x3b, 0x4b, 0xda, 0x46, 0xeb, 0xc9, 0xde, 0x9a, 0x8f, 0x87, 0xd7, 0x3a, 0x80, 0x6f, 0x2f, 0xc8,
+    0xb1, 0xb4, 0x37, 0xf7, 0x0a, 0x22, 0x13, 0x28, 0x7c, 0xcc, 0x3c, 0x89, 0xc7, 0xc3, 0x96, 0x56,
+    0x07, 0xbf, 0x7e, 0xf0, 0x0b, 0x2b, 0x97, 0x52, 0x35, 0x41, 0x79, 0x61, 0xa6, 0x4c, 0x10, 0xfe,
+    0xbc, 0x26, 0x95, 0x88, 0x8a, 0xb0, 0xa3, 0xfb, 0xc0, 0x18, 0x94, 0xf2, 0xe1, 0xe5, 0xe9, 0x5d,
+    0xd0, 0xdc, 0x11, 0x66, 0x64, 0x5c, 0xec, 0x59, 0x42, 0x75, 0x12, 0xf5, 0x74, 0x9c, 0xaa, 0x23,
+    0x0e, 0x86, 0xab, 0xbe, 0x2a, 0x02, 0xe7, 0x67, 0xe6, 0x44, 0xa2, 0x6c, 0xc2, 0x93, 0x9f, 0xf1,
+    0xf6, 0xfa, 0x36, 0xd2, 0x50, 0x68, 0x9e, 0x62, 0x71, 0x15, 0x3d, 0xd6, 0x40, 0xc4, 0xe2, 0x0f,
+    0x8e, 0x83, 0x77, 0x6b, 0x25, 0x05, 0x3f, 0x0c, 0x30, 0xea, 0x70, 0xb7, 0xa1, 0xe8, 0xa9, 0x65,
+    0x8d, 0x27, 0x1a, 0xdb, 0x81, 0xb3, 0xa0, 0xf4, 0x45, 0x7a, 0x19, 0xdf, 0xee, 0x78, 0x34, 0x60,
+];
+
+const S1: [u8; 256] = [
+    0x55, 0xc2, 0x63, 0x71, 0x3b, 0xc8, 0x47, 0x86, 0x9f, 0x3c, 0xda, 0x5b, 0x29, 0xaa, 0xfd, 0x77,
+    0x8c, 0xc5, 0x94, 0x0c, 0xa6, 0x1a, 0x13, 0x00, 0xe3, 0xa8, 0x16, 0x72, 0x40, 0xf9, 0xf8, 0x42,
+    0x44, 0x26, 0x68, 0x96, 0x81, 0xd9, 0x45, 0x3e, 0x10, 0x76, 0xc6, 0xa7, 0x8b, 0x39, 0x43, 0xe1,
+    0x3a, 0xb5, 0x56, 0x2a, 0xc0, 0x6d, 0xb3, 0x05, 0x22, 0x66, 0xbf, 0xdc, 0x0b, 0xfa, 0x62, 0x48,
+    0xdd, 0x20, 0x11, 0x06, 0x36, 0xc9, 0xc1, 0xcf, 0xf6, 0x27, 0x52, 0xbb, 0x69, 0xf5, 0xd4, 0x87,
+    0x7f, 0x84, 0x4c, 0xd2, 0x9c, 0x57, 0xa4, 0xbc, 0x4f, 0x9a, 0xdf, 0xfe, 0xd6, 0x8d, 0x7a, 0xeb,
+    0x2b, 0x53, 0xd8, 0x5c, 0xa1, 0x14, 0x17, 0xfb, 0x23, 0xd5, 0x7d, 0x30, 0x67, 0x73, 0x08, 0x09,
+    0xee, 0xb7, 0x70, 0x3f, 0x61, 0xb2, 0x19, 0x8e, 0x4e, 0xe5, 0x4b, 0x93, 0x8f, 0x5d, 0xdb, 0xa9,
+    0xad, 0xf1, 0xae, 0x2e, 0xcb, 0x0d, 0xfc, 0xf4, 0x2d, 0x46, 0x6e, 0x1d, 0x97, 0xe8, 0xd1, 0xe9,
+    0x4d, 0x37, 0xa5, 0x75, 0x5e, 0x83, 0x9e, 0xab, 0x82, 0x9d, 0xb9, 0x1c, 0xe0, 0xcd, 0x49, 0x89,
+    0x01, 0xb6, 0xbd, 0x58, 0x24, 0xa2, 0x5f, 0x38, 0x78, 0x99, 0x15, 0x90, 0x50, 0xb8, 0x95, 0xe4,
+    0xd0, 0x91, 0xc7, 0xce, 0xed, 0x0f, 0xb4, 0x6f, 0xa0, 0xcc, 0xf0, 0x02, 0x4a, 0x79, 0xc3, 0xde,
+    0xa3, 0xef, 0xea, 0x51, 0xe6, 0x6b, 0x18, 0xec, 0x1b, 0x2c, 0x80, 0xf7, 0x74, 0xe7, 0xff, 0x21,
+    0x5a, 0x6a, 0x54, 0x1e, 0x41, 0x31, 0x92, 0x35, 0xc4, 0x33, 0x07, 0x0a, 0xba, 0x7e, 0x0e, 0x34,
+    0x88, 0xb1, 0x98, 0x7c, 0xf3, 0x3d, 0x60, 0x6c, 0x7b, 0xca, 0xd3, 0x1f, 0x32, 0x65, 0x04, 0x28,
+    0x64, 0xbe, 0x85, 0x9b, 0x2f, 0x59, 0x8a, 0xd7, 0xb0, 0x25, 0xac, 0xaf, 0x12, 0x03, 0xe2, 0xf2,
+];
+
+// ZUC-128 的常量 D（15 位）
+const EK_D: [u32; 16] = [
+    0x44d7, 0x26bc, 0x626b, 0x135e, 0x5789, 0x35e2, 0x7135, 0x09af,
+    0x4d78, 0x2f13, 0x6bc4, 0x1af1, 0x5e26, 0x3c4d, 0x789a, 0x47ac,
+];
+
+// ZUC-256 的常量 D（7 位），依次用于：加密、32 位 MAC、64 位 MAC、128 位 MAC。
+const EK_D256: [[u8; 16]; 4] = [
+    [0x22, 0x2f, 0x24, 0x2a, 0x6d, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x52, 0x10, 0x30],
+    [0x22, 0x2f, 0x25, 0x2a, 0x6d, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x52, 0x10, 0x30],
+    [0x23, 0x2f, 0x24, 0x2a, 0x6d, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x52, 0x10, 0x30],
+    [0x23, 0x2f, 0x25, 0x2a, 0x6d, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x52, 0x10, 0x30],
+];
+
+const M31: u32 = 0x7fffffff;
+
+
+// a + b mod (2^31 - 1)
+#[inline]
+fn add31(a: u32, b: u32) -> u32 {
+    let c = a + b;
+    (c & M31) + (c >> 31)
+}
+
+// 2^k * a mod (2^31 - 1)
+#[inline]
+fn rot31(a: u32, k: u32) -> u32 {
+    ((a << k) | (a >> (31 - k))) & M31
+}
+
+#[inline]
+fn l1(x: u32) -> u32 {
+    x ^ x.rotate_left(2) ^ x.rotate_left(10) ^ x.rotate_left(18) ^ x.rotate_left(24)
+}
+
+#[inline]
+fn l2(x: u32) -> u32 {
+    x ^ x.rotate_left(8) ^ x.rotate_left(14) ^ x.rotate_left(22) ^ x.rotate_left(30)
+}
+
+#[inline]
+fn sbox(x: u32) -> u32 {
+    let [a, b, c, d] = x.to_be_bytes();
+    u32::from_be_bytes([S0[a as usize], S1[b as usize], S0[c as usize], S1[d as usize]])
+}
+
+#[inline]
+fn make_u31(a: u8, b: u32, c: u8, d: u8) -> u32 {
+    ((a as u32) << 23) | (b << 16) | ((c as u32) << 8) | d as u32
+}
+
+
+// NOTE: ZUC-128 和 ZUC-256 只有 LFSR 的装载方式不同。
+#[derive(Clone)]
+struct ZucState {
+    s: [u32; 16],
+    r1: u32,
+    r2: u32,
+}
+
+impl Zeroize for ZucState {
+    fn zeroize(&mut self) {
+        self.s.zeroize();
+        self.r1.zeroize();
+        self.r2.zeroize();
+    }
+}
+
+impl ZucState {
+    fn init(s: [u32; 16]) -> Self {
+        let mut state = Self { s, r1: 0, r2: 0 };
+
+        for _ in 0..32 {
+            let x = state.bit_reorganization();
+            let w = state.f(&x);
+            state.lfsr_with_init_mode(w >> 1);
+        }
+
+        let x = state.bit_reorganization();
+        state.f(&x);
+        state.lfsr_with_work_mode();
+
+        state
+    }
+
+    #[inline]
+    fn lfsr_next(&self) -> u32 {
+        let s = &self.s;
+        // 2^15 * s15 + 2^17 * s13 + 2^21 * s10 + 2^20 * s4 + (1 + 2^8) * s0 mod (2^31 - 1)
+        let mut v = s[0];
+        v = add31(v, rot31(s[0], 8));
+        v = add31(v, rot31(s[4], 20));
+        v = add31(v, rot31(s[10], 21));
+        v = add31(v, rot31(s[13], 17));
+        v = add31(v, rot31(s[15], 15));
+        v
+    }
+
+    #[inline]
+    fn lfsr_push(&mut self, v: u32) {
+        let v = if v == 0 { M31 } else { v };
+        self.s.copy_within(1.., 0);
+        self.s[15] = v;
+    }
+
+    #[inline]
+    fn lfsr_with_init_mode(&mut self, u: u32) {
+        let v = add31(self.lfsr_next(), u);
+        self.lfsr_push(v);
+    }
+
+    #[inline]
+    fn lfsr_with_work_mode(&mut self) {
+        let v = self.lfsr_next();
+        self.lfsr_push(v);
+    }
+
+    #[inline]
+    fn bit_reorganization(&self) -> [u32; 4] {
+        let s = &self.s;
+        [
+            ((s[15] & 0x7fff8000) << 1) | (s[14] & 0xffff),
+            ((s[11] & 0xffff) << 16) | (s[9] >> 15),
+            ((s[7] & 0xffff) << 16) | (s[5] >> 15),
+            ((s[2] & 0xffff) << 16) | (s[0] >> 15),
+        ]
+    }
+
+    #[inline]
+    fn f(&mut self, x: &[u32; 4]) -> u32 {
+        let w = (x[0] ^ self.r1).wrapping_add(self.r2);
+        let w1 = self.r1.wrapping_add(x[1]);
+        let w2 = self.r2 ^ x[2];
+
+        self.r1 = sbox(l1((w1 << 16) | (w2 >> 16)));
+        self.r2 = sbox(l2((w2 << 16) | (w1 >> 16)));
+
+        w
+    }
+
+    #[inline]
+    fn generate(&mut self) -> u32 {
+        let x = self.bit_reorganization();
+        let z = self.f(&x) ^ x[3];
+        self.lfsr_with_work_mode();
+        z
+    }
+}
+
+
+macro_rules! impl_zuc {
+    ($name:tt, $key_len:tt, $iv_len:tt) => {
+        /// 祖冲之序列密码算法
+        #[derive(Clone)]
+        pub struct $name {
+            state: ZucState,
+            keystream: [u8; 4],
+            offset: usize,
+        }
+
+        impl Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.state.zeroize();
+                self.keystream.zeroize();
+                self.offset = 0;
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.zeroize();
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.debug_struct(stringify!($name)).finish()
+            }
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize = $key_len;
+            pub const IV_LEN: usize  = $iv_len;
+
+
+            /// 生成一个 32 位的密钥字。
+            // NOTE: 不可以和 `encrypt_slice` 交替使用。
+            pub fn generate(&mut self) -> u32 {
+                self.state.generate()
+            }
+
+            #[inline]
+            fn in_place(&mut self, data: &mut [u8]) {
+                for byte in data.iter_mut() {
+                    if self.offset == 0 {
+                        self.keystream = self.state.generate().to_be_bytes();
+                    }
+
+                    *byte ^= self.keystream[self.offset];
+                    self.offset = (self.offset + 1) % 4;
+                }
+            }
+
+            pub fn encrypt_slice(&mut self, plaintext_and_ciphertext: &mut [u8]) {
+                self.in_place(plaintext_and_ciphertext);
+            }
+
+            pub fn decrypt_slice(&mut self, ciphertext_and_plaintext: &mut [u8]) {
+                self.in_place(ciphertext_and_plaintext);
+            }
+        }
+    }
+}
+
+impl_zuc!(Zuc128, 16, 16);
+impl_zuc!(Zuc256, 32, 25);
+
+impl Zuc128 {
+    pub fn new(key: &[u8], iv: &[u8]) -> Self {
+        assert_eq!(key.len(), Self::KEY_LEN);
+        assert_eq!(iv.len(), Self::IV_LEN);
+
+        let mut s = [0u32; 16];
+        for i in 0..16 {
+            s[i] = ((key[i] as u32) << 23) | (EK_D[i] << 8) | iv[i] as u32;
+        }
+
+        Self { state: ZucState::init(s), keystream: [0u8; 4], offset: 0 }
+    }
+}
+
+impl Zuc256 {
+    /// `iv` 的最后 8 个字节只使用低 6 位。
+    pub fn new(key: &[u8], iv: &[u8]) -> Self {
+        Self::with_d(key, iv, &EK_D256[0])
+    }
+
+    /// 用于 MAC 的初始化，`tag_len` 为 4、8 或者 16（In bytes）。
+    pub(crate) fn with_tag_len(key: &[u8], iv: &[u8], tag_len: usize) -> Self {
+        match tag_len {
+             4 => Self::with_d(key, iv, &EK_D256[1]),
+             8 => Self::with_d(key, iv, &EK_D256[2]),
+            16 => Self::with_d(key, iv, &EK_D256[3]),
+            _ => unreachable!(),
+        }
+    }
+
+    fn with_d(key: &[u8], iv: &[u8], d: &[u8; 16]) -> Self {
+        assert_eq!(key.len(), Self::KEY_LEN);
+        assert_eq!(iv.len(), Self::IV_LEN);
+
+        // NOTE: IV 的后 8 个字节（IV17 ~ IV24）只有 6 位。
+        let mut iv6 = [0u8; 25];
+        iv6.copy_from_slice(iv);
+        for b in iv6[17..].iter_mut() {
+            *b &= 0x3f;
+        }
+
+        let k = key;
+        let iv = iv6;
+        let d = |i: usize| d[i] as u32;
+        let v = |i: usize| iv[i] as u32;
+
+        let s = [
+            make_u31(k[ 0], d( 0),         k[21], k[16]),
+            make_u31(k[ 1], d( 1),         k[22], k[17]),
+            make_u31(k[ 2], d( 2),         k[23], k[18]),
+            make_u31(k[ 3], d( 3),         k[24], k[19]),
+            make_u31(k[ 4], d( 4),         k[25], k[20]),
+            make_u31(iv[0], d( 5) | v(17), k[ 5], k[26]),
+            make_u31(iv[1], d( 6) | v(18), k[ 6], k[27]),
+            make_u31(iv[10], d( 7) | v(19), k[ 7], iv[2]),
+            make_u31(k[ 8], d( 8) | v(20), iv[3], iv[11]),
+            make_u31(k[ 9], d( 9) | v(21), iv[12], iv[4]),
+            make_u31(iv[5], d(10) | v(22), k[10], k[28]),
+            make_u31(k[11], d(11) | v(23), iv[6], iv[13]),
+            make_u31(k[12], d(12) | v(24), iv[7], iv[14]),
+            make_u31(k[13], d(13),         iv[15], iv[8]),
+            make_u31(k[14], d(14) | (k[31] >> 4) as u32,   iv[16], iv[9]),
+            make_u31(k[15], d(15) | (k[31] & 0x0f) as u32, k[30], k[29]),
+        ];
+
+        Self { state: ZucState::init(s), keystream: [0u8; 4], offset: 0 }
+    }
+}
+
+
+#[test]
+fn test_zuc128() {
+    // GM/T 0001.1-2012 附录 A 运算实例
+    let suites: &[(&str, &str, [u32; 2])] = &[
+        ("00000000000000000000000000000000", "00000000000000000000000000000000", [0x27bede74, 0x018082da]),
+        ("ffffffffffffffffffffffffffffffff", "ffffffffffffffffffffffffffffffff", [0x0657cfa0, 0x7096398b]),
+        ("3d4c4be96a82fdaeb58f641db17b455b", "84319aa8de6915ca1f6bda6bfbd8c766", [0x14f1c272, 0x3279c419]),
+    ];
+    for (key, iv, z) in suites.iter() {
+        let key = hex::decode(key).unwrap();
+        let iv = hex::decode(iv).unwrap();
+
+        let mut zuc = Zuc128::new(&key, &iv);
+        assert_eq!(zuc.generate(), z[0]);
+        assert_eq!(zuc.generate(), z[1]);
+
+        let mut keystream = [0u8; 8];
+        let mut zuc = Zuc128::new(&key, &iv);
+        zuc.encrypt_slice(&mut keystream[..3]);
+        zuc.encrypt_slice(&mut keystream[3..]);
+        assert_eq!(&keystream[..4], &z[0].to_be_bytes());
+        assert_eq!(&keystream[4..], &z[1].to_be_bytes());
+    }
+}
+
+#[test]
+fn test_zuc256() {
+    // ZUC-256 Stream Cipher and Its Integrity Protection Algorithm, Test Vectors
+    let key = [0u8; 32];
+    let iv = [0u8; 25];
+    let mut zuc = Zuc256::new(&key, &iv);
+    for z in [0x58d03ad6u32, 0x2e032ce2, 0xdafc683a, 0x39bdcb03].iter() {
+        assert_eq!(zuc.generate(), *z);
+    }
+
+    let key = [0xffu8; 32];
+    let iv = [0xffu8; 25];
+    let mut zuc = Zuc256::new(&key, &iv);
+    for z in [0x3356cbaeu32, 0xd1a1c18b, 0x6baa4ffe, 0x343f777c].iter() {
+        assert_eq!(zuc.generate(), *z);
+    }
+}